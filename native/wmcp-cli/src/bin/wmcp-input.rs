@@ -26,12 +26,21 @@ enum Command {
         #[arg(short, long, default_value = "left")]
         button: String,
     },
-    /// Press a virtual key code
+    /// Press a key by virtual key code or name
     Key {
-        /// Virtual key code (hex, e.g. 0x0D for Enter)
-        #[arg(value_parser = parse_hex_or_dec)]
+        /// Virtual key code (hex, e.g. 0x0D) or key name (e.g. enter, f5)
+        #[arg(value_parser = parse_key)]
         vk_code: u16,
     },
+    /// Press a key by hardware scan code (for games that ignore virtual keys)
+    Scancode {
+        /// Scan code (hex, e.g. 0x1E, or decimal)
+        #[arg(value_parser = parse_hex_or_dec)]
+        scan_code: u16,
+        /// E0-prefixed extended key (arrows, right Ctrl/Alt, ...)
+        #[arg(short, long)]
+        extended: bool,
+    },
     /// Move cursor to coordinates
     Move {
         /// X coordinate
@@ -39,11 +48,26 @@ enum Command {
         /// Y coordinate
         y: i32,
     },
+    /// Move cursor by a relative offset (subject to mouse acceleration)
+    Moverel {
+        /// Horizontal offset in pixels
+        #[arg(allow_hyphen_values = true)]
+        dx: i32,
+        /// Vertical offset in pixels
+        #[arg(allow_hyphen_values = true)]
+        dy: i32,
+    },
     /// Send a hotkey combination
     Hotkey {
-        /// Virtual key codes (hex, e.g. 0x11 0x43 for Ctrl+C)
-        #[arg(value_parser = parse_hex_or_dec)]
-        vk_codes: Vec<u16>,
+        /// Key combo (e.g. "ctrl+c") or separate keys/hex VK codes (e.g. 0x11 0x43)
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+    /// Send a SendKeys-style sequence (e.g. "^a{DELETE}Hello{ENTER}")
+    Keys {
+        /// Key sequence: {NAME} keys, +/^/% for Shift/Ctrl/Alt, literal text
+        #[arg(allow_hyphen_values = true)]
+        sequence: String,
     },
 }
 
@@ -55,31 +79,63 @@ fn parse_hex_or_dec(s: &str) -> Result<u16, String> {
     }
 }
 
+/// Resolve a key name first (so `7` means the digit key), then a numeric VK code.
+fn parse_key(s: &str) -> Result<u16, String> {
+    match wmcp_core::input::vk_from_name(s) {
+        Some(vk) => Ok(vk),
+        None => parse_hex_or_dec(s).map_err(|_| format!("unknown key name or VK code: '{s}'")),
+    }
+}
+
 fn main() {
     let args = Args::parse();
 
-    match args.command {
+    if let Err(e) = run(args.command) {
+        eprintln!("wmcp-input: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(command: Command) -> Result<(), wmcp_core::errors::WindowsMcpError> {
+    match command {
         Command::Text { text } => {
-            let count = wmcp_core::input::send_text_raw(&text);
+            let count = wmcp_core::input::send_text_raw(&text)?;
             println!("Sent {count} events for {} chars", text.len());
         }
         Command::Click { x, y, button } => {
-            let count = wmcp_core::input::send_click_raw(x, y, &button);
+            let count = wmcp_core::input::send_click_raw(x, y, &button)?;
             println!("Sent {count} events (click {button} at {x},{y})");
         }
         Command::Key { vk_code } => {
-            wmcp_core::input::send_key_raw(vk_code, false);
-            wmcp_core::input::send_key_raw(vk_code, true);
+            wmcp_core::input::send_key_raw(vk_code, false)?;
+            wmcp_core::input::send_key_raw(vk_code, true)?;
             println!("Sent key 0x{vk_code:04X}");
         }
+        Command::Scancode {
+            scan_code,
+            extended,
+        } => {
+            wmcp_core::input::send_scancode_raw(scan_code, false, extended)?;
+            wmcp_core::input::send_scancode_raw(scan_code, true, extended)?;
+            println!("Sent scan code 0x{scan_code:02X}");
+        }
         Command::Move { x, y } => {
-            let count = wmcp_core::input::send_mouse_move_raw(x, y);
+            let count = wmcp_core::input::send_mouse_move_raw(x, y)?;
             println!("Moved cursor to {x},{y} ({count} events)");
         }
-        Command::Hotkey { vk_codes } => {
-            let count = wmcp_core::input::send_hotkey_raw(&vk_codes);
-            let hex: Vec<String> = vk_codes.iter().map(|v| format!("0x{v:04X}")).collect();
-            println!("Sent hotkey [{}] ({count} events)", hex.join("+"));
+        Command::Moverel { dx, dy } => {
+            let count = wmcp_core::input::send_mouse_move_relative_raw(dx, dy)?;
+            println!("Moved cursor by {dx},{dy} ({count} events)");
+        }
+        Command::Hotkey { keys } => {
+            let combo = keys.join("+");
+            let count = wmcp_core::input::send_hotkey_str_raw(&combo)?;
+            println!("Sent hotkey [{combo}] ({count} events)");
+        }
+        Command::Keys { sequence } => {
+            let count = wmcp_core::input::send_keys_raw(&sequence)?;
+            println!("Sent key sequence ({count} events)");
         }
     }
+    Ok(())
 }
@@ -0,0 +1,95 @@
+//! Standalone CLI tool for querying UIA elements by point or criteria.
+
+use clap::{Parser, Subcommand};
+use wmcp_core::query::FindCriteria;
+
+#[derive(Parser)]
+#[command(name = "wmcp-query", about = "Query UI Automation elements as JSON")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Compact JSON output (no pretty-printing)
+    #[arg(long, global = true)]
+    compact: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the element at screen coordinates
+    Point {
+        /// X coordinate
+        x: i32,
+        /// Y coordinate
+        y: i32,
+    },
+    /// Print elements matching the given criteria
+    Find {
+        /// Substring match on element name (case-insensitive)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Exact control type name (e.g. Button)
+        #[arg(long)]
+        control_type: Option<String>,
+
+        /// Exact AutomationId
+        #[arg(long)]
+        automation_id: Option<String>,
+
+        /// Search within this window handle instead of the whole desktop
+        #[arg(long)]
+        hwnd: Option<isize>,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if let Err(e) = run(args.command, args.compact) {
+        eprintln!("wmcp-query: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(command: Command, compact: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let json = match command {
+        Command::Point { x, y } => {
+            let element = wmcp_core::query::element_from_point(x, y)?;
+            to_json(&element, compact)?
+        }
+        Command::Find {
+            name,
+            control_type,
+            automation_id,
+            hwnd,
+            limit,
+        } => {
+            let criteria = FindCriteria {
+                name,
+                control_type,
+                automation_id,
+                window_handle: hwnd,
+                limit,
+                ..Default::default()
+            };
+            let elements = wmcp_core::query::find_elements(&criteria)?;
+            to_json(&elements, compact)?
+        }
+    };
+
+    println!("{json}");
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T, compact: bool) -> serde_json::Result<String> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
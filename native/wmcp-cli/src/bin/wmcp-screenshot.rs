@@ -1,11 +1,11 @@
 //! Standalone CLI tool for capturing screenshots.
-//!
-//! Placeholder -- DXGI screenshot module will be added in Phase 3.
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use wmcp_core::errors::WindowsMcpError;
+use wmcp_core::screenshot::{self, ScreenshotOptions};
 
 #[derive(Parser)]
-#[command(name = "wmcp-screenshot", about = "Capture screenshot via DXGI (placeholder)")]
+#[command(name = "wmcp-screenshot", about = "Capture a screenshot via DXGI")]
 struct Args {
     /// Output file path
     #[arg(short, long, default_value = "screenshot.png")]
@@ -14,14 +14,69 @@ struct Args {
     /// Monitor index (0 = primary)
     #[arg(long, default_value = "0")]
     monitor: u32,
+
+    /// Image format
+    #[arg(long, value_enum, default_value_t = Format::Png)]
+    format: Format,
+
+    /// Capture only this region, relative to the monitor origin
+    #[arg(long, value_name = "X,Y,W,H", value_parser = parse_region)]
+    region: Option<(i32, i32, u32, u32)>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Png,
+    Jpeg,
+}
+
+/// Parse `x,y,w,h` into a capture region.
+fn parse_region(s: &str) -> Result<(i32, i32, u32, u32), String> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    let [x, y, w, h] = parts[..] else {
+        return Err(format!("expected x,y,w,h but got '{s}'"));
+    };
+    let coord = |v: &str| {
+        v.parse::<i32>()
+            .map_err(|e| format!("invalid coordinate '{v}': {e}"))
+    };
+    let size = |v: &str| {
+        v.parse::<u32>()
+            .map_err(|e| format!("invalid size '{v}': {e}"))
+    };
+    Ok((coord(x)?, coord(y)?, size(w)?, size(h)?))
 }
 
 fn main() {
     let args = Args::parse();
-    eprintln!(
-        "wmcp-screenshot: DXGI capture not yet implemented. \
-         Would save monitor {} to '{}'",
-        args.monitor, args.output
+
+    if let Err(e) = run(&args) {
+        eprintln!("wmcp-screenshot: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), WindowsMcpError> {
+    let options = ScreenshotOptions::default();
+    let frame = match args.region {
+        Some((x, y, w, h)) => screenshot::capture_region_raw(args.monitor, x, y, w, h, &options)?,
+        None => screenshot::capture_frame(args.monitor, &options)?,
+    };
+    let (width, height) = (frame.width, frame.height);
+
+    let bytes = match args.format {
+        Format::Png => screenshot::encode_png(frame)?,
+        Format::Jpeg => screenshot::encode_jpeg(frame, screenshot::DEFAULT_JPEG_QUALITY)?,
+    };
+    std::fs::write(&args.output, &bytes).map_err(|e| {
+        WindowsMcpError::ScreenshotError(format!("Failed to write '{}': {e}", args.output))
+    })?;
+
+    println!(
+        "Saved monitor {} ({width}x{height}, {} bytes) to '{}'",
+        args.monitor,
+        bytes.len(),
+        args.output
     );
-    std::process::exit(1);
+    Ok(())
 }
@@ -1,5 +1,7 @@
 //! Standalone CLI tool for dumping the UIA accessibility tree as JSON.
 
+use std::io::Write;
+
 use clap::Parser;
 
 #[derive(Parser)]
@@ -17,39 +19,103 @@ struct Args {
     #[arg(long, default_value = "50")]
     max_depth: usize,
 
+    /// Skip reading ValuePattern text (faster on large trees)
+    #[arg(long)]
+    no_values: bool,
+
+    /// Keep only nodes of this control type (repeatable, e.g. Button)
+    #[arg(long = "control-type")]
+    control_types: Vec<String>,
+
+    /// Drop unnamed non-control elements (UIA control view)
+    #[arg(long)]
+    controls_only: bool,
+
+    /// Drop offscreen and zero-size elements
+    #[arg(long)]
+    skip_offscreen: bool,
+
+    /// Maximum children read per node (clipped nodes report truncated=true)
+    #[arg(long, default_value_t = wmcp_core::tree::MAX_CHILDREN_PER_NODE)]
+    max_children: usize,
+
+    /// Maximum nodes read in total (cut-off nodes report truncated=true)
+    #[arg(long, default_value_t = wmcp_core::tree::MAX_TOTAL_NODES)]
+    max_total_nodes: usize,
+
+    /// Include toggle/selection state for checkboxes, radio buttons, etc.
+    #[arg(long)]
+    state: bool,
+
+    /// Read only these element properties (repeatable, e.g. name,
+    /// bounding_rect; also help_text, access_key, culture)
+    #[arg(long = "property", value_parser = parse_property)]
+    properties: Vec<wmcp_core::tree::UiaProperty>,
+
+    /// Capture on at most this many threads (default: all cores)
+    #[arg(long)]
+    max_threads: Option<usize>,
+
     /// Compact JSON output (no pretty-printing)
     #[arg(long)]
     compact: bool,
 }
 
-fn get_foreground_hwnd() -> isize {
-    use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
-    let hwnd = unsafe { GetForegroundWindow() };
-    hwnd.0 as isize
+fn parse_property(s: &str) -> Result<wmcp_core::tree::UiaProperty, String> {
+    wmcp_core::tree::UiaProperty::from_name(s).map_err(|e| e.to_string())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
+    // No handles: capture the foreground window (also the --all fallback).
     let handles = if args.all {
         wmcp_core::window::enumerate_visible_windows().unwrap_or_else(|e| {
             eprintln!("Failed to enumerate windows: {e}");
-            vec![get_foreground_hwnd()]
+            Vec::new()
         })
-    } else if args.hwnd.is_empty() {
-        vec![get_foreground_hwnd()]
     } else {
         args.hwnd
     };
 
-    let snapshots = wmcp_core::tree::capture_tree_raw(&handles, args.max_depth);
-
-    let json = if args.compact {
-        serde_json::to_string(&snapshots)?
-    } else {
-        serde_json::to_string_pretty(&snapshots)?
+    let options = wmcp_core::tree::CaptureOptions {
+        include_values: !args.no_values,
+        control_type_filter: (!args.control_types.is_empty()).then_some(args.control_types),
+        controls_only: args.controls_only,
+        skip_offscreen: args.skip_offscreen,
+        max_children: args.max_children,
+        max_total_nodes: args.max_total_nodes,
+        include_state: args.state,
+        max_threads: args.max_threads,
+        properties: if args.properties.is_empty() {
+            wmcp_core::tree::UiaProperty::DEFAULT.to_vec()
+        } else {
+            args.properties
+        },
     };
 
-    println!("{json}");
+    // Stream straight to stdout rather than building the document first.
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    if handles.is_empty() {
+        let snapshots: Vec<_> =
+            wmcp_core::tree::capture_foreground_tree_raw(args.max_depth, &options)
+                .into_iter()
+                .collect();
+        if args.compact {
+            serde_json::to_writer(&mut out, &snapshots)?;
+        } else {
+            serde_json::to_writer_pretty(&mut out, &snapshots)?;
+        }
+    } else {
+        wmcp_core::tree::write_tree_json(
+            &handles,
+            args.max_depth,
+            &options,
+            &mut out,
+            !args.compact,
+        )?;
+    }
+    writeln!(out)?;
+    out.flush()?;
     Ok(())
 }
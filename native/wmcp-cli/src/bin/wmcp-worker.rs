@@ -2,11 +2,40 @@
 //!
 //! Reads line-delimited JSON requests from stdin, dispatches to wmcp_core,
 //! writes JSON responses to stdout.
+//!
+//! # Protocol
+//!
+//! Each line holds one request, `{"id": 1, "method": "...", "params": {...}}`,
+//! and gets one response line, `{"id": 1, "result": ...}` or
+//! `{"id": 1, "error": "..."}`.
+//!
+//! A line may instead hold a JSON array of requests (a batch).  They run
+//! sequentially in array order and the reply is a single line holding a
+//! JSON array of their responses in the same order; a failing request does
+//! not stop the rest.  An empty batch is answered with an error.
+//!
+//! A request with `"notify": true` is dispatched but gets no response, even
+//! on failure -- use it for fire-and-forget input such as `send_key`.  The
+//! `id` may then be omitted.  A batch whose requests are all notifications
+//! gets no reply line at all.
+//!
+//! The `shutdown` method answers `"ok"`, then the worker stops reading and
+//! exits with status 0 once the rest of the current line (if a batch) has
+//! been handled.  Ctrl-C, Ctrl-Break and console close also flush stdout
+//! and exit 0, as does end of input.
+//!
+//! `capture_screenshot` returns `{width, height, format, data}` with the
+//! encoded image (`format` is `"png"` or `"jpeg"`) base64-encoded in `data`.
+//! With `"data_uri": true`, `data` is a `data:image/<format>;base64,...` URI
+//! instead, ready for vision-model APIs.
 
 use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use windows::Win32::Foundation::{BOOL, TRUE};
+use windows::Win32::System::Console::SetConsoleCtrlHandler;
 
 #[derive(Parser)]
 #[command(name = "wmcp-worker", about = "Windows-MCP IPC worker process")]
@@ -16,12 +45,21 @@ struct Args {
     verbose: bool,
 }
 
+/// Set by the `shutdown` method; the read loop exits once the current
+/// line has been answered.
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 #[derive(Deserialize)]
 struct Request {
+    /// Echoed back in the response; may be omitted for notifications.
+    #[serde(default)]
     id: u64,
     method: String,
     #[serde(default)]
     params: serde_json::Value,
+    /// Fire-and-forget: dispatch the request but send no response.
+    #[serde(default)]
+    notify: bool,
 }
 
 #[derive(Serialize)]
@@ -40,6 +78,19 @@ fn json_i32(val: Option<&serde_json::Value>) -> i32 {
         .clamp(i32::MIN as i64, i32::MAX as i64) as i32
 }
 
+/// Extract an optional owned string parameter.
+fn json_string(params: &serde_json::Value, key: &str) -> Option<String> {
+    params.get(key).and_then(|v| v.as_str()).map(str::to_owned)
+}
+
+/// Serialize a UIA pattern result, mapping errors to strings.
+fn pattern_value(
+    result: Result<wmcp_core::pattern::PatternResult, wmcp_core::errors::WindowsMcpError>,
+) -> Result<serde_json::Value, String> {
+    let result = result.map_err(|e| e.to_string())?;
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
 fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Value, String> {
     match method {
         "system_info" => {
@@ -57,7 +108,51 @@ fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Valu
                 .and_then(|v| v.as_u64())
                 .map(|d| (d as usize).min(wmcp_core::tree::MAX_TREE_DEPTH))
                 .unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
-            let snapshots = wmcp_core::tree::capture_tree_raw(&handles, max_depth);
+            let properties = match params.get("properties").and_then(|v| v.as_array()) {
+                Some(names) => names
+                    .iter()
+                    .map(|name| {
+                        wmcp_core::tree::UiaProperty::from_name(name.as_str().unwrap_or_default())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?,
+                None => wmcp_core::tree::UiaProperty::DEFAULT.to_vec(),
+            };
+            let options = wmcp_core::tree::CaptureOptions {
+                include_values: params
+                    .get("include_values")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+                control_type_filter: params
+                    .get("control_types")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok()),
+                controls_only: params
+                    .get("controls_only")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                skip_offscreen: params
+                    .get("skip_offscreen")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                max_children: params
+                    .get("max_children")
+                    .and_then(|v| v.as_u64())
+                    .map_or(wmcp_core::tree::MAX_CHILDREN_PER_NODE, |n| n as usize),
+                max_total_nodes: params
+                    .get("max_total_nodes")
+                    .and_then(|v| v.as_u64())
+                    .map_or(wmcp_core::tree::MAX_TOTAL_NODES, |n| n as usize),
+                include_state: params
+                    .get("include_state")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                max_threads: params
+                    .get("max_threads")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize),
+                properties,
+            };
+            let snapshots = wmcp_core::tree::capture_tree_raw(&handles, max_depth, &options);
             serde_json::to_value(snapshots).map_err(|e| e.to_string())
         }
         "send_text" => {
@@ -65,14 +160,16 @@ fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Valu
                 .get("text")
                 .and_then(|v| v.as_str())
                 .unwrap_or("");
-            let count = wmcp_core::input::send_text_raw(text);
+            let count = wmcp_core::input::send_text_raw(text)
+                .map_err(|e| e.to_string())?;
             Ok(serde_json::Value::from(count))
         }
         "send_click" => {
             let x = json_i32(params.get("x"));
             let y = json_i32(params.get("y"));
             let button = params.get("button").and_then(|v| v.as_str()).unwrap_or("left");
-            let count = wmcp_core::input::send_click_raw(x, y, button);
+            let count = wmcp_core::input::send_click_raw(x, y, button)
+                .map_err(|e| e.to_string())?;
             Ok(serde_json::Value::from(count))
         }
         "send_key" => {
@@ -82,7 +179,8 @@ fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Valu
                 .unwrap_or(0)
                 .min(u16::MAX as u64) as u16;
             let key_up = params.get("key_up").and_then(|v| v.as_bool()).unwrap_or(false);
-            let count = wmcp_core::input::send_key_raw(vk, key_up);
+            let count = wmcp_core::input::send_key_raw(vk, key_up)
+                .map_err(|e| e.to_string())?;
             Ok(serde_json::Value::from(count))
         }
         "send_hotkey" => {
@@ -90,7 +188,8 @@ fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Valu
                 .get("vk_codes")
                 .and_then(|v| serde_json::from_value(v.clone()).ok())
                 .unwrap_or_default();
-            let count = wmcp_core::input::send_hotkey_raw(&vk_codes);
+            let count = wmcp_core::input::send_hotkey_raw(&vk_codes)
+                .map_err(|e| e.to_string())?;
             Ok(serde_json::Value::from(count))
         }
         "enumerate_windows" => {
@@ -116,16 +215,229 @@ fn dispatch(method: &str, params: &serde_json::Value) -> Result<serde_json::Valu
                 .map_err(|e| e.to_string())?;
             serde_json::to_value(info).map_err(|e| e.to_string())
         }
+        "element_from_point" => {
+            let x = json_i32(params.get("x"));
+            let y = json_i32(params.get("y"));
+            let info = wmcp_core::query::element_from_point(x, y)
+                .map_err(|e| e.to_string())?;
+            serde_json::to_value(info).map_err(|e| e.to_string())
+        }
+        "find_elements" => {
+            let scope = match params.get("scope").and_then(|v| v.as_str()) {
+                Some(name) => {
+                    wmcp_core::query::FindScope::from_name(name).map_err(|e| e.to_string())?
+                }
+                None => wmcp_core::query::FindScope::default(),
+            };
+            let criteria = wmcp_core::query::FindCriteria {
+                name: json_string(params, "name"),
+                name_regex: json_string(params, "name_regex"),
+                control_type: json_string(params, "control_type"),
+                automation_id: json_string(params, "automation_id"),
+                class_name: json_string(params, "class_name"),
+                substring: params
+                    .get("substring")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                window_handle: params
+                    .get("window_handle")
+                    .and_then(|v| v.as_i64())
+                    .map(|h| h as isize),
+                scope,
+                limit: params
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map_or(20, |n| n as usize),
+            };
+            let elements = wmcp_core::query::find_elements(&criteria)
+                .map_err(|e| e.to_string())?;
+            serde_json::to_value(elements).map_err(|e| e.to_string())
+        }
+        "invoke_at" => {
+            let x = json_i32(params.get("x"));
+            let y = json_i32(params.get("y"));
+            pattern_value(wmcp_core::pattern::invoke_at(x, y))
+        }
+        "toggle_at" => {
+            let x = json_i32(params.get("x"));
+            let y = json_i32(params.get("y"));
+            pattern_value(wmcp_core::pattern::toggle_at(x, y))
+        }
+        "set_value_at" => {
+            let x = json_i32(params.get("x"));
+            let y = json_i32(params.get("y"));
+            let value = params.get("value").and_then(|v| v.as_str()).unwrap_or("");
+            pattern_value(wmcp_core::pattern::set_value_at(x, y, value))
+        }
+        "capture_screenshot" => {
+            use base64::Engine as _;
+
+            let monitor_index = params
+                .get("monitor_index")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+                .min(u32::MAX as u64) as u32;
+            let options = wmcp_core::screenshot::ScreenshotOptions {
+                max_dimension: params
+                    .get("max_dimension")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n.min(u32::MAX as u64) as u32),
+                include_cursor: params
+                    .get("include_cursor")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                ..Default::default()
+            };
+            let frame = wmcp_core::screenshot::capture_frame(monitor_index, &options)
+                .map_err(|e| e.to_string())?;
+            let (width, height) = (frame.width, frame.height);
+
+            let format = params.get("format").and_then(|v| v.as_str()).unwrap_or("png");
+            let bytes = match format {
+                "png" => wmcp_core::screenshot::encode_png(frame),
+                "jpeg" => {
+                    let quality = params
+                        .get("quality")
+                        .and_then(|v| v.as_u64())
+                        .map_or(wmcp_core::screenshot::DEFAULT_JPEG_QUALITY, |q| {
+                            q.min(100) as u8
+                        });
+                    wmcp_core::screenshot::encode_jpeg(frame, quality)
+                }
+                _ => return Err(format!("unknown screenshot format: {format}")),
+            }
+            .map_err(|e| e.to_string())?;
+
+            let data_uri = params
+                .get("data_uri")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let data = if data_uri {
+                wmcp_core::screenshot::to_data_uri(&format!("image/{format}"), &bytes)
+            } else {
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            };
+
+            Ok(serde_json::json!({
+                "width": width,
+                "height": height,
+                "format": format,
+                "data": data,
+            }))
+        }
         "ping" => Ok(serde_json::Value::String("pong".to_owned())),
+        "shutdown" => {
+            SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+            Ok(serde_json::Value::String("ok".to_owned()))
+        }
         _ => Err(format!("unknown method: {method}")),
     }
 }
 
+/// Parse and dispatch one request object.
+///
+/// Returns `None` for a successfully parsed `notify` request, which gets
+/// no response.
+fn handle_request(value: serde_json::Value) -> Option<Response> {
+    let req: Request = match serde_json::from_value(value) {
+        Ok(r) => r,
+        Err(e) => {
+            // Invalid request -- use id=0 since we can't extract it.
+            return Some(Response {
+                id: 0,
+                result: None,
+                error: Some(format!("invalid request: {e}")),
+            });
+        }
+    };
+
+    let outcome = dispatch(&req.method, &req.params);
+    if req.notify {
+        return None;
+    }
+    Some(match outcome {
+        Ok(result) => Response {
+            id: req.id,
+            result: Some(result),
+            error: None,
+        },
+        Err(error) => Response {
+            id: req.id,
+            result: None,
+            error: Some(error),
+        },
+    })
+}
+
+/// Handle one input line: a request object or a batch array.
+///
+/// Returns the serialized response line, or `None` when nothing should be
+/// written (a notification, or a batch made only of notifications).
+fn handle_line(line: &str) -> Option<String> {
+    let value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => {
+            // Parse error -- use id=0 since we can't extract it.
+            return Some(serialize_response(&Response {
+                id: 0,
+                result: None,
+                error: Some(format!("invalid JSON: {e}")),
+            }));
+        }
+    };
+
+    match value {
+        serde_json::Value::Array(requests) if requests.is_empty() => {
+            Some(serialize_response(&Response {
+                id: 0,
+                result: None,
+                error: Some("empty batch".to_owned()),
+            }))
+        }
+        serde_json::Value::Array(requests) => {
+            let responses: Vec<Response> =
+                requests.into_iter().filter_map(handle_request).collect();
+            if responses.is_empty() {
+                return None;
+            }
+            Some(serde_json::to_string(&responses).unwrap_or_else(|_| {
+                // Serialization failed -- send minimal error response.
+                r#"[{"id":0,"error":"response serialization failed"}]"#.to_owned()
+            }))
+        }
+        request => handle_request(request).map(|resp| serialize_response(&resp)),
+    }
+}
+
+fn serialize_response(resp: &Response) -> String {
+    serde_json::to_string(resp).unwrap_or_else(|_| {
+        // Serialization failed -- send minimal error response.
+        format!(r#"{{"id":{},"error":"response serialization failed"}}"#, resp.id)
+    })
+}
+
+/// Console control handler: flush any buffered output and exit cleanly on
+/// Ctrl-C, Ctrl-Break or console close.
+///
+/// Runs on a thread the system creates.  Taking the stdout lock waits for
+/// a response line that is mid-write, so the parent never reads a
+/// truncated line.
+unsafe extern "system" fn console_ctrl_handler(_ctrl_type: u32) -> BOOL {
+    let _ = io::stdout().lock().flush();
+    std::process::exit(0);
+}
+
 fn main() {
     let args = Args::parse();
     let stdin = io::stdin();
     let mut stdout = io::stdout();
 
+    if let Err(e) = unsafe { SetConsoleCtrlHandler(Some(console_ctrl_handler), TRUE) } {
+        if args.verbose {
+            eprintln!("wmcp-worker: SetConsoleCtrlHandler failed: {e}");
+        }
+    }
+
     if args.verbose {
         eprintln!("wmcp-worker: ready");
     }
@@ -145,46 +457,18 @@ fn main() {
             continue;
         }
 
-        let req: Request = match serde_json::from_str(&line) {
-            Ok(r) => r,
-            Err(e) => {
-                // Parse error -- use id=0 since we can't extract it.
-                let resp = Response {
-                    id: 0,
-                    result: None,
-                    error: Some(format!("invalid JSON: {e}")),
-                };
-                if let Ok(json) = serde_json::to_string(&resp) {
-                    let _ = writeln!(stdout, "{json}");
-                    let _ = stdout.flush();
-                }
-                continue;
-            }
-        };
-
-        let resp = match dispatch(&req.method, &req.params) {
-            Ok(result) => Response {
-                id: req.id,
-                result: Some(result),
-                error: None,
-            },
-            Err(error) => Response {
-                id: req.id,
-                result: None,
-                error: Some(error),
-            },
-        };
-
-        if let Ok(json) = serde_json::to_string(&resp) {
+        if let Some(json) = handle_line(&line) {
             let _ = writeln!(stdout, "{json}");
-        } else {
-            // Serialization failed -- send minimal error response.
-            let _ = writeln!(
-                stdout,
-                r#"{{"id":{},"error":"response serialization failed"}}"#,
-                req.id
-            );
-        }
-        let _ = stdout.flush();
+            let _ = stdout.flush();
+        }
+
+        if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+            if args.verbose {
+                eprintln!("wmcp-worker: shutdown requested");
+            }
+            break;
+        }
     }
+
+    let _ = stdout.flush();
 }
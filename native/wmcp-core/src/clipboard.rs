@@ -0,0 +1,321 @@
+//! Clipboard access via the Win32 clipboard API: text and images.
+//!
+//! The clipboard is a process-wide shared resource that only one window can
+//! hold open at a time, so `OpenClipboard` fails transiently whenever
+//! another application (clipboard managers, remote-desktop sync) is
+//! touching it.  [`ClipboardGuard`] retries the open and closes it on drop.
+//!
+//! Data handed to `SetClipboardData` must live in a movable global memory
+//! block; ownership passes to the system on success.
+//!
+//! Writes open the clipboard with a temporary message-only owner window:
+//! with a NULL owner, `EmptyClipboard` leaves the clipboard ownerless and
+//! `SetClipboardData` is documented to fail.
+
+use windows::core::w;
+use windows::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL, HWND};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+    SetClipboardData,
+};
+use windows::Win32::System::Memory::{
+    GlobalAlloc, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+};
+use windows::Win32::System::Ole::{CF_DIB, CF_UNICODETEXT};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DestroyWindow, HWND_MESSAGE, WINDOW_EX_STYLE, WINDOW_STYLE,
+};
+
+use crate::errors::WindowsMcpError;
+use crate::screenshot::ScreenshotData;
+
+/// Number of `OpenClipboard` attempts before giving up.
+const OPEN_ATTEMPTS: u32 = 10;
+
+/// Delay between `OpenClipboard` attempts.
+const OPEN_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(20);
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// RAII wrapper that holds the clipboard open and closes it on `Drop`.
+struct ClipboardGuard;
+
+impl ClipboardGuard {
+    /// Open the clipboard for `owner` (null to read only), retrying while
+    /// another process holds it.
+    fn open(owner: HWND) -> Result<Self, WindowsMcpError> {
+        let mut last_error = None;
+        for attempt in 0..OPEN_ATTEMPTS {
+            if attempt > 0 {
+                std::thread::sleep(OPEN_RETRY_DELAY);
+            }
+            match unsafe { OpenClipboard(owner) } {
+                Ok(()) => return Ok(Self),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(WindowsMcpError::ClipboardError(format!(
+            "OpenClipboard failed after {OPEN_ATTEMPTS} attempts: {}",
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+}
+
+impl Drop for ClipboardGuard {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe { CloseClipboard() } {
+            log::warn!("CloseClipboard failed: {e}");
+        }
+    }
+}
+
+/// Hidden message-only window that owns the clipboard while it is written,
+/// destroyed on `Drop`.
+///
+/// Must outlive the [`ClipboardGuard`] opened with it.  Data already set
+/// stays on the clipboard after the owner is destroyed.
+struct ClipboardOwner(HWND);
+
+impl ClipboardOwner {
+    fn create() -> Result<Self, WindowsMcpError> {
+        let hwnd = unsafe {
+            CreateWindowExW(
+                WINDOW_EX_STYLE(0),
+                w!("STATIC"),
+                w!(""),
+                WINDOW_STYLE(0),
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                None,
+                None,
+                None,
+            )
+        }
+        .map_err(|e| {
+            WindowsMcpError::ClipboardError(format!(
+                "CreateWindowExW (clipboard owner) failed: {e}"
+            ))
+        })?;
+        Ok(Self(hwnd))
+    }
+}
+
+impl Drop for ClipboardOwner {
+    fn drop(&mut self) {
+        let _ = unsafe { DestroyWindow(self.0) };
+    }
+}
+
+/// Decode UTF-16 up to the first NUL (or the end of `units`).
+fn utf16_until_nul(units: &[u16]) -> String {
+    let len = units.iter().position(|&c| c == 0).unwrap_or(units.len());
+    String::from_utf16_lossy(&units[..len])
+}
+
+/// Encode `text` as NUL-terminated UTF-16, as `CF_UNICODETEXT` requires.
+fn to_utf16_nul(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Size of a `BITMAPINFOHEADER` in bytes.
+const BITMAPINFOHEADER_SIZE: usize = 40;
+
+/// Encode a BGRA frame as a packed `CF_DIB`: a `BITMAPINFOHEADER` followed
+/// by 32bpp `BI_RGB` pixels.
+///
+/// Rows are flipped to the bottom-up order most consumers expect (some
+/// ignore negative-height top-down DIBs).  With `BI_RGB` the fourth byte is
+/// nominally unused, but alpha-aware apps such as Word read it as alpha, so
+/// it is forced to 255: the image pastes opaque everywhere, matching Paint.
+fn dib_from_bgra(frame: &ScreenshotData) -> Result<Vec<u8>, WindowsMcpError> {
+    let row_bytes = frame.width as usize * 4;
+    let image_bytes = row_bytes * frame.height as usize;
+    if frame.data.len() != image_bytes {
+        return Err(WindowsMcpError::ClipboardError(format!(
+            "Image data is {} bytes; expected {image_bytes} for {}x{} BGRA",
+            frame.data.len(),
+            frame.width,
+            frame.height
+        )));
+    }
+    let (Ok(width), Ok(height), Ok(size_image)) = (
+        i32::try_from(frame.width),
+        i32::try_from(frame.height),
+        u32::try_from(image_bytes),
+    ) else {
+        return Err(WindowsMcpError::ClipboardError(format!(
+            "Image {}x{} is too large for a DIB",
+            frame.width, frame.height
+        )));
+    };
+
+    let mut dib = Vec::with_capacity(BITMAPINFOHEADER_SIZE + image_bytes);
+    dib.extend_from_slice(&(BITMAPINFOHEADER_SIZE as u32).to_le_bytes()); // biSize
+    dib.extend_from_slice(&width.to_le_bytes()); // biWidth
+    dib.extend_from_slice(&height.to_le_bytes()); // biHeight (> 0: bottom-up)
+    dib.extend_from_slice(&1u16.to_le_bytes()); // biPlanes
+    dib.extend_from_slice(&32u16.to_le_bytes()); // biBitCount
+    dib.extend_from_slice(&0u32.to_le_bytes()); // biCompression = BI_RGB
+    dib.extend_from_slice(&size_image.to_le_bytes()); // biSizeImage
+    dib.extend_from_slice(&[0; 16]); // biXPelsPerMeter .. biClrImportant
+
+    if row_bytes > 0 {
+        for row in frame.data.chunks_exact(row_bytes).rev() {
+            dib.extend(
+                row.chunks_exact(4)
+                    .flat_map(|px| [px[0], px[1], px[2], 255]),
+            );
+        }
+    }
+    Ok(dib)
+}
+
+/// Replace the clipboard contents with `bytes` in `format`.
+///
+/// Copies `bytes` into a movable global block, empties the clipboard and
+/// hands the block to the system.  The clipboard must not already be open
+/// on this thread.
+pub(crate) fn set_clipboard_bytes(format: u32, bytes: &[u8]) -> Result<(), WindowsMcpError> {
+    let hmem = unsafe { GlobalAlloc(GMEM_MOVEABLE, bytes.len()) }
+        .map_err(|e| WindowsMcpError::ClipboardError(format!("GlobalAlloc failed: {e}")))?;
+
+    let ptr = unsafe { GlobalLock(hmem) } as *mut u8;
+    if ptr.is_null() {
+        let _ = unsafe { GlobalFree(hmem) };
+        return Err(WindowsMcpError::ClipboardError("GlobalLock failed".into()));
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        // Returns an "error" once the lock count reaches zero; not a failure.
+        let _ = GlobalUnlock(hmem);
+    }
+
+    let result = ClipboardOwner::create().and_then(|owner| {
+        // Dropped (closing the clipboard) before `owner` is destroyed.
+        let _guard = ClipboardGuard::open(owner.0)?;
+        unsafe { EmptyClipboard() }
+            .map_err(|e| WindowsMcpError::ClipboardError(format!("EmptyClipboard failed: {e}")))?;
+        unsafe { SetClipboardData(format, HANDLE(hmem.0)) }
+            .map_err(|e| WindowsMcpError::ClipboardError(format!("SetClipboardData failed: {e}")))
+    });
+
+    // On success the system owns the block; otherwise it is still ours.
+    if result.is_err() {
+        let _ = unsafe { GlobalFree(hmem) };
+    }
+    result.map(|_| ())
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// Read the clipboard as text.
+///
+/// Returns an empty string when the clipboard is empty or holds no text
+/// (e.g. only an image).
+pub fn get_clipboard_text() -> Result<String, WindowsMcpError> {
+    let _guard = ClipboardGuard::open(HWND::default())?;
+
+    if unsafe { IsClipboardFormatAvailable(u32::from(CF_UNICODETEXT.0)) }.is_err() {
+        return Ok(String::new());
+    }
+    let handle = unsafe { GetClipboardData(u32::from(CF_UNICODETEXT.0)) }
+        .map_err(|e| WindowsMcpError::ClipboardError(format!("GetClipboardData failed: {e}")))?;
+    let hmem = HGLOBAL(handle.0);
+
+    let ptr = unsafe { GlobalLock(hmem) } as *const u16;
+    if ptr.is_null() {
+        return Err(WindowsMcpError::ClipboardError("GlobalLock failed".into()));
+    }
+    // The block may be larger than the text; stop at the terminator.
+    let units = unsafe { GlobalSize(hmem) } / std::mem::size_of::<u16>();
+    let text = utf16_until_nul(unsafe { std::slice::from_raw_parts(ptr, units) });
+    let _ = unsafe { GlobalUnlock(hmem) };
+
+    Ok(text)
+}
+
+/// Replace the clipboard contents with `text`.
+pub fn set_clipboard_text(text: &str) -> Result<(), WindowsMcpError> {
+    let units = to_utf16_nul(text);
+    let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_ne_bytes()).collect();
+    set_clipboard_bytes(u32::from(CF_UNICODETEXT.0), &bytes)
+}
+
+/// Replace the clipboard contents with a BGRA image, e.g. a screenshot.
+///
+/// Written as `CF_DIB`; Windows synthesizes `CF_DIBV5` and `CF_BITMAP` for
+/// apps that ask for those.  The image is pasted fully opaque (see
+/// [`dib_from_bgra`]).  Returns an error if `data.data` does not hold
+/// exactly `width * height * 4` bytes.
+pub fn set_clipboard_image(data: &ScreenshotData) -> Result<(), WindowsMcpError> {
+    let dib = dib_from_bgra(data)?;
+    set_clipboard_bytes(u32::from(CF_DIB.0), &dib)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_round_trip() {
+        let units = to_utf16_nul("héllo ✓");
+        assert_eq!(units.last(), Some(&0));
+        assert_eq!(utf16_until_nul(&units), "héllo ✓");
+    }
+
+    #[test]
+    fn test_utf16_until_nul_ignores_trailing_block_bytes() {
+        assert_eq!(utf16_until_nul(&[0x61, 0x62, 0, 0x63, 0x64]), "ab");
+        assert_eq!(utf16_until_nul(&[0x61, 0x62]), "ab");
+        assert_eq!(utf16_until_nul(&[]), "");
+    }
+
+    #[test]
+    fn test_dib_from_bgra_header_and_row_order() {
+        // 1x2 image: top pixel blue with alpha 0, bottom pixel red.
+        let frame = ScreenshotData {
+            width: 1,
+            height: 2,
+            data: vec![255, 0, 0, 0, 0, 0, 255, 255],
+        };
+        let dib = dib_from_bgra(&frame).unwrap();
+        assert_eq!(dib.len(), BITMAPINFOHEADER_SIZE + 8);
+        assert_eq!(&dib[0..4], &40u32.to_le_bytes());
+        assert_eq!(&dib[4..8], &1i32.to_le_bytes());
+        assert_eq!(&dib[8..12], &2i32.to_le_bytes());
+        assert_eq!(&dib[14..16], &32u16.to_le_bytes());
+        assert_eq!(&dib[20..24], &8u32.to_le_bytes());
+        // Bottom-up: the red row comes first; alpha is forced opaque.
+        assert_eq!(&dib[40..44], &[0, 0, 255, 255]);
+        assert_eq!(&dib[44..48], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_dib_from_bgra_rejects_wrong_length() {
+        let frame = ScreenshotData {
+            width: 2,
+            height: 2,
+            data: vec![0; 12],
+        };
+        assert!(dib_from_bgra(&frame).is_err());
+    }
+
+    /// Overwrites the user's clipboard; run manually on a desktop session.
+    #[test]
+    #[ignore]
+    fn test_clipboard_text_round_trip() {
+        set_clipboard_text("wmcp clipboard test").unwrap();
+        assert_eq!(get_clipboard_text().unwrap(), "wmcp clipboard test");
+    }
+}
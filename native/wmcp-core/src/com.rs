@@ -9,7 +9,9 @@
 
 use crate::errors::WindowsMcpError;
 use log;
-use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+use windows::Win32::System::Com::{
+    CoInitializeEx, CoUninitialize, COINIT, COINIT_APARTMENTTHREADED, COINIT_MULTITHREADED,
+};
 
 /// RAII wrapper that calls `CoUninitialize` on `Drop` when appropriate.
 ///
@@ -29,21 +31,45 @@ impl COMGuard {
     /// `RPC_E_CHANGED_MODE` (thread has STA; COM is usable but we must
     /// NOT call `CoUninitialize` since we did not successfully initialise).
     pub fn init() -> Result<Self, WindowsMcpError> {
-        let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        Self::init_with(COINIT_MULTITHREADED, "MTA", "STA")
+    }
+
+    /// Initialise (or join) a single-threaded (STA) COM apartment.
+    ///
+    /// For APIs that require STA, such as some shell and drag-and-drop
+    /// interfaces and legacy single-threaded controls.  Results are handled
+    /// as in [`COMGuard::init`], with the roles reversed: if the thread is
+    /// already MTA, the existing apartment is used and a warning is logged.
+    pub fn init_sta() -> Result<Self, WindowsMcpError> {
+        Self::init_with(COINIT_APARTMENTTHREADED, "STA", "MTA")
+    }
+
+    /// Shared body of [`COMGuard::init`] and [`COMGuard::init_sta`];
+    /// `requested` and `other` name the apartment kinds for diagnostics.
+    fn init_with(coinit: COINIT, requested: &str, other: &str) -> Result<Self, WindowsMcpError> {
+        let hr = unsafe { CoInitializeEx(None, coinit) };
 
         let hresult_value = hr.0 as u32;
         match hresult_value {
             // S_OK (newly initialised) or S_FALSE (already initialised).
-            0x0 | 0x1 => Ok(Self {
-                should_uninit: true,
-                _not_send: std::marker::PhantomData,
-            }),
-            // RPC_E_CHANGED_MODE -- thread already has STA.  COM is usable
-            // but we requested MTA, so log a warning for diagnostics.
+            0x0 | 0x1 => {
+                let how = if hresult_value == 0 {
+                    "initialised"
+                } else {
+                    "joined"
+                };
+                log::trace!("CoInitializeEx: {how} {requested} apartment");
+                Ok(Self {
+                    should_uninit: true,
+                    _not_send: std::marker::PhantomData,
+                })
+            }
+            // RPC_E_CHANGED_MODE -- thread already has the other apartment
+            // kind.  COM is usable, so log a warning for diagnostics.
             0x8001_0106 => {
                 log::warn!(
-                    "CoInitializeEx: RPC_E_CHANGED_MODE -- thread already has STA apartment, \
-                     using existing apartment instead of MTA"
+                    "CoInitializeEx: RPC_E_CHANGED_MODE -- thread already has {other} apartment, \
+                     using existing apartment instead of {requested}"
                 );
                 Ok(Self {
                     should_uninit: false,
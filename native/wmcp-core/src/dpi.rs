@@ -0,0 +1,34 @@
+//! Process DPI awareness.
+//!
+//! UIA reports `bounding_rect` in physical pixels, but a DPI-unaware
+//! process sees a virtualised, scaled desktop: `GetSystemMetrics`,
+//! `GetCursorPos` and absolute `SendInput` coordinates are all in logical
+//! units of the primary monitor's scale.  On mixed-DPI multi-monitor
+//! setups the two disagree on every monitor whose scale differs from the
+//! primary, so clicks computed from element rects land off-target.
+//!
+//! [`ensure_per_monitor_aware`] opts the process into per-monitor-v2
+//! awareness, after which every API above speaks physical pixels.  Entry
+//! points that read or produce screen coordinates call it, so consumers
+//! need not.
+
+use windows::Win32::UI::HiDpi::{
+    SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+};
+
+/// Declare per-monitor-v2 DPI awareness for the process, once.
+///
+/// Awareness is process-wide and can only be set once.  If the host
+/// already chose a mode -- an application manifest, or another library
+/// calling first -- that mode is kept and the failure is logged at debug
+/// level; coordinates then follow whatever the host chose.
+pub fn ensure_per_monitor_aware() {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    ONCE.call_once(|| {
+        let result =
+            unsafe { SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) };
+        if let Err(e) = result {
+            log::debug!("SetProcessDpiAwarenessContext(PER_MONITOR_AWARE_V2) failed: {e}");
+        }
+    });
+}
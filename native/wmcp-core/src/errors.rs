@@ -2,7 +2,9 @@
 //!
 //! All Rust-side failures are funnelled through [`WindowsMcpError`], which
 //! uses `thiserror` for `Display` and `Error` derives.  PyO3 conversion
-//! is handled in the `wmcp-pyo3` crate, keeping this crate PyO3-free.
+//! is handled in the `wmcp-pyo3` crate, keeping this crate PyO3-free:
+//! each variant is raised as a matching `WmcpError` subclass (e.g.
+//! `WmcpTreeError`) with the same `Display` message.
 
 use thiserror::Error;
 use windows::core::Error as WindowsError;
@@ -31,6 +33,14 @@ pub enum WindowsMcpError {
     /// Screenshot capture failure (GDI / DXGI).
     #[error("ScreenshotError: {0}")]
     ScreenshotError(String),
+
+    /// Clipboard access failure (open / read / write).
+    #[error("ClipboardError: {0}")]
+    ClipboardError(String),
+
+    /// Logger setup failure (unknown level, or another logger installed).
+    #[error("LoggingError: {0}")]
+    LoggingError(String),
 }
 
 /// Convert a `windows::core::Error` (COM / Win32 HRESULT failure) into a
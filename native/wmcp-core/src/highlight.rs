@@ -0,0 +1,226 @@
+//! Temporary on-screen highlight rectangles for debugging automation.
+//!
+//! [`highlight_rect`] outlines a screen rectangle with a coloured border so
+//! a developer can see which element an agent resolved (e.g. the `rect` of
+//! a `find_elements` result).  The border is a topmost, click-through popup
+//! window whose region is cut down to a frame, so the content underneath
+//! stays visible and usable.
+//!
+//! The call blocks for the whole duration: the window belongs to the
+//! calling thread, which pumps its messages until the highlight expires.
+
+use std::cell::Cell;
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, RECT, TRUE, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    CombineRgn, CreateRectRgn, CreateSolidBrush, DeleteObject, FillRect, SetWindowRgn, HBRUSH,
+    HDC, RGN_DIFF,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetClientRect,
+    PeekMessageW, RegisterClassW, SetLayeredWindowAttributes, ShowWindow, TranslateMessage,
+    HMENU, LWA_ALPHA, MSG, PM_REMOVE, SW_SHOWNOACTIVATE, WM_ERASEBKGND, WNDCLASSW,
+    WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT,
+    WS_POPUP,
+};
+
+use crate::errors::WindowsMcpError;
+
+/// Default border colour (red) as `0xRRGGBB`.
+pub const DEFAULT_HIGHLIGHT_COLOR: u32 = 0xFF0000;
+
+/// Border thickness in pixels.
+const BORDER_WIDTH: i32 = 3;
+
+/// Longest highlight accepted, so a bad argument cannot block a thread
+/// indefinitely.
+const MAX_HIGHLIGHT_MS: u64 = 60_000;
+
+/// How often the message loop wakes while waiting for the highlight to end.
+const PUMP_INTERVAL: Duration = Duration::from_millis(15);
+
+const CLASS_NAME: PCWSTR = w!("WmcpHighlightFrame");
+
+static REGISTER_CLASS: Once = Once::new();
+
+thread_local! {
+    /// Brush the highlight window proc paints with on this thread.
+    static FRAME_BRUSH: Cell<HBRUSH> = Cell::new(HBRUSH::default());
+}
+
+/// Convert `0xRRGGBB` to a GDI `COLORREF` value (`0x00BBGGRR`).
+fn rgb_to_colorref(rgb: u32) -> u32 {
+    let (r, g, b) = ((rgb >> 16) & 0xFF, (rgb >> 8) & 0xFF, rgb & 0xFF);
+    r | (g << 8) | (b << 16)
+}
+
+/// Width and height of `[left, top, right, bottom]`, or an `InputError` if
+/// the rect is empty or too large for an `i32` size.
+fn rect_size(rect: [i32; 4]) -> Result<(i32, i32), WindowsMcpError> {
+    let [left, top, right, bottom] = rect;
+    match (right.checked_sub(left), bottom.checked_sub(top)) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(WindowsMcpError::InputError(format!(
+            "Invalid highlight rect [{left}, {top}, {right}, {bottom}]"
+        ))),
+    }
+}
+
+unsafe extern "system" fn frame_wndproc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg == WM_ERASEBKGND {
+        let mut rc = RECT::default();
+        if GetClientRect(hwnd, &mut rc).is_ok() {
+            FillRect(HDC(wparam.0 as *mut core::ffi::c_void), &rc, FRAME_BRUSH.with(Cell::get));
+        }
+        return LRESULT(1);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Dispatch this thread's messages until `deadline`.
+fn pump_until(deadline: Instant) {
+    let mut msg = MSG::default();
+    loop {
+        unsafe {
+            while PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        std::thread::sleep(PUMP_INTERVAL.min(deadline - now));
+    }
+}
+
+/// Create the frame window at the given screen position, show it until
+/// `deadline`, then destroy it.
+unsafe fn show_frame(
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    deadline: Instant,
+) -> Result<(), WindowsMcpError> {
+    let hwnd = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+        CLASS_NAME,
+        w!(""),
+        WS_POPUP,
+        left,
+        top,
+        width,
+        height,
+        HWND::default(),
+        HMENU::default(),
+        HINSTANCE::default(),
+        None,
+    )
+    .map_err(|e| WindowsMcpError::ComError(format!("CreateWindowExW (highlight) failed: {e}")))?;
+
+    // Opaque, but layered + transparent so mouse input passes through.
+    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA);
+
+    // Cut the window down to a frame unless the rect is too small to
+    // leave an inside; the system owns the region once it is set.
+    if width > 2 * BORDER_WIDTH && height > 2 * BORDER_WIDTH {
+        let outer = CreateRectRgn(0, 0, width, height);
+        let inner = CreateRectRgn(
+            BORDER_WIDTH,
+            BORDER_WIDTH,
+            width - BORDER_WIDTH,
+            height - BORDER_WIDTH,
+        );
+        CombineRgn(outer, outer, inner, RGN_DIFF);
+        let _ = DeleteObject(inner);
+        if SetWindowRgn(hwnd, outer, TRUE) == 0 {
+            let _ = DeleteObject(outer);
+        }
+    }
+
+    let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+    pump_until(deadline);
+    let _ = DestroyWindow(hwnd);
+    Ok(())
+}
+
+/// Draw a coloured border around the screen rect `[left, top, right,
+/// bottom]` for `duration_ms`, then remove it.
+///
+/// `color` is `0xRRGGBB` (see [`DEFAULT_HIGHLIGHT_COLOR`]).  Coordinates
+/// are physical screen pixels, as in element `rect`s.  The border does not
+/// take focus or intercept clicks.  Blocks the calling thread for
+/// `duration_ms`, capped at 60 seconds.
+///
+/// Returns an `InputError` if the rect is empty or its size overflows, and
+/// a `ComError` if the overlay window cannot be created.
+pub fn highlight_rect(rect: [i32; 4], duration_ms: u64, color: u32) -> Result<(), WindowsMcpError> {
+    let (width, height) = rect_size(rect)?;
+    crate::dpi::ensure_per_monitor_aware();
+
+    REGISTER_CLASS.call_once(|| {
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(frame_wndproc),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        // A failed registration surfaces as a CreateWindowExW error.
+        unsafe { RegisterClassW(&class) };
+    });
+
+    let deadline = Instant::now() + Duration::from_millis(duration_ms.min(MAX_HIGHLIGHT_MS));
+    let brush = unsafe { CreateSolidBrush(COLORREF(rgb_to_colorref(color))) };
+    let previous = FRAME_BRUSH.with(|b| b.replace(brush));
+
+    let result = unsafe { show_frame(rect[0], rect[1], width, height, deadline) };
+
+    FRAME_BRUSH.with(|b| b.set(previous));
+    unsafe {
+        let _ = DeleteObject(brush);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb_to_colorref_swaps_red_and_blue() {
+        assert_eq!(rgb_to_colorref(0xFF0000), 0x0000FF);
+        assert_eq!(rgb_to_colorref(0x00FF00), 0x00FF00);
+        assert_eq!(rgb_to_colorref(0x123456), 0x563412);
+        assert_eq!(rgb_to_colorref(0xFF_123456), 0x563412);
+    }
+
+    #[test]
+    fn test_rect_size_rejects_empty() {
+        assert_eq!(rect_size([10, 20, 110, 70]).unwrap(), (100, 50));
+        assert!(rect_size([10, 20, 10, 70]).is_err());
+        assert!(rect_size([10, 20, 110, 10]).is_err());
+        assert!(matches!(
+            highlight_rect([5, 5, 0, 0], 10, DEFAULT_HIGHLIGHT_COLOR),
+            Err(WindowsMcpError::InputError(_))
+        ));
+    }
+
+    #[test]
+    fn test_rect_size_rejects_overflow() {
+        assert!(matches!(
+            rect_size([i32::MIN, 0, i32::MAX, 10]),
+            Err(WindowsMcpError::InputError(_))
+        ));
+        assert!(rect_size([0, i32::MIN, 10, i32::MAX]).is_err());
+        assert_eq!(rect_size([-5, -5, i32::MAX - 5, 5]).unwrap(), (i32::MAX, 10));
+    }
+}
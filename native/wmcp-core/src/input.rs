@@ -3,6 +3,13 @@
 //! All functions are pure Rust with no PyO3 dependency.  PyO3 wrappers
 //! in `wmcp-pyo3` call these via `py.allow_threads()`.
 //!
+//! # Errors
+//!
+//! Every `send_*_raw` function returns `Result<u32, WindowsMcpError>`.  When
+//! `SendInput` injects fewer events than requested (blocked by UIPI, a
+//! secure desktop, or a foreground lock), the Win32 `GetLastError` code is
+//! reported via [`WindowsMcpError::InputError`] instead of a silent `0`.
+//!
 //! # Performance
 //!
 //! `SendInput` batches multiple events atomically, avoiding per-event
@@ -10,25 +17,120 @@
 
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS,
-    KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN,
-    MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE,
-    MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEINPUT,
-    MOUSE_EVENT_FLAGS, VIRTUAL_KEY,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE,
+    MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
+    MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
+    MOUSEEVENTF_VIRTUALDESK, MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY,
 };
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    MOUSEEVENTF_HWHEEL, MOUSEEVENTF_WHEEL,
+    GetDoubleClickTime, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_WHEEL,
 };
+use windows::Win32::System::SystemInformation::GetTickCount;
+
+use crate::errors::WindowsMcpError;
+use windows::Win32::Foundation::{GetLastError, HWND, POINT};
+use windows::Win32::Graphics::Gdi::ClientToScreen;
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
-    SM_YVIRTUALSCREEN,
+    GetCursorPos, GetSystemMetrics, IsWindow, SystemParametersInfoW, SM_CXVIRTUALSCREEN,
+    SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SPI_GETWHEELSCROLLLINES,
+    SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, WHEEL_DELTA,
 };
 
-/// Maximum text length to prevent unbounded allocation.
+/// Maximum text length in UTF-16 code units (one `SendInput` key-down/up
+/// pair per unit) to prevent unbounded allocation.
 const MAX_TEXT_LENGTH: usize = 10_000;
 
+/// Characters per `SendInput` call when typing text.  Very large input
+/// arrays can be partially dropped, so [`send_text_raw`] splits long text
+/// into batches of this many characters.
+const TEXT_CHUNK_CHARS: usize = 512;
+
 /// Maximum hotkey combo length (no real hotkey uses more than 5-6 keys).
 const MAX_HOTKEY_KEYS: usize = 8;
 
+/// Maximum interpolation steps for a drag (bounds the `INPUT` vector).
+const MAX_DRAG_STEPS: u32 = 500;
+
+/// `SPI_GETWHEELSCROLLLINES` value meaning "one notch scrolls a page".
+const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+/// Windows default wheel scroll lines, used when the setting is unreadable
+/// or zero.
+const DEFAULT_WHEEL_SCROLL_LINES: u32 = 3;
+
+/// Named virtual keys accepted by [`vk_from_name`] (lower-case names).
+///
+/// Single letters `a`-`z` and digits `0`-`9` are handled separately since
+/// their VK codes equal the upper-case ASCII value.
+const KEY_NAMES: &[(&str, u16)] = &[
+    ("enter", 0x0D),
+    ("return", 0x0D),
+    ("tab", 0x09),
+    ("esc", 0x1B),
+    ("escape", 0x1B),
+    ("space", 0x20),
+    ("backspace", 0x08),
+    ("delete", 0x2E),
+    ("del", 0x2E),
+    ("insert", 0x2D),
+    ("left", 0x25),
+    ("up", 0x26),
+    ("right", 0x27),
+    ("down", 0x28),
+    ("home", 0x24),
+    ("end", 0x23),
+    ("pageup", 0x21),
+    ("pagedown", 0x22),
+    ("ctrl", 0x11),
+    ("control", 0x11),
+    ("alt", 0x12),
+    ("shift", 0x10),
+    ("lctrl", 0xA2),
+    ("rctrl", 0xA3),
+    ("lalt", 0xA4),
+    ("ralt", 0xA5),
+    ("lshift", 0xA0),
+    ("rshift", 0xA1),
+    ("win", 0x5B),
+    ("f1", 0x70),
+    ("f2", 0x71),
+    ("f3", 0x72),
+    ("f4", 0x73),
+    ("f5", 0x74),
+    ("f6", 0x75),
+    ("f7", 0x76),
+    ("f8", 0x77),
+    ("f9", 0x78),
+    ("f10", 0x79),
+    ("f11", 0x7A),
+    ("f12", 0x7B),
+];
+
+/// Virtual keys that live on the extended (E0-prefixed) part of the
+/// keyboard and need `KEYEVENTF_EXTENDEDKEY`: right Ctrl/Alt, the
+/// navigation cluster, arrows, Win/Apps keys, NumLock, and numpad Divide.
+/// Without the flag, apps that inspect scan codes see the left-side or
+/// numpad variant instead.  Right Shift is *not* an extended key.
+const EXTENDED_VKS: &[u16] = &[
+    0xA3, // VK_RCONTROL
+    0xA5, // VK_RMENU
+    0x2D, // VK_INSERT
+    0x2E, // VK_DELETE
+    0x24, // VK_HOME
+    0x23, // VK_END
+    0x21, // VK_PRIOR
+    0x22, // VK_NEXT
+    0x25, // VK_LEFT
+    0x26, // VK_UP
+    0x27, // VK_RIGHT
+    0x28, // VK_DOWN
+    0x5B, // VK_LWIN
+    0x5C, // VK_RWIN
+    0x5D, // VK_APPS
+    0x90, // VK_NUMLOCK
+    0x6F, // VK_DIVIDE
+];
+
 /// Pre-computed size of `INPUT` struct for `SendInput` calls.
 const INPUT_SIZE: i32 = std::mem::size_of::<INPUT>() as i32;
 
@@ -36,7 +138,11 @@ const INPUT_SIZE: i32 = std::mem::size_of::<INPUT>() as i32;
 ///
 /// Returns `(origin_x, origin_y, width, height)`.  On multi-monitor setups
 /// where a monitor is left of or above the primary, origin can be negative.
+///
+/// Values are physical pixels (see [`crate::dpi`]), matching UIA element
+/// rects, so normalised clicks land correctly on monitors of any scale.
 fn screen_geometry() -> (i32, i32, i32, i32) {
+    crate::dpi::ensure_per_monitor_aware();
     unsafe {
         let x = GetSystemMetrics(SM_XVIRTUALSCREEN);
         let y = GetSystemMetrics(SM_YVIRTUALSCREEN);
@@ -77,11 +183,14 @@ fn unicode_key_input(scan_code: u16, key_up: bool) -> INPUT {
 }
 
 fn virtual_key_input(vk: u16, key_up: bool) -> INPUT {
-    let flags = if key_up {
+    let mut flags = if key_up {
         KEYEVENTF_KEYUP
     } else {
         KEYBD_EVENT_FLAGS(0)
     };
+    if EXTENDED_VKS.contains(&vk) {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
 
     INPUT {
         r#type: INPUT_KEYBOARD,
@@ -97,6 +206,29 @@ fn virtual_key_input(vk: u16, key_up: bool) -> INPUT {
     }
 }
 
+fn scancode_key_input(scan_code: u16, key_up: bool, extended: bool) -> INPUT {
+    let mut flags = KEYEVENTF_SCANCODE;
+    if extended {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
+    if key_up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(0),
+                wScan: scan_code,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
 fn mouse_input(abs_x: i32, abs_y: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
     INPUT {
         r#type: INPUT_MOUSE,
@@ -113,6 +245,22 @@ fn mouse_input(abs_x: i32, abs_y: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
     }
 }
 
+fn mouse_input_timed(abs_x: i32, abs_y: i32, flags: MOUSE_EVENT_FLAGS, time: u32) -> INPUT {
+    INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx: abs_x,
+                dy: abs_y,
+                mouseData: 0,
+                dwFlags: flags,
+                time,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
 fn mouse_input_with_data(abs_x: i32, abs_y: i32, data: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
     INPUT {
         r#type: INPUT_MOUSE,
@@ -139,7 +287,13 @@ fn mouse_input_with_data(abs_x: i32, abs_y: i32, data: i32, flags: MOUSE_EVENT_F
 /// Uses the MSDN formula: `((pixel - origin) * 65535) / (screen_size - 1)`.
 /// Result is clamped to `[0, 65535]` to prevent out-of-range values.
 fn normalise_coords(x: i32, y: i32) -> (i32, i32) {
-    let (origin_x, origin_y, screen_w, screen_h) = screen_geometry();
+    normalise_coords_in(screen_geometry(), x, y)
+}
+
+/// [`normalise_coords`] against an already-queried `(origin_x, origin_y, w, h)`
+/// geometry, so multi-point gestures query `GetSystemMetrics` only once.
+fn normalise_coords_in(geometry: (i32, i32, i32, i32), x: i32, y: i32) -> (i32, i32) {
+    let (origin_x, origin_y, screen_w, screen_h) = geometry;
 
     if screen_w <= 1 || screen_h <= 1 {
         return (0, 0);
@@ -150,67 +304,665 @@ fn normalise_coords(x: i32, y: i32) -> (i32, i32) {
     (abs_x, abs_y)
 }
 
+/// Linearly interpolate `steps` points from `from` towards `to`.
+///
+/// The start point is excluded and the final point is always exactly `to`,
+/// so the returned vector has `steps` entries.  `steps == 0` yields an
+/// empty path.
+fn interpolate_path(from: (i32, i32), to: (i32, i32), steps: u32) -> Vec<(i32, i32)> {
+    let n = steps as i64;
+    let (dx, dy) = (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64);
+    (1..=n)
+        .map(|i| {
+            (
+                (from.0 as i64 + dx * i / n) as i32,
+                (from.1 as i64 + dy * i / n) as i32,
+            )
+        })
+        .collect()
+}
+
+/// Map a button name to its `(down, up)` event flags.
+///
+/// Accepts `"left"`, `"right"`, `"middle"`; anything else falls back to left.
+fn button_flags(button: &str) -> (MOUSE_EVENT_FLAGS, MOUSE_EVENT_FLAGS) {
+    match button {
+        "right" => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+        "middle" => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+        _ => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+    }
+}
+
 /// Flags for absolute mouse positioning on the virtual desktop.
 const ABSOLUTE_MOVE: MOUSE_EVENT_FLAGS =
     MOUSE_EVENT_FLAGS(MOUSEEVENTF_ABSOLUTE.0 | MOUSEEVENTF_MOVE.0 | MOUSEEVENTF_VIRTUALDESK.0);
 
+/// Inject `inputs` in one `SendInput` call.
+///
+/// Returns the number of events injected, or an `InputError` carrying the
+/// `GetLastError` code if `SendInput` injected fewer than `inputs.len()`.
+/// An empty slice is a no-op returning `Ok(0)`.
+fn send_inputs(inputs: &[INPUT]) -> Result<u32, WindowsMcpError> {
+    if inputs.is_empty() {
+        return Ok(0);
+    }
+
+    let sent = unsafe { SendInput(inputs, INPUT_SIZE) };
+    if (sent as usize) < inputs.len() {
+        let code = unsafe { GetLastError() }.0;
+        return Err(WindowsMcpError::InputError(format!(
+            "SendInput injected {sent} of {} events (Win32 error {code}); \
+             input may be blocked by UIPI or a secure desktop",
+            inputs.len()
+        )));
+    }
+    Ok(sent)
+}
+
 // ---------------------------------------------------------------------------
 // Public API -- raw functions (no PyO3)
 // ---------------------------------------------------------------------------
 
+/// Reject text longer than `MAX_TEXT_LENGTH` UTF-16 code units.
+pub(crate) fn check_text_length(units: usize) -> Result<(), WindowsMcpError> {
+    if units > MAX_TEXT_LENGTH {
+        return Err(WindowsMcpError::InputError(format!(
+            "text length {units} UTF-16 units exceeds maximum {MAX_TEXT_LENGTH}"
+        )));
+    }
+    Ok(())
+}
+
+/// Build key-down/key-up `KEYEVENTF_UNICODE` events for every UTF-16 code
+/// unit of `text`.
+///
+/// Returns an `InputError` if `text` is longer than `MAX_TEXT_LENGTH`
+/// UTF-16 code units.
+fn unicode_text_inputs(text: &str) -> Result<Vec<INPUT>, WindowsMcpError> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    check_text_length(units.len())?;
+
+    let mut inputs: Vec<INPUT> = Vec::with_capacity(units.len() * 2);
+    for &unit in &units {
+        inputs.push(unicode_key_input(unit, false));
+        inputs.push(unicode_key_input(unit, true));
+    }
+    Ok(inputs)
+}
+
+/// Split `text` into batches of at most `TEXT_CHUNK_CHARS` characters and
+/// build the Unicode key events for each.
+///
+/// Splitting on `char` boundaries keeps surrogate pairs in one batch.
+fn chunked_text_inputs(text: &str) -> Result<Vec<Vec<INPUT>>, WindowsMcpError> {
+    check_text_length(text.encode_utf16().count())?;
+
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(TEXT_CHUNK_CHARS)
+        .map(|chunk| unicode_text_inputs(&chunk.iter().collect::<String>()))
+        .collect()
+}
+
 /// Type Unicode text via `KEYEVENTF_UNICODE` events.
 ///
-/// Returns the number of input events successfully injected.
-/// Returns 0 if text is empty or exceeds `MAX_TEXT_LENGTH` (10,000 chars).
-pub fn send_text_raw(text: &str) -> u32 {
-    if text.is_empty() || text.len() > MAX_TEXT_LENGTH {
-        return 0;
+/// Text is sent as UTF-16 code units, one key-down/key-up pair per unit.
+/// Characters outside the Basic Multilingual Plane (emoji, rare CJK) are
+/// encoded as a surrogate pair, so they produce **4** events -- the target
+/// app's message loop recombines the `WM_CHAR` pair into one character.
+///
+/// Long text is sent in sequential `SendInput` calls of at most
+/// `TEXT_CHUNK_CHARS` (512) characters each, since one very large batch
+/// can be silently truncated.  If a later batch is rejected, the earlier
+/// ones have already been typed.
+///
+/// Returns the total number of input events injected (0 for empty text),
+/// or an error if text exceeds `MAX_TEXT_LENGTH` (10,000 UTF-16 code
+/// units) or `SendInput` rejects the events.
+pub fn send_text_raw(text: &str) -> Result<u32, WindowsMcpError> {
+    let mut sent = 0;
+    for inputs in chunked_text_inputs(text)? {
+        sent += send_inputs(&inputs)?;
+    }
+    Ok(sent)
+}
+
+/// Type Unicode text one character at a time with a pause between each.
+///
+/// Some Electron and terminal inputs drop characters when a whole string
+/// arrives in one batch faster than their IME/event loop can process it.
+/// When `delay_ms` is non-zero, each character is sent in its own
+/// `SendInput` call followed by a `delay_ms` sleep.  A `delay_ms` of 0 uses
+/// the fast batched path of [`send_text_raw`].
+///
+/// Blocks the calling thread for roughly `chars * delay_ms` milliseconds.
+/// Stops at the first character `SendInput` rejects.
+///
+/// Returns the number of input events injected.
+pub fn send_text_delayed_raw(text: &str, delay_ms: u32) -> Result<u32, WindowsMcpError> {
+    if delay_ms == 0 {
+        return send_text_raw(text);
     }
+    check_text_length(text.encode_utf16().count())?;
 
-    let chars: Vec<u16> = text.encode_utf16().collect();
-    let mut inputs: Vec<INPUT> = Vec::with_capacity(chars.len() * 2);
-    for &ch in &chars {
-        inputs.push(unicode_key_input(ch, false));
-        inputs.push(unicode_key_input(ch, true));
+    let delay = std::time::Duration::from_millis(u64::from(delay_ms));
+    let mut sent = 0;
+    let mut buf = [0u16; 2];
+    for (i, ch) in text.chars().enumerate() {
+        if i > 0 {
+            std::thread::sleep(delay);
+        }
+        let units = ch.encode_utf16(&mut buf);
+        let mut inputs: Vec<INPUT> = Vec::with_capacity(units.len() * 2);
+        for &unit in units.iter() {
+            inputs.push(unicode_key_input(unit, false));
+            inputs.push(unicode_key_input(unit, true));
+        }
+        sent += send_inputs(&inputs)?;
     }
-    unsafe { SendInput(&inputs, INPUT_SIZE) }
+    Ok(sent)
 }
 
 /// Press or release a virtual key code.
 ///
-/// Returns 1 on success, 0 on failure.
-pub fn send_key_raw(vk_code: u16, key_up: bool) -> u32 {
-    let input = virtual_key_input(vk_code, key_up);
-    unsafe { SendInput(&[input], INPUT_SIZE) }
+/// Returns 1 on success.
+pub fn send_key_raw(vk_code: u16, key_up: bool) -> Result<u32, WindowsMcpError> {
+    send_inputs(&[virtual_key_input(vk_code, key_up)])
+}
+
+/// Press or release a key by hardware scan code.
+///
+/// Unlike [`send_key_raw`], which injects a virtual key that Windows maps
+/// to a scan code, this sends `KEYEVENTF_SCANCODE` with `wVk = 0`, so the
+/// event looks like it came from the keyboard itself.  DirectInput and
+/// raw-input games often read only scan codes and ignore virtual keys.
+/// The code is layout-independent (set 1, e.g. `0x1E` is the key left of
+/// `S` on any layout); pass `extended` for E0-prefixed keys such as the
+/// arrows or right Ctrl.
+///
+/// Returns 1 on success.
+pub fn send_scancode_raw(
+    scan_code: u16,
+    key_up: bool,
+    extended: bool,
+) -> Result<u32, WindowsMcpError> {
+    send_inputs(&[scancode_key_input(scan_code, key_up, extended)])
+}
+
+/// Look up a virtual key code by human-readable name (case-insensitive).
+///
+/// Covers the keys in `KEY_NAMES` (enter, tab, esc, arrows, f1-f12,
+/// modifiers, ...) plus single letters and digits.  Returns `None` for
+/// unknown names.
+pub fn vk_from_name(name: &str) -> Option<u16> {
+    let lower = name.to_ascii_lowercase();
+
+    if let &[c] = lower.as_bytes() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            return Some(u16::from(c.to_ascii_uppercase()));
+        }
+    }
+
+    KEY_NAMES
+        .iter()
+        .find(|(key, _)| *key == lower)
+        .map(|&(_, vk)| vk)
+}
+
+/// Press or release a key by name (e.g. `"enter"`, `"ctrl"`, `"f5"`).
+///
+/// Returns 1 on success, or an error if the name is not recognised by
+/// [`vk_from_name`].
+pub fn send_key_by_name_raw(name: &str, key_up: bool) -> Result<u32, WindowsMcpError> {
+    let vk = vk_from_name(name)
+        .ok_or_else(|| WindowsMcpError::InputError(format!("Unknown key name: '{name}'")))?;
+    send_key_raw(vk, key_up)
+}
+
+/// Parse a hotkey string like `"ctrl+shift+esc"` into VK codes.
+///
+/// Tokens are split on `+`, trimmed, and resolved via [`vk_from_name`];
+/// a raw hex VK code (e.g. `0x43`) is also accepted per token.  Errors on
+/// an empty combo, an empty or unknown token, or more than
+/// `MAX_HOTKEY_KEYS` (8) keys.
+pub fn parse_hotkey(combo: &str) -> Result<Vec<u16>, WindowsMcpError> {
+    if combo.trim().is_empty() {
+        return Err(WindowsMcpError::InputError("Empty hotkey combo".into()));
+    }
+
+    let vk_codes = combo
+        .split('+')
+        .map(str::trim)
+        .map(|token| {
+            let hex = token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .and_then(|h| u16::from_str_radix(h, 16).ok());
+            vk_from_name(token).or(hex).ok_or_else(|| {
+                WindowsMcpError::InputError(format!(
+                    "Unknown key '{token}' in hotkey combo '{combo}'"
+                ))
+            })
+        })
+        .collect::<Result<Vec<u16>, _>>()?;
+
+    if vk_codes.len() > MAX_HOTKEY_KEYS {
+        return Err(WindowsMcpError::InputError(format!(
+            "Hotkey combo '{combo}' has {} keys; maximum is {MAX_HOTKEY_KEYS}",
+            vk_codes.len()
+        )));
+    }
+    Ok(vk_codes)
+}
+
+/// Send a hotkey given as a human-readable string (e.g. `"alt+f4"`).
+///
+/// See [`parse_hotkey`] for the accepted syntax.  Returns the number of
+/// events injected (2 per key).
+pub fn send_hotkey_str_raw(combo: &str) -> Result<u32, WindowsMcpError> {
+    send_hotkey_raw(&parse_hotkey(combo)?)
+}
+
+/// One step of a parsed [`send_keys_raw`] sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyAction {
+    /// Literal text, typed via `KEYEVENTF_UNICODE`.
+    Text(String),
+    /// Press and release a virtual key.
+    Tap(u16),
+    /// Press a modifier key and keep it held.
+    Down(u16),
+    /// Release a held modifier key.
+    Up(u16),
+}
+
+/// Modifier VKs that a `{NAME}` token holds over the next token instead of
+/// tapping: Shift, Ctrl, Alt (generic and left/right) and the Win key.
+fn is_modifier_vk(vk: u16) -> bool {
+    matches!(vk, 0x10..=0x12 | 0xA0..=0xA5 | 0x5B)
+}
+
+/// Recursive-descent parser for [`send_keys_raw`] sequences.
+///
+/// Positions in error messages are 0-based character offsets.
+struct KeySequenceParser<'a> {
+    sequence: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> KeySequenceParser<'a> {
+    fn new(sequence: &'a str) -> Self {
+        Self {
+            sequence,
+            chars: sequence.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, pos: usize, msg: &str) -> WindowsMcpError {
+        WindowsMcpError::InputError(format!(
+            "Invalid key sequence '{}' at position {pos}: {msg}",
+            self.sequence
+        ))
+    }
+
+    /// Parse tokens until the end of input, or until the `)` closing the
+    /// group opened at `group_start`.
+    fn parse_items(
+        &mut self,
+        actions: &mut Vec<KeyAction>,
+        group_start: Option<usize>,
+        held: bool,
+    ) -> Result<(), WindowsMcpError> {
+        while let Some(&c) = self.chars.get(self.pos) {
+            if c == ')' {
+                if group_start.is_none() {
+                    return Err(self.error(self.pos, "unmatched ')'"));
+                }
+                self.pos += 1;
+                return Ok(());
+            }
+            self.parse_token(actions, held)?;
+        }
+        match group_start {
+            Some(start) => Err(self.error(start, "unclosed '('")),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse one token: a prefixed token, a `(...)` group, a `{KEY}`, or a
+    /// literal character.  `held` is true while a modifier is held.
+    fn parse_token(
+        &mut self,
+        actions: &mut Vec<KeyAction>,
+        held: bool,
+    ) -> Result<(), WindowsMcpError> {
+        let start = self.pos;
+        let c = self.chars[start];
+        self.pos += 1;
+        match c {
+            '+' => self.parse_modified(actions, 0x10, start),
+            '^' => self.parse_modified(actions, 0x11, start),
+            '%' => self.parse_modified(actions, 0x12, start),
+            '(' => self.parse_items(actions, Some(start), held),
+            '{' => {
+                let name = self.parse_brace(start)?;
+                match vk_from_name(&name) {
+                    Some(vk) if is_modifier_vk(vk) => self.parse_modified(actions, vk, start),
+                    Some(vk) => {
+                        actions.push(KeyAction::Tap(vk));
+                        Ok(())
+                    }
+                    None => {
+                        let mut chars = name.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(ch), None) => self.push_char(actions, ch, held, start),
+                            _ => Err(self.error(start, &format!("unknown key '{{{name}}}'"))),
+                        }
+                    }
+                }
+            }
+            '}' => Err(self.error(start, "unmatched '}'")),
+            ch => self.push_char(actions, ch, held, start),
+        }
+    }
+
+    /// Hold `vk` over the next token.
+    fn parse_modified(
+        &mut self,
+        actions: &mut Vec<KeyAction>,
+        vk: u16,
+        start: usize,
+    ) -> Result<(), WindowsMcpError> {
+        match self.chars.get(self.pos) {
+            None | Some(')') => Err(self.error(start, "modifier is not followed by a key")),
+            Some(_) => {
+                actions.push(KeyAction::Down(vk));
+                self.parse_token(actions, true)?;
+                actions.push(KeyAction::Up(vk));
+                Ok(())
+            }
+        }
+    }
+
+    /// Read the name inside `{...}`; the opening brace is at `open`.
+    ///
+    /// A `}` directly after the opening brace is part of the name, so
+    /// `{}}` names a literal `}`.
+    fn parse_brace(&mut self, open: usize) -> Result<String, WindowsMcpError> {
+        let name_start = self.pos;
+        if self.chars.get(self.pos) == Some(&'}') {
+            self.pos += 1;
+        }
+        while self.chars.get(self.pos).is_some_and(|&c| c != '}') {
+            self.pos += 1;
+        }
+        if self.pos >= self.chars.len() {
+            return Err(self.error(open, "unclosed '{'"));
+        }
+        let name: String = self.chars[name_start..self.pos].iter().collect();
+        self.pos += 1;
+        Ok(name)
+    }
+
+    /// Emit a literal character: as text normally, or as a key tap while a
+    /// modifier is held (Unicode input ignores modifier state).
+    fn push_char(
+        &self,
+        actions: &mut Vec<KeyAction>,
+        ch: char,
+        held: bool,
+        pos: usize,
+    ) -> Result<(), WindowsMcpError> {
+        if held {
+            let vk = match ch {
+                ' ' => 0x20,
+                c if c.is_ascii_alphanumeric() => u16::from(c.to_ascii_uppercase() as u8),
+                _ => {
+                    let msg = format!("cannot hold a modifier over '{ch}'; use {{KEY}}");
+                    return Err(self.error(pos, &msg));
+                }
+            };
+            actions.push(KeyAction::Tap(vk));
+        } else if let Some(KeyAction::Text(text)) = actions.last_mut() {
+            text.push(ch);
+        } else {
+            actions.push(KeyAction::Text(ch.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Parse a SendKeys-style sequence into key actions without injecting.
+fn parse_key_sequence(sequence: &str) -> Result<Vec<KeyAction>, WindowsMcpError> {
+    let mut parser = KeySequenceParser::new(sequence);
+    let mut actions = Vec::new();
+    parser.parse_items(&mut actions, None, false)?;
+    Ok(actions)
+}
+
+/// Send a SendKeys-style sequence such as `"^a{DELETE}Hello{ENTER}"`.
+///
+/// Syntax:
+/// - `{NAME}` taps a key by [`vk_from_name`] name (`{ENTER}`, `{F5}`, ...).
+///   A single character that is not a key name is typed literally, so
+///   `{+}`, `{^}`, `{%}`, `{(}`, `{)}`, `{{}` and `{}}` escape the
+///   special characters.
+/// - `+`, `^` and `%` hold Shift, Ctrl and Alt over the next token or
+///   `(...)` group, e.g. `^c` or `+(abc)`.  `{CTRL}`, `{SHIFT}`, `{ALT}`,
+///   `{WIN}` and their left/right variants do the same, so `{CTRL}a` is
+///   Ctrl+A.
+/// - Anything else is literal text, typed via the Unicode path like
+///   [`send_text_raw`].
+///
+/// While a modifier is held, literal characters are sent as virtual keys
+/// (letters case-insensitively, so `^A` is Ctrl+A, not Ctrl+Shift+A) and
+/// only letters, digits and space are allowed.
+///
+/// The whole sequence is injected in one `SendInput` call.  Returns the
+/// number of events injected, or an `InputError` with the character
+/// position if the sequence is malformed or longer than
+/// `MAX_TEXT_LENGTH` (10,000 UTF-16 code units).
+pub fn send_keys_raw(sequence: &str) -> Result<u32, WindowsMcpError> {
+    check_text_length(sequence.encode_utf16().count())?;
+
+    let mut inputs: Vec<INPUT> = Vec::new();
+    for action in parse_key_sequence(sequence)? {
+        match action {
+            KeyAction::Text(text) => inputs.extend(unicode_text_inputs(&text)?),
+            KeyAction::Tap(vk) => {
+                inputs.push(virtual_key_input(vk, false));
+                inputs.push(virtual_key_input(vk, true));
+            }
+            KeyAction::Down(vk) => inputs.push(virtual_key_input(vk, false)),
+            KeyAction::Up(vk) => inputs.push(virtual_key_input(vk, true)),
+        }
+    }
+    send_inputs(&inputs)
 }
 
 /// Click the mouse at absolute screen coordinates.
 ///
 /// Returns the number of events injected (2 on success: down + up).
-pub fn send_click_raw(x: i32, y: i32, button: &str) -> u32 {
+pub fn send_click_raw(x: i32, y: i32, button: &str) -> Result<u32, WindowsMcpError> {
     let (abs_x, abs_y) = normalise_coords(x, y);
 
-    let (down_flag, up_flag) = match button {
-        "right" => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
-        "middle" => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
-        _ => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
-    };
+    let (down_flag, up_flag) = button_flags(button);
 
     let inputs = [
         mouse_input(abs_x, abs_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | down_flag.0)),
         mouse_input(abs_x, abs_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | up_flag.0)),
     ];
 
-    unsafe { SendInput(&inputs, INPUT_SIZE) }
+    send_inputs(&inputs)
+}
+
+/// Click at `(client_x, client_y)` relative to the client area of `hwnd`.
+///
+/// The point is translated to screen coordinates with `ClientToScreen` at
+/// call time, so "20px from the left edge of Notepad" lands correctly
+/// wherever the window currently sits; the click itself goes through
+/// [`send_click_raw`].
+///
+/// Returns an `InputError` if `hwnd` is not a valid window.
+pub fn send_click_in_window_raw(
+    hwnd: isize,
+    client_x: i32,
+    client_y: i32,
+    button: &str,
+) -> Result<u32, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let handle = HWND(hwnd as *mut core::ffi::c_void);
+    if !unsafe { IsWindow(handle) }.as_bool() {
+        return Err(WindowsMcpError::InputError(format!(
+            "Invalid window handle: {hwnd}"
+        )));
+    }
+
+    let mut pt = POINT {
+        x: client_x,
+        y: client_y,
+    };
+    if !unsafe { ClientToScreen(handle, &mut pt) }.as_bool() {
+        return Err(WindowsMcpError::InputError(format!(
+            "ClientToScreen failed for window {hwnd}"
+        )));
+    }
+    send_click_raw(pt.x, pt.y, button)
+}
+
+/// Click at absolute screen coordinates after hovering for `hover_ms`.
+///
+/// Moves the cursor in one `SendInput` call, sleeps, then sends down + up
+/// in a second, so apps that debounce hover (tooltips, hover-opened menus)
+/// see the pointer arrive before the click.  A `hover_ms` of 0 is exactly
+/// [`send_click_raw`].
+///
+/// Returns the number of events injected: 3 on success (move, down, up),
+/// or 2 (down, up) when `hover_ms` is 0.
+pub fn send_click_hover_raw(
+    x: i32,
+    y: i32,
+    button: &str,
+    hover_ms: u32,
+) -> Result<u32, WindowsMcpError> {
+    if hover_ms == 0 {
+        return send_click_raw(x, y, button);
+    }
+
+    let (abs_x, abs_y) = normalise_coords(x, y);
+    let (down_flag, up_flag) = button_flags(button);
+
+    let moved = send_inputs(&[mouse_input(abs_x, abs_y, ABSOLUTE_MOVE)])?;
+    std::thread::sleep(std::time::Duration::from_millis(u64::from(hover_ms)));
+    let clicked = send_inputs(&[
+        mouse_input(
+            abs_x,
+            abs_y,
+            MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | down_flag.0),
+        ),
+        mouse_input(abs_x, abs_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | up_flag.0)),
+    ])?;
+    Ok(moved + clicked)
+}
+
+/// Double-click the mouse at absolute screen coordinates.
+///
+/// Batches move + down + up + down + up into a single `SendInput` call.
+/// Each event carries an explicit timestamp spaced well inside
+/// `GetDoubleClickTime()`, so apps always see a double-click rather than
+/// two single clicks (which is what two separate `send_click_raw` calls
+/// from Python frequently produce).
+///
+/// Returns the number of events injected (5 on success).
+pub fn send_double_click_raw(x: i32, y: i32, button: &str) -> Result<u32, WindowsMcpError> {
+    let (abs_x, abs_y) = normalise_coords(x, y);
+    let (down_flag, up_flag) = button_flags(button);
+    let down = MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | down_flag.0);
+    let up = MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | up_flag.0);
+
+    let gap = double_click_gap_ms(unsafe { GetDoubleClickTime() });
+    let t0 = unsafe { GetTickCount() };
+
+    let inputs = [
+        mouse_input_timed(abs_x, abs_y, ABSOLUTE_MOVE, t0),
+        mouse_input_timed(abs_x, abs_y, down, t0.wrapping_add(gap)),
+        mouse_input_timed(abs_x, abs_y, up, t0.wrapping_add(gap * 2)),
+        mouse_input_timed(abs_x, abs_y, down, t0.wrapping_add(gap * 3)),
+        mouse_input_timed(abs_x, abs_y, up, t0.wrapping_add(gap * 4)),
+    ];
+
+    send_inputs(&inputs)
+}
+
+/// Per-event timestamp gap for [`send_double_click_raw`].
+///
+/// Keeps the whole five-event sequence under a quarter of the system
+/// double-click interval, capped at 10ms per event.
+fn double_click_gap_ms(double_click_time: u32) -> u32 {
+    (double_click_time / 16).clamp(1, 10)
+}
+
+/// Press a mouse button at absolute screen coordinates without releasing it.
+///
+/// Pair with [`send_mouse_up_raw`] to compose custom gestures (press, move
+/// with logic in between, release).
+///
+/// Returns 1 on success.
+pub fn send_mouse_down_raw(x: i32, y: i32, button: &str) -> Result<u32, WindowsMcpError> {
+    let (abs_x, abs_y) = normalise_coords(x, y);
+    let (down_flag, _) = button_flags(button);
+    let input = mouse_input(abs_x, abs_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | down_flag.0));
+    send_inputs(&[input])
+}
+
+/// Release a mouse button at absolute screen coordinates.
+///
+/// Returns 1 on success.
+pub fn send_mouse_up_raw(x: i32, y: i32, button: &str) -> Result<u32, WindowsMcpError> {
+    let (abs_x, abs_y) = normalise_coords(x, y);
+    let (_, up_flag) = button_flags(button);
+    let input = mouse_input(abs_x, abs_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | up_flag.0));
+    send_inputs(&[input])
 }
 
 /// Move the mouse cursor to absolute screen coordinates without clicking.
 ///
 /// Returns 1 on success.
-pub fn send_mouse_move_raw(x: i32, y: i32) -> u32 {
+pub fn send_mouse_move_raw(x: i32, y: i32) -> Result<u32, WindowsMcpError> {
     let (abs_x, abs_y) = normalise_coords(x, y);
     let input = mouse_input(abs_x, abs_y, ABSOLUTE_MOVE);
-    unsafe { SendInput(&[input], INPUT_SIZE) }
+    send_inputs(&[input])
+}
+
+/// Get the current cursor position in physical screen pixels.
+///
+/// Useful before a relative move or drag, or to restore the cursor
+/// afterwards.  Coordinates are in the same space as the absolute
+/// `send_*` functions.
+///
+/// Returns an `InputError` if `GetCursorPos` fails (e.g. on a locked or
+/// secure desktop).
+pub fn get_cursor_pos() -> Result<(i32, i32), WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let mut pt = POINT::default();
+    unsafe { GetCursorPos(&mut pt) }
+        .map_err(|e| WindowsMcpError::InputError(format!("GetCursorPos failed: {e}")))?;
+    Ok((pt.x, pt.y))
+}
+
+/// Move the mouse cursor by `(dx, dy)` pixels relative to its current position.
+///
+/// Sends `MOUSEEVENTF_MOVE` without `MOUSEEVENTF_ABSOLUTE`, which some apps
+/// (games, 3D viewers using raw input) require.  The distance actually moved
+/// is subject to the user's mouse speed and "Enhance pointer precision"
+/// (acceleration) settings, so it may not equal `(dx, dy)` on screen.
+///
+/// Returns 1 on success.
+pub fn send_mouse_move_relative_raw(dx: i32, dy: i32) -> Result<u32, WindowsMcpError> {
+    let input = mouse_input(dx, dy, MOUSEEVENTF_MOVE);
+    send_inputs(&[input])
 }
 
 /// Send a key combination (e.g. Ctrl+C, Alt+Tab).
@@ -218,10 +970,14 @@ pub fn send_mouse_move_raw(x: i32, y: i32) -> u32 {
 /// Presses all keys in order, releases in reverse -- all in a single
 /// atomic `SendInput` call.
 ///
-/// Returns 0 if `vk_codes` is empty or exceeds `MAX_HOTKEY_KEYS` (8).
-pub fn send_hotkey_raw(vk_codes: &[u16]) -> u32 {
+/// Returns an `InputError` if `vk_codes` is empty or exceeds
+/// `MAX_HOTKEY_KEYS` (8).
+pub fn send_hotkey_raw(vk_codes: &[u16]) -> Result<u32, WindowsMcpError> {
     if vk_codes.is_empty() || vk_codes.len() > MAX_HOTKEY_KEYS {
-        return 0;
+        return Err(WindowsMcpError::InputError(format!(
+            "hotkey must have 1-{MAX_HOTKEY_KEYS} keys, got {}",
+            vk_codes.len()
+        )));
     }
 
     let mut inputs: Vec<INPUT> = Vec::with_capacity(vk_codes.len() * 2);
@@ -233,7 +989,7 @@ pub fn send_hotkey_raw(vk_codes: &[u16]) -> u32 {
         inputs.push(virtual_key_input(vk, true));
     }
 
-    unsafe { SendInput(&inputs, INPUT_SIZE) }
+    send_inputs(&inputs)
 }
 
 /// Scroll the mouse wheel at absolute screen coordinates.
@@ -242,7 +998,7 @@ pub fn send_hotkey_raw(vk_codes: &[u16]) -> u32 {
 /// `horizontal` selects horizontal vs vertical scrolling.
 ///
 /// Returns the number of events injected (2: move + wheel).
-pub fn send_scroll_raw(x: i32, y: i32, delta: i32, horizontal: bool) -> u32 {
+pub fn send_scroll_raw(x: i32, y: i32, delta: i32, horizontal: bool) -> Result<u32, WindowsMcpError> {
     let (abs_x, abs_y) = normalise_coords(x, y);
 
     let wheel_flag = if horizontal {
@@ -257,31 +1013,138 @@ pub fn send_scroll_raw(x: i32, y: i32, delta: i32, horizontal: bool) -> u32 {
         mouse_input(abs_x, abs_y, ABSOLUTE_MOVE),
         mouse_input_with_data(0, 0, delta, wheel_flag),
     ];
-    unsafe { SendInput(&inputs, INPUT_SIZE) }
+    send_inputs(&inputs)
 }
 
-/// Drag the mouse from current position to (`to_x`, `to_y`).
+/// Read the user's "lines per wheel notch" setting.
 ///
-/// Sends: left-button-down at current position, move to destination,
-/// left-button-up at destination.  The caller must ensure the cursor is
-/// already at the desired drag origin.
+/// Returns [`WHEEL_PAGESCROLL`] when the wheel is set to scroll a page per
+/// notch, and [`DEFAULT_WHEEL_SCROLL_LINES`] if the query fails or the
+/// setting is zero.
+fn wheel_scroll_lines() -> u32 {
+    let mut lines: u32 = 0;
+    let result = unsafe {
+        SystemParametersInfoW(
+            SPI_GETWHEELSCROLLLINES,
+            0,
+            Some(&mut lines as *mut u32 as *mut core::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    match result {
+        Ok(()) if lines > 0 => lines,
+        _ => DEFAULT_WHEEL_SCROLL_LINES,
+    }
+}
+
+/// Convert `lines` to a wheel delta given the system `scroll_lines` setting.
 ///
-/// `steps` is reserved for future interpolation (currently ignored).
+/// One notch (`WHEEL_DELTA`) scrolls `scroll_lines` lines, so the delta is
+/// `lines * WHEEL_DELTA / scroll_lines`, rounded to the nearest unit and
+/// saturated to `i32`.  In page mode (`WHEEL_PAGESCROLL`) each line is one
+/// notch, i.e. one page.
+fn lines_to_wheel_delta(lines: i32, scroll_lines: u32) -> i32 {
+    let per_notch = if scroll_lines == WHEEL_PAGESCROLL {
+        1
+    } else {
+        i64::from(scroll_lines.max(1))
+    };
+    let scaled = i64::from(lines) * i64::from(WHEEL_DELTA);
+    // Round half away from zero so small requests never collapse to 0.
+    let delta = (scaled + scaled.signum() * (per_notch / 2)) / per_notch;
+    delta.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+}
+
+/// Scroll the mouse wheel by `lines` at absolute screen coordinates.
+///
+/// Converts lines to a wheel delta using the user's configured
+/// `SPI_GETWHEELSCROLLLINES` setting, so the scroll distance matches what a
+/// physical wheel would do.  If the wheel is set to scroll a page per
+/// notch, `lines` is treated as a page count.  Positive `lines` scroll up
+/// (or right when `horizontal`), as with [`send_scroll_raw`].
 ///
-/// Returns total events injected (3 on success).
-pub fn send_drag_raw(to_x: i32, to_y: i32, _steps: u32) -> u32 {
-    let (abs_to_x, abs_to_y) = normalise_coords(to_x, to_y);
+/// Returns the number of events injected (2: move + wheel).
+pub fn send_scroll_lines_raw(
+    x: i32,
+    y: i32,
+    lines: i32,
+    horizontal: bool,
+) -> Result<u32, WindowsMcpError> {
+    let delta = lines_to_wheel_delta(lines, wheel_scroll_lines());
+    send_scroll_raw(x, y, delta, horizontal)
+}
 
-    let inputs = [
-        // Press left button at current position (relative 0,0)
-        mouse_input(0, 0, MOUSEEVENTF_LEFTDOWN),
-        // Move to destination while holding
-        mouse_input(abs_to_x, abs_to_y, ABSOLUTE_MOVE),
-        // Release left button at destination
-        mouse_input(abs_to_x, abs_to_y, MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | MOUSEEVENTF_LEFTUP.0)),
-    ];
+/// Build the `INPUT` sequence for a drag from `from` to `to`.
+///
+/// Emits button-down at `from`, `steps` linearly-interpolated moves ending
+/// at `to`, and button-up at `to`.  `steps` is clamped to
+/// `1..=MAX_DRAG_STEPS`, so the result always has `steps + 2` entries.
+fn drag_inputs(from: (i32, i32), to: (i32, i32), steps: u32, button: &str) -> Vec<INPUT> {
+    let steps = steps.clamp(1, MAX_DRAG_STEPS);
+    let geometry = screen_geometry();
+    let (down_flag, up_flag) = button_flags(button);
+
+    let (abs_from_x, abs_from_y) = normalise_coords_in(geometry, from.0, from.1);
+    let (abs_to_x, abs_to_y) = normalise_coords_in(geometry, to.0, to.1);
+
+    let mut inputs: Vec<INPUT> = Vec::with_capacity(steps as usize + 2);
+    // Press the button at the origin
+    inputs.push(mouse_input(
+        abs_from_x,
+        abs_from_y,
+        MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | down_flag.0),
+    ));
+    // Move to destination through interpolated points while holding
+    for (x, y) in interpolate_path(from, to, steps) {
+        let (abs_x, abs_y) = normalise_coords_in(geometry, x, y);
+        inputs.push(mouse_input(abs_x, abs_y, ABSOLUTE_MOVE));
+    }
+    // Release the button at destination
+    inputs.push(mouse_input(
+        abs_to_x,
+        abs_to_y,
+        MOUSE_EVENT_FLAGS(ABSOLUTE_MOVE.0 | up_flag.0),
+    ));
+    inputs
+}
 
-    unsafe { SendInput(&inputs, INPUT_SIZE) }
+/// Drag the mouse from current position to (`to_x`, `to_y`).
+///
+/// Reads the drag origin via `GetCursorPos`, then sends: button-down at
+/// the origin, `steps` linearly-interpolated moves ending at the destination,
+/// and button-up at the destination -- all in a single atomic
+/// `SendInput` call.  Many apps (canvas editors, sliders, drag-to-select)
+/// only register a drag when they see intermediate `WM_MOUSEMOVE` events.
+///
+/// `steps` is clamped to `1..=MAX_DRAG_STEPS` (500).  `button` is
+/// `"left"`, `"right"`, or `"middle"`; unknown names fall back to left.
+///
+/// Returns total events injected (`steps + 2` on success), or an
+/// `InputError` if the origin cannot be read (e.g. on a locked desktop)
+/// rather than sending a zero-length drag.
+pub fn send_drag_raw(to_x: i32, to_y: i32, steps: u32, button: &str) -> Result<u32, WindowsMcpError> {
+    let from = get_cursor_pos()?;
+    let inputs = drag_inputs(from, (to_x, to_y), steps, button);
+    send_inputs(&inputs)
+}
+
+/// Drag the mouse from (`from_x`, `from_y`) to (`to_x`, `to_y`).
+///
+/// Like [`send_drag_raw`] but with an explicit origin, so there is no race
+/// between positioning the cursor and pressing the button.  Both endpoints
+/// and all interpolated moves are sent in one atomic `SendInput` call.
+///
+/// Returns total events injected (`steps + 2` on success).
+pub fn send_drag_from_raw(
+    from_x: i32,
+    from_y: i32,
+    to_x: i32,
+    to_y: i32,
+    steps: u32,
+    button: &str,
+) -> Result<u32, WindowsMcpError> {
+    let inputs = drag_inputs((from_x, from_y), (to_x, to_y), steps, button);
+    send_inputs(&inputs)
 }
 
 // ---------------------------------------------------------------------------
@@ -292,26 +1155,100 @@ pub fn send_drag_raw(to_x: i32, to_y: i32, _steps: u32) -> u32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lines_to_wheel_delta_default_setting() {
+        assert_eq!(lines_to_wheel_delta(3, 3), 120);
+        assert_eq!(lines_to_wheel_delta(-3, 3), -120);
+        assert_eq!(lines_to_wheel_delta(1, 3), 40);
+        assert_eq!(lines_to_wheel_delta(0, 3), 0);
+    }
+
+    #[test]
+    fn test_lines_to_wheel_delta_rounds_and_saturates() {
+        assert_eq!(lines_to_wheel_delta(1, 7), 17);
+        assert_eq!(lines_to_wheel_delta(-1, 7), -17);
+        assert_eq!(lines_to_wheel_delta(1, 240), 1);
+        assert_eq!(lines_to_wheel_delta(i32::MAX, 1), i32::MAX);
+        assert_eq!(lines_to_wheel_delta(i32::MIN, 1), i32::MIN);
+    }
+
+    #[test]
+    fn test_lines_to_wheel_delta_page_scroll() {
+        assert_eq!(lines_to_wheel_delta(2, WHEEL_PAGESCROLL), 240);
+        assert_eq!(lines_to_wheel_delta(-1, WHEEL_PAGESCROLL), -120);
+    }
+
     #[test]
     fn test_send_text_raw_empty_string() {
-        assert_eq!(send_text_raw(""), 0);
+        assert_eq!(send_text_raw("").unwrap(), 0);
     }
 
     #[test]
     fn test_send_text_raw_too_long() {
         let long = "a".repeat(MAX_TEXT_LENGTH + 1);
-        assert_eq!(send_text_raw(&long), 0);
+        assert!(matches!(
+            send_text_raw(&long),
+            Err(WindowsMcpError::InputError(_))
+        ));
+    }
+
+    #[test]
+    fn test_unicode_text_inputs_surrogate_pair() {
+        // U+1F600 is two UTF-16 code units -> 2 x (down + up)
+        assert_eq!(unicode_text_inputs("\u{1F600}").unwrap().len(), 4);
+        assert_eq!(unicode_text_inputs("abc").unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_chunked_text_inputs_counts_and_batches() {
+        // 5000 ASCII chars -> 10,000 events in 512-char batches
+        let chunks = chunked_text_inputs(&"a".repeat(5000)).unwrap();
+        assert_eq!(chunks.iter().map(Vec::len).sum::<usize>(), 10_000);
+        assert_eq!(chunks.len(), 10);
+        assert_eq!(chunks[0].len(), TEXT_CHUNK_CHARS * 2);
+
+        // Surrogate pairs stay within one batch: 4 events per emoji
+        let chunks = chunked_text_inputs(&"\u{1F600}".repeat(600)).unwrap();
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(
+            sizes,
+            vec![TEXT_CHUNK_CHARS * 4, (600 - TEXT_CHUNK_CHARS) * 4]
+        );
+
+        assert!(chunked_text_inputs("").unwrap().is_empty());
+        assert!(chunked_text_inputs(&"a".repeat(MAX_TEXT_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_unicode_text_inputs_length_counts_utf16_units() {
+        // 10k CJK chars are 30k UTF-8 bytes but only 10k UTF-16 units
+        let cjk = "\u{4E2D}".repeat(MAX_TEXT_LENGTH);
+        assert!(unicode_text_inputs(&cjk).is_ok());
+        // 5001 emoji are 10,002 UTF-16 units
+        let emoji = "\u{1F600}".repeat(MAX_TEXT_LENGTH / 2 + 1);
+        assert!(unicode_text_inputs(&emoji).is_err());
+    }
+
+    #[test]
+    fn test_send_text_delayed_raw_empty_string() {
+        assert_eq!(send_text_delayed_raw("", 10).unwrap(), 0);
     }
 
     #[test]
     fn test_send_hotkey_raw_empty_codes() {
-        assert_eq!(send_hotkey_raw(&[]), 0);
+        assert!(matches!(
+            send_hotkey_raw(&[]),
+            Err(WindowsMcpError::InputError(_))
+        ));
     }
 
     #[test]
     fn test_send_hotkey_raw_too_many_keys() {
         let keys: Vec<u16> = (0..MAX_HOTKEY_KEYS as u16 + 1).collect();
-        assert_eq!(send_hotkey_raw(&keys), 0);
+        assert!(matches!(
+            send_hotkey_raw(&keys),
+            Err(WindowsMcpError::InputError(_))
+        ));
     }
 
     #[test]
@@ -324,6 +1261,286 @@ mod tests {
         assert_eq!(MAX_HOTKEY_KEYS, 8);
     }
 
+    #[test]
+    fn test_max_drag_steps_constant() {
+        assert_eq!(MAX_DRAG_STEPS, 500);
+    }
+
+    #[test]
+    fn test_normalise_coords_secondary_monitor_left_of_primary() {
+        // 3840x2160 secondary at 150% left of a 1920x1080 primary; with
+        // per-monitor awareness the virtual desktop is in physical pixels.
+        let geometry = (-3840, 0, 5760, 2160);
+        assert_eq!(normalise_coords_in(geometry, -3840, 0), (0, 0));
+        assert_eq!(normalise_coords_in(geometry, 1919, 2159), (65535, 65535));
+
+        // Centre of a control on the secondary maps back to the same pixel
+        // when Windows denormalises (pixel = origin + abs * (size - 1) / 65535).
+        let (cx, cy) = (-2000, 900);
+        let (ax, ay) = normalise_coords_in(geometry, cx, cy);
+        let back_x = -3840 + ((ax as i64 * 5759 + 32767) / 65535) as i32;
+        let back_y = ((ay as i64 * 2159 + 32767) / 65535) as i32;
+        assert!((back_x - cx).abs() <= 1, "{back_x} vs {cx}");
+        assert!((back_y - cy).abs() <= 1, "{back_y} vs {cy}");
+    }
+
+    /// End-to-end check for mixed-DPI setups: put a button on a secondary
+    /// monitor, click the centre of its rect, and confirm the pointer
+    /// landed on it.  Needs an interactive desktop with two monitors,
+    /// ideally at different scale factors.
+    #[test]
+    #[ignore = "needs an interactive desktop with a secondary monitor"]
+    fn test_click_lands_on_control_on_secondary_monitor() {
+        use windows::core::w;
+        use windows::Win32::Foundation::{BOOL, HINSTANCE, HWND, LPARAM, RECT};
+        use windows::Win32::Graphics::Gdi::{
+            EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO,
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, DestroyWindow, GetWindowRect, WindowFromPoint, HMENU,
+            MONITORINFOF_PRIMARY, WS_EX_TOPMOST, WS_POPUP, WS_VISIBLE,
+        };
+
+        unsafe extern "system" fn collect(
+            monitor: HMONITOR,
+            _: HDC,
+            _: *mut RECT,
+            data: LPARAM,
+        ) -> BOOL {
+            let monitors = &mut *(data.0 as *mut Vec<MONITORINFO>);
+            let mut info = MONITORINFO {
+                cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+                ..Default::default()
+            };
+            if GetMonitorInfoW(monitor, &mut info).as_bool() {
+                monitors.push(info);
+            }
+            BOOL(1)
+        }
+
+        crate::dpi::ensure_per_monitor_aware();
+        let mut monitors: Vec<MONITORINFO> = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                HDC::default(),
+                None,
+                Some(collect),
+                LPARAM(std::ptr::addr_of_mut!(monitors) as isize),
+            );
+        }
+        let secondary = monitors
+            .iter()
+            .find(|m| m.dwFlags & MONITORINFOF_PRIMARY == 0)
+            .expect("no secondary monitor attached");
+
+        let work = secondary.rcWork;
+        let button = unsafe {
+            CreateWindowExW(
+                WS_EX_TOPMOST,
+                w!("BUTTON"),
+                w!("wmcp-dpi-test"),
+                WS_POPUP | WS_VISIBLE,
+                work.left + 40,
+                work.top + 40,
+                160,
+                60,
+                HWND::default(),
+                HMENU::default(),
+                HINSTANCE::default(),
+                None,
+            )
+        }
+        .expect("CreateWindowExW failed");
+
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(button, &mut rect) }.expect("GetWindowRect failed");
+        let (cx, cy) = ((rect.left + rect.right) / 2, (rect.top + rect.bottom) / 2);
+
+        let clicked = send_click_raw(cx, cy, "left");
+        let landed = get_cursor_pos().ok();
+        let hit = landed.map(|(x, y)| unsafe { WindowFromPoint(POINT { x, y }) });
+        unsafe {
+            let _ = DestroyWindow(button);
+        }
+
+        clicked.expect("send_click_raw failed");
+        let (x, y) = landed.expect("GetCursorPos failed");
+        assert!(
+            (x - cx).abs() <= 1 && (y - cy).abs() <= 1,
+            "clicked ({cx},{cy}) but cursor is at ({x},{y})"
+        );
+        assert_eq!(hit, Some(button));
+    }
+
+    #[test]
+    fn test_interpolate_path_ends_at_target() {
+        let path = interpolate_path((0, 0), (300, -150), 30);
+        assert_eq!(path.len(), 30);
+        assert_eq!(path[0], (10, -5));
+        assert_eq!(*path.last().unwrap(), (300, -150));
+    }
+
+    #[test]
+    fn test_interpolate_path_zero_steps() {
+        assert!(interpolate_path((5, 5), (10, 10), 0).is_empty());
+    }
+
+    #[test]
+    fn test_drag_inputs_event_count() {
+        assert_eq!(drag_inputs((0, 0), (100, 100), 30, "left").len(), 32);
+        // steps is clamped to 1..=MAX_DRAG_STEPS
+        assert_eq!(drag_inputs((0, 0), (100, 100), 0, "left").len(), 3);
+        assert_eq!(
+            drag_inputs((0, 0), (100, 100), 10_000, "left").len(),
+            MAX_DRAG_STEPS as usize + 2
+        );
+    }
+
+    #[test]
+    fn test_double_click_gap_within_interval() {
+        // Default double-click time is 500ms
+        assert_eq!(double_click_gap_ms(500), 10);
+        // Whole sequence (4 gaps) stays under the interval even when tiny
+        assert!(double_click_gap_ms(100) * 4 < 100);
+        assert_eq!(double_click_gap_ms(0), 1);
+    }
+
+    #[test]
+    fn test_button_flags_fallback_to_left() {
+        assert_eq!(button_flags("right"), (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP));
+        assert_eq!(button_flags("middle"), (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP));
+        assert_eq!(button_flags("bogus"), (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP));
+    }
+
+    #[test]
+    fn test_vk_from_name_case_insensitive() {
+        assert_eq!(vk_from_name("enter"), Some(0x0D));
+        assert_eq!(vk_from_name("ENTER"), Some(0x0D));
+        assert_eq!(vk_from_name("PageDown"), Some(0x22));
+        assert_eq!(vk_from_name("f12"), Some(0x7B));
+    }
+
+    #[test]
+    fn test_vk_from_name_letters_and_digits() {
+        assert_eq!(vk_from_name("a"), Some(0x41));
+        assert_eq!(vk_from_name("Z"), Some(0x5A));
+        assert_eq!(vk_from_name("7"), Some(0x37));
+    }
+
+    #[test]
+    fn test_vk_from_name_unknown() {
+        assert_eq!(vk_from_name("notakey"), None);
+        assert_eq!(vk_from_name(""), None);
+        assert!(send_key_by_name_raw("notakey", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_hotkey_names() {
+        assert_eq!(parse_hotkey("ctrl+shift+esc").unwrap(), vec![0x11, 0x10, 0x1B]);
+        assert_eq!(parse_hotkey(" Alt + F4 ").unwrap(), vec![0x12, 0x73]);
+        assert_eq!(parse_hotkey("ctrl+0x43").unwrap(), vec![0x11, 0x43]);
+    }
+
+    #[test]
+    fn test_parse_hotkey_rejects_bad_input() {
+        assert!(parse_hotkey("").is_err());
+        assert!(parse_hotkey("   ").is_err());
+        assert!(parse_hotkey("ctrl+").is_err());
+        assert!(parse_hotkey("ctrl+bogus").is_err());
+        assert!(parse_hotkey("a+b+c+d+e+f+g+h+i").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_sequence_text_and_keys() {
+        use KeyAction::*;
+        assert_eq!(
+            parse_key_sequence("Hi there{ENTER}").unwrap(),
+            vec![Text("Hi there".into()), Tap(0x0D)]
+        );
+        assert_eq!(parse_key_sequence("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_parse_key_sequence_modifiers() {
+        use KeyAction::*;
+        assert_eq!(
+            parse_key_sequence("^a{DELETE}").unwrap(),
+            vec![Down(0x11), Tap(0x41), Up(0x11), Tap(0x2E)]
+        );
+        assert_eq!(
+            parse_key_sequence("{CTRL}A").unwrap(),
+            vec![Down(0x11), Tap(0x41), Up(0x11)]
+        );
+        assert_eq!(
+            parse_key_sequence("^+{ESC}").unwrap(),
+            vec![Down(0x11), Down(0x10), Tap(0x1B), Up(0x10), Up(0x11)]
+        );
+    }
+
+    #[test]
+    fn test_parse_key_sequence_group() {
+        use KeyAction::*;
+        assert_eq!(
+            parse_key_sequence("+(ab)c").unwrap(),
+            vec![Down(0x10), Tap(0x41), Tap(0x42), Up(0x10), Text("c".into())]
+        );
+    }
+
+    #[test]
+    fn test_parse_key_sequence_escapes() {
+        use KeyAction::*;
+        assert_eq!(
+            parse_key_sequence("1{+}1{{}{}}{%}").unwrap(),
+            vec![Text("1+1{}%".into())]
+        );
+    }
+
+    #[test]
+    fn test_parse_key_sequence_errors_report_position() {
+        let cases = [
+            ("ab{ENTER", "position 2"),
+            ("{NOPE}", "unknown key"),
+            ("x)", "position 1"),
+            ("^(ab", "unclosed '('"),
+            ("abc^", "position 3"),
+            ("}", "unmatched '}'"),
+            ("^!", "over '!'"),
+        ];
+        for (sequence, expected) in cases {
+            let err = parse_key_sequence(sequence).unwrap_err().to_string();
+            assert!(err.contains(expected), "{sequence}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_virtual_key_input_extended_flag() {
+        let flags = |vk, up| unsafe { virtual_key_input(vk, up).Anonymous.ki.dwFlags };
+
+        // Right-side Ctrl/Alt are extended keys
+        assert!(flags(vk_from_name("rctrl").unwrap(), false).contains(KEYEVENTF_EXTENDEDKEY));
+        assert!(flags(vk_from_name("ralt").unwrap(), true).contains(KEYEVENTF_EXTENDEDKEY));
+        assert!(flags(vk_from_name("ralt").unwrap(), true).contains(KEYEVENTF_KEYUP));
+
+        // Left-side modifiers and right Shift are not
+        assert!(!flags(vk_from_name("lctrl").unwrap(), false).contains(KEYEVENTF_EXTENDEDKEY));
+        assert!(!flags(vk_from_name("rshift").unwrap(), false).contains(KEYEVENTF_EXTENDEDKEY));
+    }
+
+    #[test]
+    fn test_scancode_key_input_flags() {
+        let ki = |code, up, ext| unsafe { scancode_key_input(code, up, ext).Anonymous.ki };
+
+        let down = ki(0x1E, false, false);
+        assert_eq!(down.wVk, VIRTUAL_KEY(0));
+        assert_eq!(down.wScan, 0x1E);
+        assert_eq!(down.dwFlags, KEYEVENTF_SCANCODE);
+
+        let up = ki(0x48, true, true);
+        assert!(up.dwFlags.contains(KEYEVENTF_SCANCODE));
+        assert!(up.dwFlags.contains(KEYEVENTF_EXTENDEDKEY));
+        assert!(up.dwFlags.contains(KEYEVENTF_KEYUP));
+    }
+
     #[test]
     fn test_input_size_constant() {
         assert_eq!(INPUT_SIZE as usize, std::mem::size_of::<INPUT>());
@@ -11,7 +11,11 @@
 //! | Module | Purpose |
 //! |--------|---------|
 //! | [`errors`] | `WindowsMcpError` enum via `thiserror` |
+//! | [`clipboard`] | Clipboard text read/write and image write |
 //! | [`com`] | `COMGuard` RAII wrapper for COM apartment init |
+//! | [`dpi`] | Per-monitor-v2 DPI awareness so coordinates are physical pixels |
+//! | [`highlight`] | Temporary on-screen border around a rect, for debugging |
+//! | `logging` | `init_logging` stderr logger setup (feature `logging`) |
 //! | [`system_info`] | System telemetry via `sysinfo` crate |
 //! | [`input`] | `SendInput` keyboard/mouse simulation |
 //! | [`tree`] | UIA accessibility tree traversal via `windows-rs` + Rayon |
@@ -19,9 +23,14 @@
 //! | [`query`] | UIA element queries: `ElementFromPoint`, `FindAll`, screen metrics |
 //! | [`pattern`] | UIA pattern invocation: Invoke, Toggle, SetValue, Expand, etc. |
 
+pub mod clipboard;
 pub mod com;
+pub mod dpi;
 pub mod errors;
+pub mod highlight;
 pub mod input;
+#[cfg(feature = "logging")]
+pub mod logging;
 pub mod pattern;
 pub mod query;
 pub mod screenshot;
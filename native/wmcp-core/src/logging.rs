@@ -0,0 +1,63 @@
+//! Optional logger setup for embedders (feature `logging`).
+//!
+//! The crate reports diagnostics through the `log` facade: COM apartment
+//! setup, DXGI-to-GDI screenshot fallbacks, and windows skipped during a
+//! tree capture.  Nothing is printed unless a logger is installed.  An
+//! embedder with its own `log` backend needs nothing from here; others
+//! can call [`init_logging`] to send the records to stderr via
+//! `env_logger`.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use log::LevelFilter;
+
+use crate::errors::WindowsMcpError;
+
+/// Outcome of the first successful-level [`init_logging`] call.
+static INIT_STATUS: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Parse `"off"`, `"error"`, `"warn"`, `"info"`, `"debug"` or `"trace"`
+/// (case-insensitive).
+fn parse_level(level: &str) -> Result<LevelFilter, WindowsMcpError> {
+    LevelFilter::from_str(level.trim()).map_err(|_| {
+        WindowsMcpError::LoggingError(format!(
+            "Unknown log level '{level}'; expected off, error, warn, info, debug or trace"
+        ))
+    })
+}
+
+/// Route this crate's `log` output to stderr through `env_logger` at
+/// `level`.  A `RUST_LOG` environment variable, if set, refines the filter
+/// (e.g. `RUST_LOG=wmcp_core::tree=trace`).
+///
+/// Idempotent: the first call with a valid level installs the logger and
+/// later calls return its outcome without changing the level.  Returns a
+/// `LoggingError` for an unknown level name, or if the process already
+/// has a different logger installed.
+pub fn init_logging(level: &str) -> Result<(), WindowsMcpError> {
+    let filter = parse_level(level)?;
+    INIT_STATUS
+        .get_or_init(|| {
+            env_logger::Builder::new()
+                .filter_level(filter)
+                .parse_env(env_logger::Env::default())
+                .try_init()
+                .map_err(|e| format!("Logger initialization failed: {e}"))
+        })
+        .clone()
+        .map_err(WindowsMcpError::LoggingError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level_names() {
+        assert_eq!(parse_level("warn").unwrap(), LevelFilter::Warn);
+        assert_eq!(parse_level(" DEBUG ").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level("off").unwrap(), LevelFilter::Off);
+        assert!(parse_level("verbose").is_err());
+    }
+}
@@ -1,8 +1,11 @@
-//! UIA pattern invocation: Invoke, Toggle, SetValue, Expand, Collapse, Select.
+//! UIA pattern invocation: Invoke, Toggle, SetValue, Expand, Collapse, Select,
+//! Scroll, plus reading text via TextPattern.
 //!
-//! Each function locates the element at screen coordinates via `ElementFromPoint`,
-//! then invokes the requested UIA pattern.  All functions are pure Rust with no
-//! PyO3 dependency.
+//! Each `*_at` function locates the element at screen coordinates via
+//! `ElementFromPoint`, then invokes the requested UIA pattern.  The
+//! `*_by_automation_id` variants instead find the first descendant of a window
+//! with a given AutomationId, which survives overlapping windows and controls
+//! that move.  All functions are pure Rust with no PyO3 dependency.
 //!
 //! # COM apartment model
 //!
@@ -10,17 +13,22 @@
 
 use serde::Serialize;
 use windows::core::Interface;
-use windows::Win32::Foundation::POINT;
+use windows::Win32::Foundation::{HWND, POINT};
 use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
 use windows::Win32::UI::Accessibility::{
     CUIAutomation, IUIAutomation, IUIAutomationElement, IUIAutomationExpandCollapsePattern,
-    IUIAutomationInvokePattern, IUIAutomationSelectionItemPattern, IUIAutomationTogglePattern,
-    IUIAutomationValuePattern, UIA_ExpandCollapsePatternId, UIA_InvokePatternId,
-    UIA_SelectionItemPatternId, UIA_TogglePatternId, UIA_ValuePatternId,
+    IUIAutomationGridPattern, IUIAutomationInvokePattern, IUIAutomationScrollPattern,
+    IUIAutomationSelectionItemPattern, IUIAutomationTextPattern, IUIAutomationTextRangeArray,
+    IUIAutomationTogglePattern, IUIAutomationValuePattern, IUIAutomationWindowPattern,
+    UIA_ExpandCollapsePatternId, UIA_GridPatternId, UIA_InvokePatternId, UIA_ScrollPatternId,
+    UIA_ScrollPatternNoScroll, UIA_SelectionItemPatternId, UIA_TextPatternId, UIA_TogglePatternId,
+    UIA_ValuePatternId, UIA_WindowPatternId, TreeScope_Descendants, WindowVisualState,
+    WindowVisualState_Maximized, WindowVisualState_Minimized, WindowVisualState_Normal,
 };
 
 use crate::com::COMGuard;
 use crate::errors::WindowsMcpError;
+use crate::query::{build_find_condition, read_element_info, ElementInfo, FindCriteria};
 use crate::tree::control_type_name;
 
 // ---------------------------------------------------------------------------
@@ -35,6 +43,26 @@ pub struct PatternResult {
     pub action: String,
     pub success: bool,
     pub detail: String,
+    /// `ValuePattern.CurrentIsReadOnly`; only set by [`get_value_at`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_read_only: Option<bool>,
+}
+
+/// Text read through `TextPattern`.
+///
+/// `success` is `false` (with empty text and the reason in `detail`) when
+/// the element does not support the pattern, mirroring [`PatternResult`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TextResult {
+    pub element_name: String,
+    pub element_type: String,
+    pub success: bool,
+    pub detail: String,
+    /// The whole document range.
+    pub full_text: String,
+    /// The current selection; multiple selected ranges are joined with
+    /// newlines.  Empty when nothing is selected.
+    pub selected_text: String,
 }
 
 // ---------------------------------------------------------------------------
@@ -49,6 +77,7 @@ unsafe fn element_at(
     x: i32,
     y: i32,
 ) -> Result<(IUIAutomation, IUIAutomationElement), WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
     let uia: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)?;
 
     let point = POINT { x, y };
@@ -59,6 +88,64 @@ unsafe fn element_at(
     Ok((uia, element))
 }
 
+/// Find the first descendant of `window` whose AutomationId is `id`.
+///
+/// Returns `(IUIAutomation, IUIAutomationElement)` like [`element_at`].
+unsafe fn element_by_automation_id(
+    window: isize,
+    id: &str,
+) -> Result<(IUIAutomation, IUIAutomationElement), WindowsMcpError> {
+    let uia: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)?;
+
+    let root = uia
+        .ElementFromHandle(HWND(window as *mut core::ffi::c_void))
+        .map_err(|e| WindowsMcpError::TreeError(format!("ElementFromHandle({window}): {e}")))?;
+
+    let criteria = FindCriteria {
+        automation_id: Some(id.to_owned()),
+        ..Default::default()
+    };
+    let condition = build_find_condition(&uia, &criteria)?;
+    // FindFirst reports "not found" as a null element, which windows-rs
+    // surfaces as an error.
+    let element = root.FindFirst(TreeScope_Descendants, &condition).map_err(|e| {
+        WindowsMcpError::TreeError(format!(
+            "No element with AutomationId '{id}' in window {window}: {e}"
+        ))
+    })?;
+
+    Ok((uia, element))
+}
+
+/// Which element a pattern action targets.
+#[derive(Debug, Clone, Copy)]
+enum Target<'a> {
+    /// The element under a screen point.
+    Point(i32, i32),
+    /// The first descendant of `window` with AutomationId `id`.
+    AutomationId { window: isize, id: &'a str },
+}
+
+impl std::fmt::Display for Target<'_> {
+    /// Renders as the tail of a `detail` message, e.g. "at (10,20)".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Point(x, y) => write!(f, "at ({x},{y})"),
+            Self::AutomationId { id, .. } => write!(f, "by AutomationId '{id}'"),
+        }
+    }
+}
+
+/// Locate the element `target` refers to.
+unsafe fn locate(
+    target: Target<'_>,
+) -> Result<(IUIAutomation, IUIAutomationElement), WindowsMcpError> {
+    match target {
+        Target::Point(x, y) => element_at(x, y),
+        Target::AutomationId { window, id } => element_by_automation_id(window, id),
+    }
+}
+
 /// Read element name for diagnostics.
 unsafe fn elem_name(element: &IUIAutomationElement) -> String {
     element
@@ -83,18 +170,167 @@ fn pattern_not_supported(name: &str, etype: &str, action: &str, pattern_name: &s
         action: action.to_owned(),
         success: false,
         detail: format!("Element does not support {pattern_name}"),
+        is_read_only: None,
     }
 }
 
+/// Map a UIA `ToggleState` value to its lowercase name.
+pub(crate) fn toggle_state_name(state: i32) -> &'static str {
+    match state {
+        0 => "off",
+        1 => "on",
+        2 => "indeterminate",
+        _ => "unknown",
+    }
+}
+
+/// Map a UIA `WindowInteractionState` value to its lowercase name.
+fn interaction_state_name(state: i32) -> &'static str {
+    match state {
+        0 => "running",
+        1 => "closing",
+        2 => "ready",
+        3 => "blocked by modal window",
+        4 => "not responding",
+        _ => "unknown",
+    }
+}
+
+/// What [`window_pattern_action_at`] asks of a `WindowPattern`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WindowAction {
+    SetState(WindowVisualState),
+    Close,
+}
+
+/// Parse a window action name (case-insensitive).
+fn window_action(action: &str) -> Option<WindowAction> {
+    match action.to_ascii_lowercase().as_str() {
+        "maximize" => Some(WindowAction::SetState(WindowVisualState_Maximized)),
+        "minimize" => Some(WindowAction::SetState(WindowVisualState_Minimized)),
+        "normal" => Some(WindowAction::SetState(WindowVisualState_Normal)),
+        "close" => Some(WindowAction::Close),
+        _ => None,
+    }
+}
+
+/// Maximum ancestors walked looking for a `WindowPattern` (or
+/// `GridPattern`) provider.
+const MAX_WINDOW_ANCESTORS: usize = 64;
+
+/// Return `element` or its nearest control-view ancestor that supports
+/// `WindowPattern`, with the pattern.
+unsafe fn window_pattern_ancestor(
+    uia: &IUIAutomation,
+    element: IUIAutomationElement,
+) -> Option<(IUIAutomationElement, IUIAutomationWindowPattern)> {
+    let walker = uia.ControlViewWalker().ok()?;
+    let mut current = element;
+    for _ in 0..MAX_WINDOW_ANCESTORS {
+        let pattern = current
+            .GetCurrentPattern(UIA_WindowPatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationWindowPattern>().ok());
+        if let Some(pattern) = pattern {
+            return Some((current, pattern));
+        }
+        // The desktop root has no parent, which ends the walk.
+        current = walker.GetParentElement(&current).ok()?;
+    }
+    None
+}
+
+/// Return the `GridPattern` of `element` or of its nearest control-view
+/// ancestor, so a point on a cell still finds its grid.
+unsafe fn grid_pattern_ancestor(
+    uia: &IUIAutomation,
+    element: IUIAutomationElement,
+) -> Option<IUIAutomationGridPattern> {
+    let walker = uia.ControlViewWalker().ok()?;
+    let mut current = element;
+    for _ in 0..MAX_WINDOW_ANCESTORS {
+        let pattern = current
+            .GetCurrentPattern(UIA_GridPatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationGridPattern>().ok());
+        if pattern.is_some() {
+            return pattern;
+        }
+        current = walker.GetParentElement(&current).ok()?;
+    }
+    None
+}
+
+/// Find the grid at `(x, y)` and read its `(rows, columns)`.
+unsafe fn grid_at(
+    x: i32,
+    y: i32,
+) -> Result<(IUIAutomationGridPattern, i32, i32), WindowsMcpError> {
+    let (uia, element) = element_at(x, y)?;
+    let grid = grid_pattern_ancestor(&uia, element).ok_or_else(|| {
+        WindowsMcpError::TreeError(format!(
+            "Element at ({x},{y}) and its ancestors do not support GridPattern"
+        ))
+    })?;
+    let rows = grid
+        .CurrentRowCount()
+        .map_err(|e| WindowsMcpError::TreeError(format!("GridPattern.RowCount failed: {e}")))?;
+    let columns = grid
+        .CurrentColumnCount()
+        .map_err(|e| WindowsMcpError::TreeError(format!("GridPattern.ColumnCount failed: {e}")))?;
+    Ok((grid, rows, columns))
+}
+
+/// Check that `(row, column)` lies inside a `rows` x `columns` grid.
+fn check_grid_cell(row: i32, column: i32, rows: i32, columns: i32) -> Result<(), WindowsMcpError> {
+    if (0..rows).contains(&row) && (0..columns).contains(&column) {
+        Ok(())
+    } else {
+        Err(WindowsMcpError::TreeError(format!(
+            "Cell ({row},{column}) is outside the {rows}x{columns} grid"
+        )))
+    }
+}
+
+/// Concatenate the text of every range in `ranges`, one per line.
+unsafe fn ranges_text(ranges: &IUIAutomationTextRangeArray) -> String {
+    let count = ranges.Length().unwrap_or(0);
+    (0..count)
+        .filter_map(|i| ranges.GetElement(i).ok())
+        .filter_map(|range| range.GetText(-1).ok())
+        .map(|text| text.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A scroll percentage is valid for `SetScrollPercent` when it is
+/// [`UIA_ScrollPatternNoScroll`] or within `0.0..=100.0`.
+fn valid_scroll_percent(percent: f64) -> bool {
+    percent == UIA_ScrollPatternNoScroll || (0.0..=100.0).contains(&percent)
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
 
 /// Invoke the `InvokePattern` on the element at `(x, y)`.
 pub fn invoke_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    invoke(Target::Point(x, y))
+}
+
+/// Invoke the `InvokePattern` on the first element in `window_handle` whose
+/// AutomationId is `automation_id`.
+pub fn invoke_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    invoke(Target::AutomationId { window: window_handle, id: automation_id })
+}
+
+fn invoke(target: Target<'_>) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -114,7 +350,8 @@ pub fn invoke_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 element_type: etype,
                 action: "invoke".into(),
                 success: true,
-                detail: format!("Invoked at ({x},{y})"),
+                detail: format!("Invoked {target}"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(&name, &etype, "invoke", "InvokePattern")),
@@ -125,9 +362,22 @@ pub fn invoke_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
 ///
 /// Returns the new toggle state in `detail` (e.g. "State: on").
 pub fn toggle_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    toggle(Target::Point(x, y))
+}
+
+/// Toggle the `TogglePattern` on the element with `automation_id` in
+/// `window_handle`.  See [`toggle_at`].
+pub fn toggle_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    toggle(Target::AutomationId { window: window_handle, id: automation_id })
+}
+
+fn toggle(target: Target<'_>) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -144,12 +394,7 @@ pub fn toggle_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 .map_err(|e| WindowsMcpError::TreeError(format!("Toggle failed: {e}")))?;
 
             let state = unsafe { p.CurrentToggleState() }.unwrap_or_default();
-            let state_name = match state.0 {
-                0 => "off",
-                1 => "on",
-                2 => "indeterminate",
-                _ => "unknown",
-            };
+            let state_name = toggle_state_name(state.0);
 
             Ok(PatternResult {
                 element_name: name,
@@ -157,6 +402,7 @@ pub fn toggle_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 action: "toggle".into(),
                 success: true,
                 detail: format!("State: {state_name}"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(&name, &etype, "toggle", "TogglePattern")),
@@ -165,9 +411,38 @@ pub fn toggle_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
 
 /// Set a value via `ValuePattern` on the element at `(x, y)`.
 pub fn set_value_at(x: i32, y: i32, value: &str) -> Result<PatternResult, WindowsMcpError> {
+    set_value(Target::Point(x, y), value)
+}
+
+/// Set a value via `ValuePattern` on the element with `automation_id` in
+/// `window_handle`.
+pub fn set_value_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+    value: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    set_value(Target::AutomationId { window: window_handle, id: automation_id }, value)
+}
+
+/// Characters of a set value echoed back in [`PatternResult::detail`].
+const VALUE_PREVIEW_CHARS: usize = 50;
+
+/// The first [`VALUE_PREVIEW_CHARS`] characters of `value`, with `...`
+/// appended when it was cut.  Counts characters, not bytes, so multi-byte
+/// text is never split mid-character.
+fn value_preview(value: &str) -> String {
+    let mut chars = value.chars();
+    let mut preview: String = chars.by_ref().take(VALUE_PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        preview.push_str("...");
+    }
+    preview
+}
+
+fn set_value(target: Target<'_>, value: &str) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -184,11 +459,7 @@ pub fn set_value_at(x: i32, y: i32, value: &str) -> Result<PatternResult, Window
             unsafe { p.SetValue(&bstr) }
                 .map_err(|e| WindowsMcpError::TreeError(format!("SetValue failed: {e}")))?;
 
-            let preview = if value.len() > 50 {
-                format!("{}...", &value[..50])
-            } else {
-                value.to_owned()
-            };
+            let preview = value_preview(value);
 
             Ok(PatternResult {
                 element_name: name,
@@ -196,17 +467,203 @@ pub fn set_value_at(x: i32, y: i32, value: &str) -> Result<PatternResult, Window
                 action: "set_value".into(),
                 success: true,
                 detail: format!("Value set to '{preview}'"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(&name, &etype, "set_value", "ValuePattern")),
     }
 }
 
+/// Append `text` to the current value via `ValuePattern` on the element at
+/// `(x, y)`.
+///
+/// Reads `CurrentValue`, concatenates `text` and writes the result back
+/// with `SetValue`, so existing text (e.g. in a search box) is kept.  The
+/// combined length in characters is reported in `detail`.  Fails the same
+/// way as [`set_value_at`]: `success: false` when the element lacks
+/// `ValuePattern`, and a `TreeError` when `SetValue` is rejected (e.g. a
+/// read-only field).
+pub fn append_value_at(x: i32, y: i32, text: &str) -> Result<PatternResult, WindowsMcpError> {
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let pattern: Option<IUIAutomationValuePattern> = unsafe {
+        element
+            .GetCurrentPattern(UIA_ValuePatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationValuePattern>().ok())
+    };
+
+    match pattern {
+        Some(p) => {
+            let current = unsafe { p.CurrentValue() }
+                .map_err(|e| WindowsMcpError::TreeError(format!("CurrentValue failed: {e}")))?;
+            let combined = format!("{current}{text}");
+
+            let bstr = windows::core::BSTR::from(combined.as_str());
+            unsafe { p.SetValue(&bstr) }
+                .map_err(|e| WindowsMcpError::TreeError(format!("SetValue failed: {e}")))?;
+
+            Ok(PatternResult {
+                element_name: name,
+                element_type: etype,
+                action: "append_value".into(),
+                success: true,
+                detail: format!("Value length now {}", combined.chars().count()),
+                is_read_only: None,
+            })
+        }
+        None => Ok(pattern_not_supported(&name, &etype, "append_value", "ValuePattern")),
+    }
+}
+
+/// Focus the element at `(x, y)` and type `text` into it.
+///
+/// Unlike [`crate::input::send_text_raw`], which types into whatever has
+/// focus, this first checks `CurrentIsKeyboardFocusable` and calls
+/// `SetFocus`.  If the element is not focusable or refuses focus, no text
+/// is sent and `success` is `false` with the reason in `detail`; otherwise
+/// `detail` reports the number of input events injected.
+///
+/// Returns an `InputError` if `text` exceeds the `send_text_raw` length
+/// limit (checked before focusing) or `SendInput` is blocked.
+pub fn send_text_to_element(x: i32, y: i32, text: &str) -> Result<PatternResult, WindowsMcpError> {
+    crate::input::check_text_length(text.encode_utf16().count())?;
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let failed = |detail: String| PatternResult {
+        element_name: name.clone(),
+        element_type: etype.clone(),
+        action: "send_text".into(),
+        success: false,
+        detail,
+        is_read_only: None,
+    };
+
+    let focusable = unsafe { element.CurrentIsKeyboardFocusable() }
+        .map(|b| b.as_bool())
+        .unwrap_or(false);
+    if !focusable {
+        return Ok(failed(format!("Element at ({x},{y}) is not keyboard focusable")));
+    }
+    if let Err(e) = unsafe { element.SetFocus() } {
+        return Ok(failed(format!("SetFocus failed: {e}")));
+    }
+
+    let sent = crate::input::send_text_raw(text)?;
+    Ok(PatternResult {
+        element_name: name,
+        element_type: etype,
+        action: "send_text".into(),
+        success: true,
+        detail: format!("Focused element and sent {sent} events"),
+        is_read_only: None,
+    })
+}
+
+/// Replace the text of the field at `(x, y)`: click it, select all with
+/// Ctrl+A, delete the selection, then type `text`.
+///
+/// This uses input injection rather than `ValuePattern`, so it works on
+/// controls that lack the pattern, but it acts on whatever takes focus
+/// from the click.  UIA is only used to name the element in the result;
+/// `detail` reports the number of input events injected.  An empty `text`
+/// just clears the field.
+///
+/// Returns an `InputError` if `text` exceeds the `send_text_raw` length
+/// limit (checked before clicking) or `SendInput` is blocked.
+pub fn replace_text_at(x: i32, y: i32, text: &str) -> Result<PatternResult, WindowsMcpError> {
+    const VK_CONTROL: u16 = 0x11;
+    const VK_A: u16 = 0x41;
+    const VK_DELETE: u16 = 0x2E;
+
+    crate::input::check_text_length(text.encode_utf16().count())?;
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let mut sent = crate::input::send_click_raw(x, y, "left")?;
+    sent += crate::input::send_hotkey_raw(&[VK_CONTROL, VK_A])?;
+    sent += crate::input::send_key_raw(VK_DELETE, false)?;
+    sent += crate::input::send_key_raw(VK_DELETE, true)?;
+    if !text.is_empty() {
+        sent += crate::input::send_text_raw(text)?;
+    }
+
+    Ok(PatternResult {
+        element_name: name,
+        element_type: etype,
+        action: "replace_text".into(),
+        success: true,
+        detail: format!("Cleared field and sent {sent} events"),
+        is_read_only: None,
+    })
+}
+
+/// Read the current value via `ValuePattern` on the element at `(x, y)`.
+///
+/// Returns the value in `detail` and the pattern's read-only flag in
+/// `is_read_only`, so a form field can be checked without a tree capture.
+pub fn get_value_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let pattern: Option<IUIAutomationValuePattern> = unsafe {
+        element
+            .GetCurrentPattern(UIA_ValuePatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationValuePattern>().ok())
+    };
+
+    match pattern {
+        Some(p) => {
+            let value = unsafe { p.CurrentValue() }
+                .map_err(|e| WindowsMcpError::TreeError(format!("CurrentValue failed: {e}")))?;
+            let read_only = unsafe { p.CurrentIsReadOnly() }.map(|b| b.as_bool()).ok();
+
+            Ok(PatternResult {
+                element_name: name,
+                element_type: etype,
+                action: "get_value".into(),
+                success: true,
+                detail: value.to_string(),
+                is_read_only: read_only,
+            })
+        }
+        None => Ok(pattern_not_supported(&name, &etype, "get_value", "ValuePattern")),
+    }
+}
+
 /// Expand via `ExpandCollapsePattern` on the element at `(x, y)`.
 pub fn expand_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    expand(Target::Point(x, y))
+}
+
+/// Expand via `ExpandCollapsePattern` on the element with `automation_id` in
+/// `window_handle`.
+pub fn expand_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    expand(Target::AutomationId { window: window_handle, id: automation_id })
+}
+
+fn expand(target: Target<'_>) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -226,7 +683,8 @@ pub fn expand_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 element_type: etype,
                 action: "expand".into(),
                 success: true,
-                detail: format!("Expanded at ({x},{y})"),
+                detail: format!("Expanded {target}"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(
@@ -240,9 +698,22 @@ pub fn expand_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
 
 /// Collapse via `ExpandCollapsePattern` on the element at `(x, y)`.
 pub fn collapse_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    collapse(Target::Point(x, y))
+}
+
+/// Collapse via `ExpandCollapsePattern` on the element with `automation_id` in
+/// `window_handle`.
+pub fn collapse_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    collapse(Target::AutomationId { window: window_handle, id: automation_id })
+}
+
+fn collapse(target: Target<'_>) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -262,7 +733,8 @@ pub fn collapse_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 element_type: etype,
                 action: "collapse".into(),
                 success: true,
-                detail: format!("Collapsed at ({x},{y})"),
+                detail: format!("Collapsed {target}"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(
@@ -276,9 +748,22 @@ pub fn collapse_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
 
 /// Select via `SelectionItemPattern` on the element at `(x, y)`.
 pub fn select_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
+    select(Target::Point(x, y))
+}
+
+/// Select via `SelectionItemPattern` on the element with `automation_id` in
+/// `window_handle`.
+pub fn select_by_automation_id(
+    window_handle: isize,
+    automation_id: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    select(Target::AutomationId { window: window_handle, id: automation_id })
+}
+
+fn select(target: Target<'_>) -> Result<PatternResult, WindowsMcpError> {
     let _com = COMGuard::init()?;
 
-    let (_uia, element) = unsafe { element_at(x, y)? };
+    let (_uia, element) = unsafe { locate(target)? };
     let name = unsafe { elem_name(&element) };
     let etype = unsafe { elem_type(&element) };
 
@@ -298,7 +783,8 @@ pub fn select_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
                 element_type: etype,
                 action: "select".into(),
                 success: true,
-                detail: format!("Selected at ({x},{y})"),
+                detail: format!("Selected {target}"),
+                is_read_only: None,
             })
         }
         None => Ok(pattern_not_supported(
@@ -310,6 +796,223 @@ pub fn select_at(x: i32, y: i32) -> Result<PatternResult, WindowsMcpError> {
     }
 }
 
+/// Scroll the container at `(x, y)` to absolute positions via
+/// `ScrollPattern.SetScrollPercent`.
+///
+/// Each percent is `0.0..=100.0`, or `-1.0` (`UIA_ScrollPatternNoScroll`)
+/// to leave that axis alone.  Unlike `send_scroll`, which injects mouse
+/// wheel notches that land on whatever is under the pointer and move by an
+/// app-defined step, this addresses the element directly, jumps to an
+/// exact position, and does not move the mouse.  Returns the resulting
+/// percentages in `detail`.
+pub fn scroll_pattern_at(
+    x: i32,
+    y: i32,
+    horizontal_percent: f64,
+    vertical_percent: f64,
+) -> Result<PatternResult, WindowsMcpError> {
+    if !valid_scroll_percent(horizontal_percent) || !valid_scroll_percent(vertical_percent) {
+        return Err(WindowsMcpError::TreeError(format!(
+            "Scroll percents must be 0-100 or -1 (no scroll); got \
+             ({horizontal_percent}, {vertical_percent})"
+        )));
+    }
+
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let pattern: Option<IUIAutomationScrollPattern> = unsafe {
+        element
+            .GetCurrentPattern(UIA_ScrollPatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationScrollPattern>().ok())
+    };
+
+    match pattern {
+        Some(p) => {
+            unsafe { p.SetScrollPercent(horizontal_percent, vertical_percent) }.map_err(|e| {
+                WindowsMcpError::TreeError(format!("SetScrollPercent failed: {e}"))
+            })?;
+
+            let h = unsafe { p.CurrentHorizontalScrollPercent() };
+            let v = unsafe { p.CurrentVerticalScrollPercent() };
+            let (h, v) = (
+                h.unwrap_or(UIA_ScrollPatternNoScroll),
+                v.unwrap_or(UIA_ScrollPatternNoScroll),
+            );
+
+            Ok(PatternResult {
+                element_name: name,
+                element_type: etype,
+                action: "scroll".into(),
+                success: true,
+                detail: format!("Scroll position: horizontal {h:.1}%, vertical {v:.1}%"),
+                is_read_only: None,
+            })
+        }
+        None => Ok(pattern_not_supported(&name, &etype, "scroll", "ScrollPattern")),
+    }
+}
+
+/// Maximize, minimize, restore or close the window containing `(x, y)`
+/// via UIA `WindowPattern`.
+///
+/// `action` is `"maximize"`, `"minimize"`, `"normal"` or `"close"`
+/// (case-insensitive).  The pattern lives on the window element, so the
+/// element under the point and then its ancestors are searched for it.
+/// UWP and WPF apps that ignore `ShowWindow` (see
+/// [`crate::window::set_window_state`]) usually honour this.  Returns the
+/// window's `WindowInteractionState` afterwards in `detail`.
+pub fn window_pattern_action_at(
+    x: i32,
+    y: i32,
+    action: &str,
+) -> Result<PatternResult, WindowsMcpError> {
+    let window_action = window_action(action).ok_or_else(|| {
+        WindowsMcpError::TreeError(format!(
+            "Unknown window action '{action}'; expected maximize, minimize, normal or close"
+        ))
+    })?;
+
+    let _com = COMGuard::init()?;
+
+    let (uia, element) = unsafe { element_at(x, y)? };
+    let Some((window, p)) = (unsafe { window_pattern_ancestor(&uia, element.clone()) }) else {
+        let name = unsafe { elem_name(&element) };
+        let etype = unsafe { elem_type(&element) };
+        return Ok(pattern_not_supported(&name, &etype, "window", "WindowPattern"));
+    };
+    let name = unsafe { elem_name(&window) };
+    let etype = unsafe { elem_type(&window) };
+
+    let verb = match window_action {
+        WindowAction::SetState(state) => {
+            unsafe { p.SetWindowVisualState(state) }.map_err(|e| {
+                WindowsMcpError::TreeError(format!("SetWindowVisualState failed: {e}"))
+            })?;
+            if state == WindowVisualState_Maximized {
+                "maximized"
+            } else if state == WindowVisualState_Minimized {
+                "minimized"
+            } else {
+                "restored"
+            }
+        }
+        WindowAction::Close => {
+            unsafe { p.Close() }
+                .map_err(|e| WindowsMcpError::TreeError(format!("Close failed: {e}")))?;
+            "closed"
+        }
+    };
+
+    // A closed window may already be gone, leaving the state unreadable.
+    let state = unsafe { p.CurrentWindowInteractionState() }
+        .map(|s| interaction_state_name(s.0))
+        .unwrap_or("unknown");
+
+    Ok(PatternResult {
+        element_name: name,
+        element_type: etype,
+        action: "window".into(),
+        success: true,
+        detail: format!("Window {verb}; interaction state: {state}"),
+        is_read_only: None,
+    })
+}
+
+/// Read the text of the element at `(x, y)` via `TextPattern`.
+///
+/// Rich edit controls, browsers and document views often leave
+/// `ValuePattern` empty; `TextPattern` exposes their content.  Returns the
+/// whole document range and the current selection.
+pub fn get_text_at(x: i32, y: i32) -> Result<TextResult, WindowsMcpError> {
+    let _com = COMGuard::init()?;
+
+    let (_uia, element) = unsafe { element_at(x, y)? };
+    let name = unsafe { elem_name(&element) };
+    let etype = unsafe { elem_type(&element) };
+
+    let pattern: Option<IUIAutomationTextPattern> = unsafe {
+        element
+            .GetCurrentPattern(UIA_TextPatternId)
+            .ok()
+            .and_then(|p| p.cast::<IUIAutomationTextPattern>().ok())
+    };
+
+    let Some(p) = pattern else {
+        let unsupported = pattern_not_supported(&name, &etype, "get_text", "TextPattern");
+        return Ok(TextResult {
+            element_name: name,
+            element_type: etype,
+            success: false,
+            detail: unsupported.detail,
+            full_text: String::new(),
+            selected_text: String::new(),
+        });
+    };
+
+    let full_text = unsafe { p.DocumentRange().and_then(|range| range.GetText(-1)) }
+        .map_err(|e| WindowsMcpError::TreeError(format!("TextPattern.DocumentRange failed: {e}")))?
+        .to_string();
+    // Controls without a selection concept may fail GetSelection; treat
+    // that as nothing selected.
+    let selected_text = unsafe { p.GetSelection() }
+        .map(|ranges| unsafe { ranges_text(&ranges) })
+        .unwrap_or_default();
+
+    Ok(TextResult {
+        element_name: name,
+        element_type: etype,
+        success: true,
+        detail: format!(
+            "Read {} chars ({} selected)",
+            full_text.chars().count(),
+            selected_text.chars().count()
+        ),
+        full_text,
+        selected_text,
+    })
+}
+
+/// Read the cell at zero-based `(row, column)` of the grid (data grid,
+/// table, spreadsheet) at `(x, y)` via `GridPattern.GetItem`.
+///
+/// `(x, y)` may be on the grid itself or on anything inside it, such as a
+/// cell.  Returns a `TreeError` if neither the element there nor any
+/// ancestor supports `GridPattern`, or if the cell is out of range (see
+/// [`get_grid_size_at`]).  Virtualized grids may only return cells that
+/// have been scrolled into view.
+pub fn get_grid_cell_at(
+    x: i32,
+    y: i32,
+    row: i32,
+    column: i32,
+) -> Result<ElementInfo, WindowsMcpError> {
+    let _com = COMGuard::init()?;
+
+    let (grid, rows, columns) = unsafe { grid_at(x, y)? };
+    check_grid_cell(row, column, rows, columns)?;
+
+    let cell = unsafe { grid.GetItem(row, column) }.map_err(|e| {
+        WindowsMcpError::TreeError(format!("GridPattern.GetItem({row},{column}) failed: {e}"))
+    })?;
+    Ok(unsafe { read_element_info(&cell) })
+}
+
+/// Read the `(rows, columns)` of the grid at `(x, y)` via `GridPattern`.
+///
+/// Finds the grid as [`get_grid_cell_at`] does, with the same error when
+/// there is none.
+pub fn get_grid_size_at(x: i32, y: i32) -> Result<(i32, i32), WindowsMcpError> {
+    let _com = COMGuard::init()?;
+
+    let (_grid, rows, columns) = unsafe { grid_at(x, y)? };
+    Ok((rows, columns))
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -326,12 +1029,21 @@ mod tests {
             action: "invoke".into(),
             success: true,
             detail: "Invoked at (100,200)".into(),
+            is_read_only: None,
         };
         let json = serde_json::to_string(&r).unwrap();
         assert!(json.contains("\"success\":true"));
         assert!(json.contains("OK Button"));
     }
 
+    #[test]
+    fn test_pattern_result_read_only_serialization() {
+        let mut r = pattern_not_supported("Name", "Edit", "get_value", "ValuePattern");
+        assert!(!serde_json::to_string(&r).unwrap().contains("is_read_only"));
+        r.is_read_only = Some(true);
+        assert!(serde_json::to_string(&r).unwrap().contains("\"is_read_only\":true"));
+    }
+
     #[test]
     fn test_pattern_result_failure() {
         let r = pattern_not_supported("test", "Button", "toggle", "TogglePattern");
@@ -347,19 +1059,95 @@ mod tests {
             action: "toggle".into(),
             success: true,
             detail: "State: on".into(),
+            is_read_only: None,
         };
         assert_eq!(r.detail, "State: on");
     }
 
     #[test]
-    fn test_set_value_preview_truncation() {
-        let long_value = "a".repeat(100);
-        let preview = if long_value.len() > 50 {
-            format!("{}...", &long_value[..50])
-        } else {
-            long_value.clone()
+    fn test_toggle_state_name() {
+        assert_eq!(toggle_state_name(0), "off");
+        assert_eq!(toggle_state_name(1), "on");
+        assert_eq!(toggle_state_name(2), "indeterminate");
+        assert_eq!(toggle_state_name(7), "unknown");
+    }
+
+    #[test]
+    fn test_check_grid_cell() {
+        assert!(check_grid_cell(0, 0, 3, 2).is_ok());
+        assert!(check_grid_cell(2, 1, 3, 2).is_ok());
+        assert!(check_grid_cell(3, 0, 3, 2).is_err());
+        assert!(check_grid_cell(0, -1, 3, 2).is_err());
+        let err = check_grid_cell(0, 0, 0, 0).unwrap_err();
+        assert!(err.to_string().contains("outside the 0x0 grid"));
+    }
+
+    #[test]
+    fn test_window_action() {
+        assert_eq!(
+            window_action("Maximize"),
+            Some(WindowAction::SetState(WindowVisualState_Maximized))
+        );
+        assert_eq!(
+            window_action("normal"),
+            Some(WindowAction::SetState(WindowVisualState_Normal))
+        );
+        assert_eq!(window_action("CLOSE"), Some(WindowAction::Close));
+        assert_eq!(window_action("restore"), None);
+    }
+
+    #[test]
+    fn test_interaction_state_name() {
+        assert_eq!(interaction_state_name(0), "running");
+        assert_eq!(interaction_state_name(3), "blocked by modal window");
+        assert_eq!(interaction_state_name(9), "unknown");
+    }
+
+    #[test]
+    fn test_text_result_serialization() {
+        let r = TextResult {
+            element_name: "Document".into(),
+            element_type: "Document".into(),
+            success: true,
+            detail: "Read 11 chars (5 selected)".into(),
+            full_text: "hello world".into(),
+            selected_text: "world".into(),
         };
+        let json = serde_json::to_string(&r).unwrap();
+        assert!(json.contains("\"full_text\":\"hello world\""));
+        assert!(json.contains("\"selected_text\":\"world\""));
+    }
+
+    #[test]
+    fn test_target_display() {
+        assert_eq!(Target::Point(10, -20).to_string(), "at (10,-20)");
+        let by_id = Target::AutomationId { window: 0x1234, id: "okButton" };
+        assert_eq!(by_id.to_string(), "by AutomationId 'okButton'");
+    }
+
+    #[test]
+    fn test_valid_scroll_percent() {
+        assert!(valid_scroll_percent(0.0));
+        assert!(valid_scroll_percent(100.0));
+        assert!(valid_scroll_percent(UIA_ScrollPatternNoScroll));
+        assert!(!valid_scroll_percent(100.5));
+        assert!(!valid_scroll_percent(-0.5));
+        assert!(!valid_scroll_percent(f64::NAN));
+    }
+
+    #[test]
+    fn test_set_value_preview_truncation() {
+        let preview = value_preview(&"a".repeat(100));
         assert_eq!(preview.len(), 53); // 50 chars + "..."
         assert!(preview.ends_with("..."));
+
+        assert_eq!(value_preview(&"a".repeat(50)), "a".repeat(50));
+
+        // 17 CJK characters put byte 50 inside a character.
+        let cjk = "\u{6F22}".repeat(60);
+        let preview = value_preview(&cjk);
+        assert_eq!(preview.chars().count(), 53);
+        assert!(preview.starts_with(&"\u{6F22}".repeat(50)));
+        assert!(preview.ends_with("..."));
     }
 }
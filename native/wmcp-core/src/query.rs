@@ -8,15 +8,21 @@
 //! Each function initialises its own MTA COM apartment via [`COMGuard`].
 //! COM interfaces are never shared across function boundaries.
 
-use serde::Serialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use windows::core::Interface;
 use windows::Win32::Foundation::{HWND, POINT};
 use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+use windows::Win32::System::Ole::{
+    SafeArrayDestroy, SafeArrayGetElement, SafeArrayGetLBound, SafeArrayGetUBound,
+};
 use windows::Win32::UI::Accessibility::{
-    CUIAutomation, IUIAutomation, IUIAutomationCondition, IUIAutomationElement,
-    TreeScope_Descendants, UIA_AutomationIdPropertyId, UIA_ControlTypePropertyId,
-    UIA_ExpandCollapsePatternId, UIA_InvokePatternId, UIA_SelectionItemPatternId,
-    UIA_TogglePatternId, UIA_ValuePatternId,
+    CUIAutomation, IUIAutomation, IUIAutomationCondition, IUIAutomationElement, TreeScope,
+    TreeScope_Children, TreeScope_Descendants, TreeScope_Subtree, UIA_AutomationIdPropertyId,
+    UIA_ClassNamePropertyId, UIA_ControlTypePropertyId, UIA_ExpandCollapsePatternId,
+    UIA_GridPatternId, UIA_InvokePatternId, UIA_RangeValuePatternId, UIA_ScrollPatternId,
+    UIA_SelectionItemPatternId, UIA_TextPatternId, UIA_TogglePatternId, UIA_ValuePatternId,
+    UIA_WindowPatternId,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
     GetSystemMetrics, SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN,
@@ -29,18 +35,30 @@ use crate::tree::control_type_name;
 /// Maximum number of results from `find_elements`.
 const MAX_FIND_LIMIT: usize = 100;
 
-/// UIA pattern IDs to probe for `supported_patterns`.
+/// UIA pattern IDs probed by [`get_supported_patterns_at`].
 ///
 /// Stores the raw i32 pattern IDs (used with `GetCurrentPattern` which
-/// takes `UIA_PATTERN_ID` -- a newtype around i32).
+/// takes `UIA_PATTERN_ID` -- a newtype around i32).  Each probe is a
+/// cross-process call, so [`ElementInfo::supported_patterns`] only probes
+/// the first [`ELEMENT_INFO_PROBES`] entries.
 const PATTERN_PROBES: &[(i32, &str)] = &[
     (UIA_InvokePatternId.0, "InvokePattern"),
     (UIA_TogglePatternId.0, "TogglePattern"),
     (UIA_ValuePatternId.0, "ValuePattern"),
     (UIA_ExpandCollapsePatternId.0, "ExpandCollapsePattern"),
     (UIA_SelectionItemPatternId.0, "SelectionItemPattern"),
+    (UIA_ScrollPatternId.0, "ScrollPattern"),
+    (UIA_RangeValuePatternId.0, "RangeValuePattern"),
+    (UIA_GridPatternId.0, "GridPattern"),
+    (UIA_TextPatternId.0, "TextPattern"),
+    (UIA_WindowPatternId.0, "WindowPattern"),
 ];
 
+/// Leading [`PATTERN_PROBES`] entries (Invoke through SelectionItem)
+/// probed for every [`ElementInfo`], keeping bulk reads such as
+/// `find_elements_in_rect` at five probes per element.
+const ELEMENT_INFO_PROBES: usize = 5;
+
 // ---------------------------------------------------------------------------
 // Data structures
 // ---------------------------------------------------------------------------
@@ -48,8 +66,9 @@ const PATTERN_PROBES: &[(i32, &str)] = &[
 /// An owned, COM-free snapshot of a single UIA element's properties.
 ///
 /// All strings are UTF-8.  `bounding_rect` stores `[left, top, right, bottom]`
-/// as `f64` to match the Python convention.
-#[derive(Debug, Clone, Serialize)]
+/// as `f64` to match the Python convention.  `center` is its midpoint in
+/// physical screen pixels, ready for `send_click`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ElementInfo {
     pub name: String,
     pub automation_id: String,
@@ -57,23 +76,94 @@ pub struct ElementInfo {
     pub localized_control_type: String,
     pub class_name: String,
     pub bounding_rect: [f64; 4],
+    pub center: [i32; 2],
+    /// UIA runtime ID: unique among live elements for the session, but
+    /// reusable once the element is destroyed.  Empty if unavailable.
+    pub runtime_id: Vec<i32>,
     pub is_enabled: bool,
     pub is_offscreen: bool,
     pub has_keyboard_focus: bool,
+    /// Supported action patterns among Invoke, Toggle, Value,
+    /// ExpandCollapse and SelectionItem; see [`get_supported_patterns_at`]
+    /// for the full probe.
     pub supported_patterns: Vec<String>,
 }
 
+/// The element at a point together with its neighbours in the UIA control
+/// view, from [`get_element_relatives`].  Each relative is `None` when it
+/// does not exist (e.g. no children, or the first sibling has no
+/// `previous_sibling`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ElementRelatives {
+    pub element: ElementInfo,
+    pub parent: Option<ElementInfo>,
+    pub first_child: Option<ElementInfo>,
+    pub last_child: Option<ElementInfo>,
+    pub next_sibling: Option<ElementInfo>,
+    pub previous_sibling: Option<ElementInfo>,
+}
+
+/// How far below the root [`find_elements`] searches.
+///
+/// Cost grows with the number of elements UIA has to visit, and each
+/// visit is a cross-process call into the target app.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FindScope {
+    /// Direct children of the root only.  Cheapest: one level, so use it
+    /// when the target is known to sit just under the window.
+    Children,
+    /// Every element below the root.  Walks the whole subtree, which can
+    /// take seconds on large browser or IDE windows.
+    #[default]
+    Descendants,
+    /// Like `Descendants`, but the root itself can also match.
+    Subtree,
+}
+
+impl FindScope {
+    /// Parse `"children"`, `"descendants"` or `"subtree"` (case-insensitive).
+    pub fn from_name(name: &str) -> Result<Self, WindowsMcpError> {
+        match name.to_ascii_lowercase().as_str() {
+            "children" => Ok(Self::Children),
+            "descendants" => Ok(Self::Descendants),
+            "subtree" => Ok(Self::Subtree),
+            _ => Err(WindowsMcpError::TreeError(format!(
+                "Unknown find scope '{name}'; expected children, descendants or subtree"
+            ))),
+        }
+    }
+
+    fn tree_scope(self) -> TreeScope {
+        match self {
+            Self::Children => TreeScope_Children,
+            Self::Descendants => TreeScope_Descendants,
+            Self::Subtree => TreeScope_Subtree,
+        }
+    }
+}
+
 /// Criteria for [`find_elements`].
 #[derive(Debug, Clone, Default)]
 pub struct FindCriteria {
     /// Substring match on element name (case-insensitive).
     pub name: Option<String>,
+    /// Regular expression matched against the element name, applied in
+    /// addition to `name` when both are set.  Case-sensitive unless the
+    /// pattern starts with `(?i)`.
+    pub name_regex: Option<String>,
     /// Exact match on control type name (e.g. "Button").
     pub control_type: Option<String>,
-    /// Exact match on AutomationId.
+    /// Match on AutomationId; exact unless `substring` is set.
     pub automation_id: Option<String>,
+    /// Match on class name; exact unless `substring` is set.
+    pub class_name: Option<String>,
+    /// Match `automation_id` and `class_name` as case-insensitive
+    /// substrings, filtered client-side, instead of exact UIA conditions.
+    pub substring: bool,
     /// Scope search to a specific window handle.
     pub window_handle: Option<isize>,
+    /// How deep below the root to search.
+    pub scope: FindScope,
     /// Maximum results (clamped to [`MAX_FIND_LIMIT`]).
     pub limit: usize,
 }
@@ -91,8 +181,49 @@ pub struct ScreenMetrics {
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Midpoint of a `[left, top, right, bottom]` rect, rounded down.
+fn rect_center(rect: &[f64; 4]) -> [i32; 2] {
+    [
+        ((rect[0] + rect[2]) / 2.0).floor() as i32,
+        ((rect[1] + rect[3]) / 2.0).floor() as i32,
+    ]
+}
+
+/// Read an element's runtime ID, or an empty vec if UIA cannot supply one.
+unsafe fn read_runtime_id(element: &IUIAutomationElement) -> Vec<i32> {
+    let Ok(array) = element.GetRuntimeId() else {
+        return Vec::new();
+    };
+    if array.is_null() {
+        return Vec::new();
+    }
+
+    let mut id = Vec::new();
+    if let (Ok(lower), Ok(upper)) = (SafeArrayGetLBound(array, 1), SafeArrayGetUBound(array, 1)) {
+        for index in lower..=upper {
+            let mut value = 0i32;
+            let out = &mut value as *mut i32 as *mut core::ffi::c_void;
+            if SafeArrayGetElement(array, &index, out).is_ok() {
+                id.push(value);
+            }
+        }
+    }
+    let _ = SafeArrayDestroy(array);
+    id
+}
+
 /// Read common properties from a live UIA element into an owned [`ElementInfo`].
-unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
+pub(crate) unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
+    let mut info = read_element_properties(element);
+    info.supported_patterns = probe_patterns(element, &PATTERN_PROBES[..ELEMENT_INFO_PROBES]);
+    info
+}
+
+/// [`read_element_info`] without the pattern probes, which cost one
+/// cross-process `GetCurrentPattern` call per [`PATTERN_PROBES`] entry;
+/// `supported_patterns` is left empty.  Used to filter search candidates
+/// before paying for the probes.
+unsafe fn read_element_properties(element: &IUIAutomationElement) -> ElementInfo {
     let name = element
         .CurrentName()
         .map(|b| b.to_string())
@@ -118,6 +249,8 @@ unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
         .CurrentBoundingRectangle()
         .map(|r| [r.left as f64, r.top as f64, r.right as f64, r.bottom as f64])
         .unwrap_or([0.0, 0.0, 0.0, 0.0]);
+    let center = rect_center(&bounding_rect);
+    let runtime_id = read_runtime_id(element);
 
     let is_enabled = element
         .CurrentIsEnabled()
@@ -132,18 +265,6 @@ unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
         .map(|b| b.as_bool())
         .unwrap_or(false);
 
-    // Probe supported patterns -- GetCurrentPattern returns Err if unsupported
-    let mut supported_patterns = Vec::new();
-    for &(pattern_id, pattern_name) in PATTERN_PROBES {
-        use windows::Win32::UI::Accessibility::UIA_PATTERN_ID;
-        if element
-            .GetCurrentPattern(UIA_PATTERN_ID(pattern_id))
-            .is_ok()
-        {
-            supported_patterns.push(pattern_name.to_owned());
-        }
-    }
-
     ElementInfo {
         name,
         automation_id,
@@ -151,13 +272,27 @@ unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
         localized_control_type,
         class_name,
         bounding_rect,
+        center,
+        runtime_id,
         is_enabled,
         is_offscreen,
         has_keyboard_focus,
-        supported_patterns,
+        supported_patterns: Vec::new(),
     }
 }
 
+/// Names of the `probes` patterns `element` supports, in probe order.
+unsafe fn probe_patterns(element: &IUIAutomationElement, probes: &[(i32, &str)]) -> Vec<String> {
+    use windows::Win32::UI::Accessibility::UIA_PATTERN_ID;
+
+    // GetCurrentPattern returns Err if unsupported
+    probes
+        .iter()
+        .filter(|&&(pattern_id, _)| element.GetCurrentPattern(UIA_PATTERN_ID(pattern_id)).is_ok())
+        .map(|&(_, pattern_name)| pattern_name.to_owned())
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -167,6 +302,7 @@ unsafe fn read_element_info(element: &IUIAutomationElement) -> ElementInfo {
 /// Returns an [`ElementInfo`] with all commonly needed properties, or an
 /// error if no element is found or COM fails.
 pub fn element_from_point(x: i32, y: i32) -> Result<ElementInfo, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
     let _com = COMGuard::init()?;
 
     let uia: IUIAutomation = unsafe {
@@ -183,6 +319,67 @@ pub fn element_from_point(x: i32, y: i32) -> Result<ElementInfo, WindowsMcpError
     Ok(info)
 }
 
+/// List the control patterns supported by the element at the given screen
+/// coordinates, e.g. `["InvokePattern", "ScrollPattern"]`.
+///
+/// Probes every [`PATTERN_PROBES`] entry, a superset of
+/// [`ElementInfo::supported_patterns`], without reading the other
+/// properties, for deciding how to act on an element cheaply.
+/// Returns an error if no element is found or COM fails.
+pub fn get_supported_patterns_at(x: i32, y: i32) -> Result<Vec<String>, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let _com = COMGuard::init()?;
+
+    let uia: IUIAutomation =
+        unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)? };
+
+    let element: IUIAutomationElement = unsafe {
+        uia.ElementFromPoint(POINT { x, y })
+            .map_err(|e| WindowsMcpError::TreeError(format!("ElementFromPoint({x},{y}): {e}")))?
+    };
+
+    Ok(unsafe { probe_patterns(&element, PATTERN_PROBES) })
+}
+
+/// Query the UIA element at the given screen coordinates together with its
+/// parent, first and last child, and next and previous sibling.
+///
+/// Navigates the control view (`ControlViewWalker`), which skips the
+/// layout-only elements of the raw view, so relatives are the ones a user
+/// would recognise.  Lets callers step around an element without
+/// capturing a whole subtree.
+pub fn get_element_relatives(x: i32, y: i32) -> Result<ElementRelatives, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let _com = COMGuard::init()?;
+
+    let uia: IUIAutomation = unsafe {
+        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)?
+    };
+    let element = unsafe {
+        uia.ElementFromPoint(POINT { x, y })
+            .map_err(|e| WindowsMcpError::TreeError(format!("ElementFromPoint({x},{y}): {e}")))?
+    };
+    let walker = unsafe {
+        uia.ControlViewWalker()
+            .map_err(|e| WindowsMcpError::TreeError(format!("ControlViewWalker: {e}")))?
+    };
+
+    // The walker reports "no such relative" as an error (null element).
+    let relative = |found: windows::core::Result<IUIAutomationElement>| {
+        found.ok().map(|e| unsafe { read_element_info(&e) })
+    };
+    unsafe {
+        Ok(ElementRelatives {
+            parent: relative(walker.GetParentElement(&element)),
+            first_child: relative(walker.GetFirstChildElement(&element)),
+            last_child: relative(walker.GetLastChildElement(&element)),
+            next_sibling: relative(walker.GetNextSiblingElement(&element)),
+            previous_sibling: relative(walker.GetPreviousSiblingElement(&element)),
+            element: read_element_info(&element),
+        })
+    }
+}
+
 /// Search for UIA elements matching the given criteria.
 ///
 /// If `criteria.window_handle` is set, the search is scoped to that window's
@@ -190,6 +387,10 @@ pub fn element_from_point(x: i32, y: i32) -> Result<ElementInfo, WindowsMcpError
 ///
 /// Returns up to `criteria.limit` matches (clamped to [`MAX_FIND_LIMIT`]).
 pub fn find_elements(criteria: &FindCriteria) -> Result<Vec<ElementInfo>, WindowsMcpError> {
+    // Reject a bad pattern before any UIA work.
+    let name_regex = compile_name_regex(criteria)?;
+
+    crate::dpi::ensure_per_monitor_aware();
     let _com = COMGuard::init()?;
 
     let uia: IUIAutomation = unsafe {
@@ -212,9 +413,8 @@ pub fn find_elements(criteria: &FindCriteria) -> Result<Vec<ElementInfo>, Window
         build_find_condition(&uia, criteria)?
     };
 
-    // FindAll with TreeScope_Descendants
     let elements = unsafe {
-        root.FindAll(TreeScope_Descendants, &condition)
+        root.FindAll(criteria.scope.tree_scope(), &condition)
             .map_err(|e| WindowsMcpError::TreeError(format!("FindAll: {e}")))?
     };
 
@@ -227,39 +427,200 @@ pub fn find_elements(criteria: &FindCriteria) -> Result<Vec<ElementInfo>, Window
             break;
         }
         if let Ok(elem) = unsafe { elements.GetElement(i) } {
-            let info = unsafe { read_element_info(&elem) };
-
-            // Apply name substring filter (case-insensitive) client-side
-            // since UIA PropertyCondition for Name is exact match only.
-            if let Some(ref name_filter) = criteria.name {
-                if !info.name.to_lowercase().contains(&name_filter.to_lowercase()) {
-                    continue;
-                }
+            // Probe patterns only for the candidates the filters keep.
+            let mut info = unsafe { read_element_properties(&elem) };
+            if matches_client_filters(&info, criteria, name_regex.as_ref()) {
+                info.supported_patterns =
+                    unsafe { probe_patterns(&elem, &PATTERN_PROBES[..ELEMENT_INFO_PROBES]) };
+                results.push(info);
             }
+        }
+    }
+
+    Ok(results)
+}
 
-            results.push(info);
+/// Poll [`find_elements`] until an element matches `criteria` or
+/// `timeout_ms` elapses, e.g. to wait for a dialog's Save button.
+///
+/// Returns the first match, or `None` on timeout.  Sleeps `poll_ms`
+/// (at least 1ms) between attempts and always makes at least one attempt,
+/// so `timeout_ms = 0` is a single check.  `criteria.limit` is ignored.
+/// Each attempt is a full `find_elements` call, which initialises COM and
+/// creates a UIA client afresh, so very short poll intervals cost more
+/// than the search alone.
+///
+/// # Errors
+///
+/// Returns the first error from [`find_elements`] (e.g. an invalid
+/// `name_regex` or window handle) instead of retrying.
+pub fn wait_for_element(
+    criteria: &FindCriteria,
+    timeout_ms: u64,
+    poll_ms: u64,
+) -> Result<Option<ElementInfo>, WindowsMcpError> {
+    let criteria = FindCriteria {
+        limit: 1,
+        ..criteria.clone()
+    };
+    let timeout = std::time::Duration::from_millis(timeout_ms);
+    let poll = std::time::Duration::from_millis(poll_ms.max(1));
+    let start = std::time::Instant::now();
+
+    loop {
+        if let Some(found) = find_elements(&criteria)?.into_iter().next() {
+            return Ok(Some(found));
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Ok(None);
         }
+        std::thread::sleep(poll.min(timeout - elapsed));
+    }
+}
+
+/// Search a window for elements whose bounding rect overlaps `rect`
+/// (`[left, top, right, bottom]` in physical screen pixels), e.g. every
+/// control inside a toolbar area.
+///
+/// Walks all descendants of `window_handle` with `FindAll`, keeping
+/// elements that overlap `rect` by at least one pixel and, when
+/// `control_types` is set, whose control type is in the list (matched
+/// case-insensitively).  Returns up to [`MAX_FIND_LIMIT`] matches in
+/// document order.
+///
+/// # Errors
+///
+/// Returns [`WindowsMcpError::TreeError`] if `rect` is empty or the
+/// window cannot be searched.
+pub fn find_elements_in_rect(
+    window_handle: isize,
+    rect: [i32; 4],
+    control_types: Option<Vec<String>>,
+) -> Result<Vec<ElementInfo>, WindowsMcpError> {
+    let [left, top, right, bottom] = rect;
+    if right <= left || bottom <= top {
+        return Err(WindowsMcpError::TreeError(format!(
+            "Empty search rect [{left}, {top}, {right}, {bottom}]"
+        )));
+    }
+
+    crate::dpi::ensure_per_monitor_aware();
+    let _com = COMGuard::init()?;
+
+    let uia: IUIAutomation = unsafe {
+        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)?
+    };
+    let root: IUIAutomationElement = unsafe {
+        uia.ElementFromHandle(HWND(window_handle as *mut core::ffi::c_void))
+            .map_err(|e| WindowsMcpError::TreeError(format!("ElementFromHandle: {e}")))?
+    };
+    let condition = unsafe {
+        uia.CreateTrueCondition()
+            .map_err(|e| WindowsMcpError::TreeError(format!("CreateTrueCondition: {e}")))?
+    };
+    let elements = unsafe {
+        root.FindAll(TreeScope_Descendants, &condition)
+            .map_err(|e| WindowsMcpError::TreeError(format!("FindAll: {e}")))?
+    };
+
+    let count = unsafe { elements.Length().unwrap_or(0) };
+    let mut results = Vec::new();
+    for i in 0..count {
+        if results.len() >= MAX_FIND_LIMIT {
+            break;
+        }
+        let Ok(elem) = (unsafe { elements.GetElement(i) }) else {
+            continue;
+        };
+        // Check the cheap properties before reading the full ElementInfo.
+        let Ok(bounds) = (unsafe { elem.CurrentBoundingRectangle() }) else {
+            continue;
+        };
+        if !rects_overlap([bounds.left, bounds.top, bounds.right, bounds.bottom], rect) {
+            continue;
+        }
+        if let Some(types) = &control_types {
+            let control_type = unsafe { elem.CurrentControlType() }
+                .map(control_type_name)
+                .unwrap_or("Unknown");
+            if !types.iter().any(|t| t.eq_ignore_ascii_case(control_type)) {
+                continue;
+            }
+        }
+        results.push(unsafe { read_element_info(&elem) });
     }
 
     Ok(results)
 }
 
+/// True when two `[left, top, right, bottom]` rects share at least one
+/// pixel; touching edges do not count.
+fn rects_overlap(a: [i32; 4], b: [i32; 4]) -> bool {
+    a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3]
+}
+
+/// Case-insensitive substring test; `needle` matches anything when `None`.
+fn contains_ignore_case(haystack: &str, needle: Option<&str>) -> bool {
+    needle.map_or(true, |n| haystack.to_lowercase().contains(&n.to_lowercase()))
+}
+
+/// Compile `criteria.name_regex`, if set.
+fn compile_name_regex(criteria: &FindCriteria) -> Result<Option<Regex>, WindowsMcpError> {
+    criteria
+        .name_regex
+        .as_deref()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|e| {
+                WindowsMcpError::TreeError(format!("Invalid name_regex '{pattern}': {e}"))
+            })
+        })
+        .transpose()
+}
+
+/// Apply the [`FindCriteria`] filters that UIA conditions cannot express.
+///
+/// UIA PropertyConditions only match exactly, so the name filters always
+/// run here, and the AutomationId and class name filters do when
+/// `criteria.substring` is set (otherwise [`build_find_condition`] already
+/// applied them).  `name_regex` is `criteria.name_regex` compiled by
+/// [`compile_name_regex`].
+fn matches_client_filters(
+    info: &ElementInfo,
+    criteria: &FindCriteria,
+    name_regex: Option<&Regex>,
+) -> bool {
+    if !contains_ignore_case(&info.name, criteria.name.as_deref()) {
+        return false;
+    }
+    if name_regex.is_some_and(|re| !re.is_match(&info.name)) {
+        return false;
+    }
+    if criteria.substring {
+        return contains_ignore_case(&info.automation_id, criteria.automation_id.as_deref())
+            && contains_ignore_case(&info.class_name, criteria.class_name.as_deref());
+    }
+    true
+}
+
 /// Build a UIA condition from [`FindCriteria`].
 ///
 /// - If `automation_id` is set, creates a PropertyCondition on AutomationId.
+/// - If `class_name` is set, creates a PropertyCondition on ClassName.
 /// - If `control_type` is set, creates a PropertyCondition on ControlType name.
 /// - Otherwise, uses `CreateTrueCondition` (match all).
 ///
 /// Name filtering is done client-side because UIA PropertyCondition on Name
-/// only supports exact match, not substring.
-unsafe fn build_find_condition(
+/// only supports exact match, not substring.  With `criteria.substring` the
+/// AutomationId and ClassName conditions are skipped for the same reason.
+pub(crate) unsafe fn build_find_condition(
     uia: &IUIAutomation,
     criteria: &FindCriteria,
 ) -> Result<IUIAutomationCondition, WindowsMcpError> {
     let mut conditions: Vec<IUIAutomationCondition> = Vec::new();
 
-    // AutomationId -- exact match
-    if let Some(ref aid) = criteria.automation_id {
+    // AutomationId -- exact match (substring matching is client-side)
+    if let Some(aid) = criteria.automation_id.as_ref().filter(|_| !criteria.substring) {
         let variant = windows::core::VARIANT::from(windows::core::BSTR::from(aid.as_str()));
         let cond = uia
             .CreatePropertyCondition(UIA_AutomationIdPropertyId, &variant)
@@ -267,6 +628,19 @@ unsafe fn build_find_condition(
         conditions.push(cond.cast::<IUIAutomationCondition>().map_err(|e| WindowsMcpError::TreeError(format!("cast AutomationId condition: {e}")))?);
     }
 
+    // ClassName -- exact match (substring matching is client-side)
+    if let Some(class) = criteria.class_name.as_ref().filter(|_| !criteria.substring) {
+        let variant = windows::core::VARIANT::from(windows::core::BSTR::from(class.as_str()));
+        let cond = uia
+            .CreatePropertyCondition(UIA_ClassNamePropertyId, &variant)
+            .map_err(|e| {
+                WindowsMcpError::TreeError(format!("CreatePropertyCondition(ClassName): {e}"))
+            })?;
+        conditions.push(cond.cast::<IUIAutomationCondition>().map_err(|e| {
+            WindowsMcpError::TreeError(format!("cast ClassName condition: {e}"))
+        })?);
+    }
+
     // ControlType -- convert name to ID, then exact match
     if let Some(ref ct_name) = criteria.control_type {
         if let Some(ct_id) = control_type_id_from_name(ct_name) {
@@ -360,6 +734,7 @@ fn control_type_id_from_name(name: &str) -> Option<i32> {
 ///
 /// Uses `GetSystemMetrics` (not cached -- resolution can change at runtime).
 pub fn get_screen_metrics() -> Result<ScreenMetrics, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
     let (pw, ph, vw, vh) = unsafe {
         (
             GetSystemMetrics(SM_CXSCREEN),
@@ -391,13 +766,35 @@ pub fn get_screen_metrics() -> Result<ScreenMetrics, WindowsMcpError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pattern_probes_are_distinct() {
+        let mut ids: Vec<i32> = PATTERN_PROBES.iter().map(|&(id, _)| id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), PATTERN_PROBES.len());
+        assert!(PATTERN_PROBES
+            .iter()
+            .any(|&(_, name)| name == "GridPattern"));
+        assert!(PATTERN_PROBES
+            .iter()
+            .all(|&(_, name)| name.ends_with("Pattern")));
+        assert_eq!(
+            PATTERN_PROBES[ELEMENT_INFO_PROBES - 1].1,
+            "SelectionItemPattern"
+        );
+    }
+
     #[test]
     fn test_find_criteria_default() {
         let c = FindCriteria::default();
         assert!(c.name.is_none());
+        assert!(c.name_regex.is_none());
         assert!(c.control_type.is_none());
         assert!(c.automation_id.is_none());
+        assert!(c.class_name.is_none());
+        assert!(!c.substring);
         assert!(c.window_handle.is_none());
+        assert_eq!(c.scope, FindScope::Descendants);
         assert_eq!(c.limit, 0);
     }
 
@@ -423,6 +820,8 @@ mod tests {
             localized_control_type: "button".into(),
             class_name: "Button".into(),
             bounding_rect: [10.0, 20.0, 110.0, 50.0],
+            center: [60, 35],
+            runtime_id: vec![42, 1234, 5],
             is_enabled: true,
             is_offscreen: false,
             has_keyboard_focus: false,
@@ -431,6 +830,127 @@ mod tests {
         let json = serde_json::to_string(&info).unwrap();
         assert!(json.contains("\"name\":\"OK\""));
         assert!(json.contains("InvokePattern"));
+        assert!(json.contains("\"center\":[60,35]"));
+        assert!(json.contains("\"runtime_id\":[42,1234,5]"));
+    }
+
+    #[test]
+    fn test_rect_center() {
+        assert_eq!(rect_center(&[10.0, 20.0, 110.0, 50.0]), [60, 35]);
+        assert_eq!(rect_center(&[0.0, 0.0, 5.0, 5.0]), [2, 2]);
+        // Secondary monitor left of the primary has negative coordinates.
+        assert_eq!(rect_center(&[-1920.0, 100.0, -1820.0, 121.0]), [-1870, 110]);
+    }
+
+    fn sample_element() -> ElementInfo {
+        ElementInfo {
+            name: "Save As".into(),
+            automation_id: "FileSaveAsButton".into(),
+            control_type: "Button".into(),
+            localized_control_type: "button".into(),
+            class_name: "Microsoft.UI.Xaml.Controls.Button".into(),
+            bounding_rect: [0.0, 0.0, 80.0, 24.0],
+            center: [40, 12],
+            runtime_id: vec![],
+            is_enabled: true,
+            is_offscreen: false,
+            has_keyboard_focus: false,
+            supported_patterns: vec![],
+        }
+    }
+
+    #[test]
+    fn test_client_filter_name_substring() {
+        let info = sample_element();
+        let mut c = FindCriteria { name: Some("save".into()), ..Default::default() };
+        assert!(matches_client_filters(&info, &c, None));
+        c.name = Some("open".into());
+        assert!(!matches_client_filters(&info, &c, None));
+    }
+
+    #[test]
+    fn test_client_filter_exact_mode_leaves_ids_to_uia() {
+        // Without `substring`, AutomationId and class name are matched by
+        // the UIA condition, so a partial value is not rejected here.
+        let info = sample_element();
+        let c = FindCriteria {
+            automation_id: Some("SaveAs".into()),
+            class_name: Some("xaml".into()),
+            ..Default::default()
+        };
+        assert!(matches_client_filters(&info, &c, None));
+    }
+
+    #[test]
+    fn test_client_filter_substring_automation_id_and_class() {
+        let info = sample_element();
+        let mut c = FindCriteria {
+            automation_id: Some("saveas".into()),
+            class_name: Some("XAML.CONTROLS".into()),
+            substring: true,
+            ..Default::default()
+        };
+        assert!(matches_client_filters(&info, &c, None));
+        c.class_name = Some("Win32".into());
+        assert!(!matches_client_filters(&info, &c, None));
+        c.class_name = None;
+        c.automation_id = Some("Open".into());
+        assert!(!matches_client_filters(&info, &c, None));
+    }
+
+    #[test]
+    fn test_client_filter_name_regex() {
+        let mut info = sample_element();
+        let c = FindCriteria {
+            name_regex: Some("^Save( As)?$".into()),
+            ..Default::default()
+        };
+        let re = compile_name_regex(&c).unwrap();
+        assert!(matches_client_filters(&info, &c, re.as_ref()));
+        info.name = "Save".into();
+        assert!(matches_client_filters(&info, &c, re.as_ref()));
+        info.name = "Save All".into();
+        assert!(!matches_client_filters(&info, &c, re.as_ref()));
+    }
+
+    #[test]
+    fn test_client_filter_name_and_regex_both_apply() {
+        let info = sample_element();
+        let mut c = FindCriteria {
+            name: Some("save".into()),
+            name_regex: Some("As$".into()),
+            ..Default::default()
+        };
+        let re = compile_name_regex(&c).unwrap();
+        assert!(matches_client_filters(&info, &c, re.as_ref()));
+        c.name = Some("open".into());
+        assert!(!matches_client_filters(&info, &c, re.as_ref()));
+    }
+
+    #[test]
+    fn test_compile_name_regex_invalid() {
+        let c = FindCriteria {
+            name_regex: Some("Save(".into()),
+            ..Default::default()
+        };
+        let err = compile_name_regex(&c).unwrap_err();
+        assert!(err.to_string().contains("Invalid name_regex 'Save('"));
+        assert!(compile_name_regex(&FindCriteria::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_scope_from_name() {
+        assert_eq!(FindScope::from_name("children").unwrap(), FindScope::Children);
+        assert_eq!(FindScope::from_name("Descendants").unwrap(), FindScope::Descendants);
+        assert_eq!(FindScope::from_name("SUBTREE").unwrap(), FindScope::Subtree);
+        assert!(FindScope::from_name("ancestors").is_err());
+    }
+
+    #[test]
+    fn test_find_scope_tree_scope() {
+        assert_eq!(FindScope::Children.tree_scope(), TreeScope_Children);
+        assert_eq!(FindScope::Descendants.tree_scope(), TreeScope_Descendants);
+        assert_eq!(FindScope::Subtree.tree_scope(), TreeScope_Subtree);
     }
 
     #[test]
@@ -446,6 +966,26 @@ mod tests {
         assert!(control_type_id_from_name("").is_none());
     }
 
+    #[test]
+    fn test_rects_overlap() {
+        let toolbar = [0, 0, 100, 40];
+        assert!(rects_overlap([10, 10, 20, 20], toolbar));
+        assert!(rects_overlap([90, 30, 200, 80], toolbar));
+        assert!(rects_overlap([-50, -50, 500, 500], toolbar));
+        // Touching edges and disjoint rects do not overlap.
+        assert!(!rects_overlap([100, 0, 120, 40], toolbar));
+        assert!(!rects_overlap([0, 40, 100, 60], toolbar));
+        assert!(!rects_overlap([200, 200, 300, 300], toolbar));
+        // Zero-size elements never match.
+        assert!(!rects_overlap([10, 10, 10, 10], toolbar));
+    }
+
+    #[test]
+    fn test_find_elements_in_rect_rejects_empty_rect() {
+        assert!(find_elements_in_rect(0, [10, 10, 10, 50], None).is_err());
+        assert!(find_elements_in_rect(0, [10, 50, 20, 10], None).is_err());
+    }
+
     #[test]
     fn test_max_find_limit_clamp() {
         assert_eq!(200_usize.clamp(1, MAX_FIND_LIMIT), MAX_FIND_LIMIT);
@@ -6,6 +6,13 @@
 //! inside a Remote Desktop session or on Windows Server SKUs without a
 //! hardware-accelerated session).
 //!
+//! [`capture_window_raw`] captures a single window by handle with GDI
+//! `PrintWindow`, which also works for windows covered by others, and
+//! [`capture_region_raw`] captures a sub-rectangle of one monitor.
+//!
+//! Neither DXGI nor `BitBlt` includes the mouse pointer; set
+//! [`ScreenshotOptions::include_cursor`] to draw it into the frame.
+//!
 //! # DXGI Output Duplication overview
 //!
 //! 1. Create a `D3D11Device` with a hardware or WARP adapter.
@@ -16,13 +23,21 @@
 //! 5. Create a CPU-readable staging texture (`D3D11_USAGE_STAGING`), copy
 //!    the desktop frame into it, then map it with `D3D11_MAP_READ` to
 //!    obtain a `*const u8` pointer to BGRA pixels.
-//! 6. Release the frame and duplicate interface after reading.
+//! 6. Release the frame after reading.  The device, context,
+//!    duplication interface and staging texture are kept for the next
+//!    call (see below); the staging texture doubles as the last frame
+//!    when the desktop has not changed since.
 //!
 //! # Thread safety
 //!
-//! All DXGI / D3D11 interfaces are COM objects.  This module creates them
-//! fresh on every call -- there is no shared global state.  Each call must
-//! be made from a thread with a valid COM apartment (call
+//! All DXGI / D3D11 interfaces are COM objects.  Creating a D3D11 device
+//! and duplication session dominates the cost of a single capture, so one
+//! session per monitor is cached in a process-wide `parking_lot::Mutex`
+//! and reused.  The mutex serialises use of the (single-threaded)
+//! immediate context.  A session is rebuilt when DXGI reports
+//! `DXGI_ERROR_ACCESS_LOST` (mode change, desktop switch, UAC prompt), and
+//! [`reset_screenshot_cache`] drops them all.  Each call must be made from
+//! a thread with a valid COM apartment (call
 //! [`crate::com::COMGuard::init`] before invoking these functions from a
 //! new thread).
 //!
@@ -36,10 +51,14 @@
 //! println!("{}x{} {} bytes", frame.width, frame.height, frame.data.len());
 //!
 //! // Capture primary monitor as a PNG
-//! let png_bytes = capture_png(0).expect("PNG encode failed");
+//! let png_bytes = capture_png(0, &Default::default()).expect("PNG encode failed");
 //! std::fs::write("screenshot.png", &png_bytes).unwrap();
 //! ```
 
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use parking_lot::Mutex;
 use windows::Win32::Foundation::RECT;
 use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
 use windows::Win32::Graphics::Direct3D11::{
@@ -50,7 +69,8 @@ use windows::Win32::Graphics::Direct3D11::{
 use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC};
 use windows::Win32::Graphics::Dxgi::{
     CreateDXGIFactory1, IDXGIAdapter, IDXGIFactory1, IDXGIOutput, IDXGIOutput1,
-    IDXGIOutputDuplication, IDXGIResource, DXGI_OUTDUPL_FRAME_INFO,
+    IDXGIOutputDuplication, IDXGIResource, DXGI_ERROR_ACCESS_LOST, DXGI_ERROR_WAIT_TIMEOUT,
+    DXGI_OUTDUPL_FRAME_INFO,
 };
 use windows::core::Interface;
 
@@ -62,10 +82,16 @@ use crate::errors::WindowsMcpError;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Graphics::Gdi::{
     BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC,
-    GetDIBits, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
-    SRCCOPY,
+    GetDIBits, GetObjectW, PatBlt, ReleaseDC, SelectObject, BITMAP, BITMAPINFO,
+    BITMAPINFOHEADER, BI_RGB, BLACKNESS, DIB_RGB_COLORS, HBITMAP, HBRUSH, HDC, HMONITOR,
+    ROP_CODE, SRCCOPY, WHITENESS,
+};
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DrawIconEx, GetCursorInfo, GetIconInfo, GetSystemMetrics, GetWindowRect, IsIconic,
+    IsWindow, CURSORINFO, CURSOR_SHOWING, DI_NORMAL, HICON, ICONINFO, PW_RENDERFULLCONTENT,
+    SM_CXSCREEN, SM_CYSCREEN,
 };
-use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
 
 // ---------------------------------------------------------------------------
 // Public data types
@@ -86,6 +112,96 @@ pub struct ScreenshotData {
     pub data: Vec<u8>,
 }
 
+/// How long a DXGI capture waits for a new desktop frame by default.
+///
+/// 500ms is ample for a 60Hz display (~16ms between frames).
+pub const DEFAULT_FRAME_TIMEOUT_MS: u32 = 500;
+
+/// Options for the monitor and region capture functions.
+///
+/// `Default` gives a full-resolution frame without the cursor, waiting
+/// [`DEFAULT_FRAME_TIMEOUT_MS`] for a frame.
+#[derive(Debug, Clone)]
+pub struct ScreenshotOptions {
+    /// Downscale so the longest side is at most this many pixels (see
+    /// [`downscale`]); `None` keeps the native resolution.
+    pub max_dimension: Option<u32>,
+    /// Draw the mouse pointer into the frame.  Skipped when the cursor is
+    /// hidden.
+    pub include_cursor: bool,
+    /// How long DXGI waits for the desktop to change.  On a still desktop
+    /// the wait expires and the previously captured frame is returned, so
+    /// this bounds latency rather than causing failures.  The very first
+    /// capture of a monitor -- including the first after a long idle, when
+    /// a lock screen or desktop switch invalidated the cached session --
+    /// has no previous frame; if it times out, capture falls back to GDI.
+    pub timeout_ms: u32,
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> Self {
+        Self {
+            max_dimension: None,
+            include_cursor: false,
+            timeout_ms: DEFAULT_FRAME_TIMEOUT_MS,
+        }
+    }
+}
+
+/// A pixel rectangle relative to a monitor's top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CaptureRegion {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl CaptureRegion {
+    /// The whole `width` x `height` surface.
+    fn full(width: u32, height: u32) -> Self {
+        Self { x: 0, y: 0, width, height }
+    }
+
+    /// Validate a caller-supplied region against a `mon_width` x `mon_height`
+    /// monitor.  The region must be non-empty and lie entirely inside it.
+    fn within(
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        mon_width: u32,
+        mon_height: u32,
+    ) -> Result<Self, WindowsMcpError> {
+        let fits = x >= 0
+            && y >= 0
+            && width > 0
+            && height > 0
+            && u64::from(x as u32) + u64::from(width) <= u64::from(mon_width)
+            && u64::from(y as u32) + u64::from(height) <= u64::from(mon_height);
+        if !fits {
+            return Err(WindowsMcpError::ScreenshotError(format!(
+                "Region {width}x{height} at ({x},{y}) is outside the \
+                 {mon_width}x{mon_height} monitor"
+            )));
+        }
+        Ok(Self { x: x as u32, y: y as u32, width, height })
+    }
+}
+
+/// Copy `region` out of a BGRA surface whose rows are `row_pitch` bytes
+/// apart (pitch may include GPU alignment padding).
+fn copy_region(src: &[u8], row_pitch: usize, region: CaptureRegion) -> Vec<u8> {
+    let row_bytes = region.width as usize * 4;
+    let x_offset = region.x as usize * 4;
+    let mut pixels = Vec::with_capacity(row_bytes * region.height as usize);
+    for row in region.y as usize..(region.y + region.height) as usize {
+        let start = row * row_pitch + x_offset;
+        pixels.extend_from_slice(&src[start..start + row_bytes]);
+    }
+    pixels
+}
+
 // ---------------------------------------------------------------------------
 // Internal DXGI capture helpers
 // ---------------------------------------------------------------------------
@@ -165,19 +281,16 @@ fn create_d3d11_device() -> Result<(ID3D11Device, ID3D11DeviceContext), WindowsM
     Ok((dev, ctx))
 }
 
-/// Enumerate DXGI outputs (monitors) and return the `IDXGIOutput1` for
-/// `monitor_index`, plus the adapter that owns it and the monitor
-/// desktop coordinates.
-fn get_dxgi_output(
-    monitor_index: u32,
-) -> Result<(IDXGIAdapter, IDXGIOutput1, RECT), WindowsMcpError> {
+/// Enumerate DXGI outputs (monitors) across all adapters in global index
+/// order, each paired with the adapter that owns it.
+fn enumerate_dxgi_outputs() -> Result<Vec<(IDXGIAdapter, IDXGIOutput)>, WindowsMcpError> {
     let factory: IDXGIFactory1 = unsafe {
         CreateDXGIFactory1().map_err(|e| {
             WindowsMcpError::ScreenshotError(format!("CreateDXGIFactory1 failed: {e}"))
         })?
     };
 
-    let mut global_output_index: u32 = 0;
+    let mut outputs = Vec::new();
 
     // Walk adapters (graphics cards) in enumeration order.
     let mut adapter_index: u32 = 0;
@@ -197,71 +310,205 @@ fn get_dxgi_output(
                 Ok(o) => o,
                 Err(_) => break, // end of outputs on this adapter
             };
-
-            if global_output_index == monitor_index {
-                let output1: IDXGIOutput1 = output.cast::<IDXGIOutput1>().map_err(|e| {
-                    WindowsMcpError::ScreenshotError(format!(
-                        "IDXGIOutput -> IDXGIOutput1 cast failed (monitor {monitor_index}): {e}"
-                    ))
-                })?;
-
-                // GetDesc returns Result<DXGI_OUTPUT_DESC> in windows 0.58.
-                let desc = unsafe {
-                    output1.GetDesc().map_err(|e| {
-                        WindowsMcpError::ScreenshotError(format!(
-                            "IDXGIOutput1::GetDesc failed: {e}"
-                        ))
-                    })?
-                };
-
-                return Ok((adapter, output1, desc.DesktopCoordinates));
-            }
-
-            global_output_index += 1;
+            outputs.push((adapter.clone(), output));
             output_index += 1;
         }
 
         adapter_index += 1;
     }
 
-    Err(WindowsMcpError::ScreenshotError(format!(
-        "Monitor index {monitor_index} not found; system has {global_output_index} monitor(s)"
-    )))
+    Ok(outputs)
 }
 
-/// Acquire one frame from `duplication`, copy it into a CPU-readable
-/// staging texture, and return the raw BGRA pixel bytes.
-///
-/// The device/context pair must have been created against the same DXGI
-/// adapter as the output used to create `duplication`.
-fn read_frame(
+/// Return the `IDXGIOutput1` for `monitor_index`, plus the adapter that
+/// owns it and the monitor desktop coordinates.
+fn get_dxgi_output(
+    monitor_index: u32,
+) -> Result<(IDXGIAdapter, IDXGIOutput1, RECT), WindowsMcpError> {
+    let outputs = enumerate_dxgi_outputs()?;
+    let count = outputs.len();
+    let (adapter, output) = outputs.into_iter().nth(monitor_index as usize).ok_or_else(|| {
+        WindowsMcpError::ScreenshotError(format!(
+            "Monitor index {monitor_index} not found; system has {count} monitor(s)"
+        ))
+    })?;
+
+    let output1: IDXGIOutput1 = output.cast::<IDXGIOutput1>().map_err(|e| {
+        WindowsMcpError::ScreenshotError(format!(
+            "IDXGIOutput -> IDXGIOutput1 cast failed (monitor {monitor_index}): {e}"
+        ))
+    })?;
+
+    // GetDesc returns Result<DXGI_OUTPUT_DESC> in windows 0.58.
+    let desc = unsafe {
+        output1.GetDesc().map_err(|e| {
+            WindowsMcpError::ScreenshotError(format!("IDXGIOutput1::GetDesc failed: {e}"))
+        })?
+    };
+
+    Ok((adapter, output1, desc.DesktopCoordinates))
+}
+
+/// `HMONITOR` and desktop coordinates of every DXGI output, indexed like
+/// `monitor_index`, so a window's `MonitorFromWindow` result can be
+/// mapped to a capture index.
+pub(crate) fn dxgi_monitors() -> Result<Vec<(HMONITOR, RECT)>, WindowsMcpError> {
+    enumerate_dxgi_outputs()?
+        .iter()
+        .map(|(_, output)| {
+            let desc = unsafe { output.GetDesc() }.map_err(|e| {
+                WindowsMcpError::ScreenshotError(format!("IDXGIOutput::GetDesc failed: {e}"))
+            })?;
+            Ok((desc.Monitor, desc.DesktopCoordinates))
+        })
+        .collect()
+}
+
+/// Desktop coordinates of every DXGI output, indexed like `monitor_index`.
+fn dxgi_monitor_rects() -> Result<Vec<RECT>, WindowsMcpError> {
+    Ok(dxgi_monitors()?.into_iter().map(|(_, rect)| rect).collect())
+}
+
+/// Why [`read_frame`] failed; the first two mean the session should be
+/// rebuilt rather than reported.
+enum FrameError {
+    /// `DXGI_ERROR_ACCESS_LOST`: the duplication interface is dead.
+    AccessLost,
+    /// `DXGI_ERROR_WAIT_TIMEOUT` before the session ever produced a frame.
+    Timeout,
+    Failed(WindowsMcpError),
+}
+
+impl From<WindowsMcpError> for FrameError {
+    fn from(e: WindowsMcpError) -> Self {
+        Self::Failed(e)
+    }
+}
+
+/// Create the CPU-readable staging texture that desktop frames are copied
+/// into.
+fn create_staging_texture(
     device: &ID3D11Device,
+    width: u32,
+    height: u32,
+) -> Result<ID3D11Texture2D, WindowsMcpError> {
+    // BindFlags / CPUAccessFlags / MiscFlags are plain u32 in this struct.
+    let staging_desc = D3D11_TEXTURE2D_DESC {
+        Width: width,
+        Height: height,
+        MipLevels: 1,
+        ArraySize: 1,
+        Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        Usage: D3D11_USAGE_STAGING,
+        BindFlags: 0,
+        CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+        MiscFlags: 0,
+    };
+
+    let mut staging_texture: Option<ID3D11Texture2D> = None;
+    unsafe {
+        device
+            .CreateTexture2D(&staging_desc, None, Some(&mut staging_texture))
+            .map_err(|e| {
+                WindowsMcpError::ScreenshotError(format!("CreateTexture2D (staging) failed: {e}"))
+            })?;
+    }
+
+    staging_texture.ok_or_else(|| {
+        WindowsMcpError::ScreenshotError("CreateTexture2D returned a null staging texture".into())
+    })
+}
+
+/// Map `staging` and return the raw BGRA pixel bytes of `region`.
+fn map_staging(
     context: &ID3D11DeviceContext,
-    duplication: &IDXGIOutputDuplication,
+    staging: &ID3D11Texture2D,
     width: u32,
     height: u32,
+    region: CaptureRegion,
 ) -> Result<Vec<u8>, WindowsMcpError> {
-    // AcquireNextFrame blocks until a new frame is available.
-    // 500ms timeout is ample for a 60Hz display (~16ms between frames).
+    let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+    unsafe {
+        context
+            .Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+            .map_err(|e| {
+                WindowsMcpError::ScreenshotError(format!("ID3D11DeviceContext::Map failed: {e}"))
+            })?;
+    }
+
+    // Copy the requested rows out.
+    // `mapped.RowPitch` >= `width * 4` due to GPU alignment padding;
+    // `copy_region` skips the padding bytes at the end of each row.
+    let row_pitch = mapped.RowPitch as usize;
+    let pixels = unsafe {
+        let src = std::slice::from_raw_parts(
+            mapped.pData as *const u8,
+            row_pitch * (height as usize - 1) + width as usize * 4,
+        );
+        copy_region(src, row_pitch, region)
+    };
+
+    // Unmap before the staging texture can be reused or dropped.
+    unsafe {
+        context.Unmap(staging, 0);
+    }
+
+    Ok(pixels)
+}
+
+/// Wait up to `timeout_ms` for a desktop frame, copy it into the session's
+/// staging texture, and return the raw BGRA pixel bytes of `region`.
+///
+/// DXGI only delivers a frame when something on screen changed, so on a
+/// still desktop `AcquireNextFrame` times out.  The staging texture keeps
+/// the last frame that did arrive, and a timeout re-reads it: the screen
+/// has not changed since.  Only a session that has never produced a frame
+/// reports [`FrameError::Timeout`].
+fn read_frame(
+    session: &mut DxgiSession,
+    region: CaptureRegion,
+    timeout_ms: u32,
+) -> Result<Vec<u8>, FrameError> {
     let mut frame_info = DXGI_OUTDUPL_FRAME_INFO::default();
     // AcquireNextFrame takes *mut Option<IDXGIResource> -- must use a raw ptr.
     let mut desktop_resource: Option<IDXGIResource> = None;
 
-    unsafe {
-        duplication
-            .AcquireNextFrame(
-                500,
-                std::ptr::addr_of_mut!(frame_info),
-                std::ptr::addr_of_mut!(desktop_resource),
-            )
-            .map_err(|e| {
-                WindowsMcpError::ScreenshotError(format!("AcquireNextFrame failed: {e}"))
-            })?;
+    let acquired = unsafe {
+        session.duplication.AcquireNextFrame(
+            timeout_ms,
+            std::ptr::addr_of_mut!(frame_info),
+            std::ptr::addr_of_mut!(desktop_resource),
+        )
+    };
+    match acquired {
+        Ok(()) => {}
+        Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT => {
+            return match &session.staging {
+                Some(staging) => Ok(map_staging(
+                    &session.context,
+                    staging,
+                    session.width,
+                    session.height,
+                    region,
+                )?),
+                None => Err(FrameError::Timeout),
+            };
+        }
+        Err(e) if e.code() == DXGI_ERROR_ACCESS_LOST => return Err(FrameError::AccessLost),
+        Err(e) => {
+            return Err(FrameError::Failed(WindowsMcpError::ScreenshotError(format!(
+                "AcquireNextFrame failed: {e}"
+            ))))
+        }
     }
 
     // We must call ReleaseFrame before returning -- even on error paths.
     // Implement with a defer-style closure.
-    let pixel_result = (|| -> Result<Vec<u8>, WindowsMcpError> {
+    let copied = (|| -> Result<(), WindowsMcpError> {
         let resource = desktop_resource.ok_or_else(|| {
             WindowsMcpError::ScreenshotError(
                 "AcquireNextFrame returned a null desktop resource".into(),
@@ -275,109 +522,69 @@ fn read_frame(
             ))
         })?;
 
-        // Create a CPU-readable staging texture with matching dimensions.
-        // BindFlags / CPUAccessFlags / MiscFlags are plain u32 in this struct.
-        let staging_desc = D3D11_TEXTURE2D_DESC {
-            Width: width,
-            Height: height,
-            MipLevels: 1,
-            ArraySize: 1,
-            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
-            SampleDesc: DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
-            },
-            Usage: D3D11_USAGE_STAGING,
-            BindFlags: 0,
-            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
-            MiscFlags: 0,
-        };
-
-        let mut staging_texture: Option<ID3D11Texture2D> = None;
-        unsafe {
-            device
-                .CreateTexture2D(&staging_desc, None, Some(&mut staging_texture))
-                .map_err(|e| {
-                    WindowsMcpError::ScreenshotError(format!(
-                        "CreateTexture2D (staging) failed: {e}"
-                    ))
-                })?;
+        if session.staging.is_none() {
+            session.staging =
+                Some(create_staging_texture(&session.device, session.width, session.height)?);
         }
 
-        let staging_texture = staging_texture.ok_or_else(|| {
-            WindowsMcpError::ScreenshotError(
-                "CreateTexture2D returned a null staging texture".into(),
-            )
-        })?;
-
         // Copy the GPU-resident desktop texture into the CPU-readable staging
         // texture.  This is a GPU-to-GPU blit; the CPU sees the result after
         // Map().
-        unsafe {
-            context.CopyResource(&staging_texture, &gpu_texture);
-        }
-
-        // Map the staging texture to obtain a CPU pointer to the pixel data.
-        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
-        unsafe {
-            context
-                .Map(
-                    &staging_texture,
-                    0,
-                    D3D11_MAP_READ,
-                    0,
-                    Some(&mut mapped),
-                )
-                .map_err(|e| {
-                    WindowsMcpError::ScreenshotError(format!(
-                        "ID3D11DeviceContext::Map failed: {e}"
-                    ))
-                })?;
-        }
-
-        // Copy pixels out row-by-row.
-        // `mapped.RowPitch` >= `width * 4` due to GPU alignment padding;
-        // skip the padding bytes at the end of each row.
-        let row_pitch = mapped.RowPitch as usize;
-        let row_bytes = (width as usize) * 4;
-        let mut pixels: Vec<u8> = Vec::with_capacity(row_bytes * height as usize);
-
-        unsafe {
-            let src_ptr = mapped.pData as *const u8;
-            for row in 0..height as usize {
-                let row_start = src_ptr.add(row * row_pitch);
-                let src_slice = std::slice::from_raw_parts(row_start, row_bytes);
-                pixels.extend_from_slice(src_slice);
+        if let Some(staging) = &session.staging {
+            unsafe {
+                session.context.CopyResource(staging, &gpu_texture);
             }
         }
-
-        // Unmap before the staging texture is dropped.
-        unsafe {
-            context.Unmap(&staging_texture, 0);
-        }
-
-        Ok(pixels)
+        Ok(())
     })();
 
     // Always release the acquired DXGI frame.
     unsafe {
-        let _ = duplication.ReleaseFrame();
+        let _ = session.duplication.ReleaseFrame();
+    }
+    copied?;
+
+    match &session.staging {
+        Some(staging) => Ok(map_staging(
+            &session.context,
+            staging,
+            session.width,
+            session.height,
+            region,
+        )?),
+        None => Err(FrameError::Timeout),
     }
-
-    pixel_result
 }
 
 // ---------------------------------------------------------------------------
 // DXGI capture entry point
 // ---------------------------------------------------------------------------
 
-/// Capture the desktop for `monitor_index` via DXGI Output Duplication.
+/// A live duplication session for one monitor, reused across captures.
 ///
-/// Returns raw BGRA pixel data or a [`WindowsMcpError::ScreenshotError`].
-/// This path requires a D3D11 device and fails in pure Remote Desktop
-/// sessions without GPU passthrough.  The public [`capture_raw`] falls
-/// back to GDI automatically.
-fn capture_dxgi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
+/// Field order is release order: the duplication and staging texture are
+/// dropped before the device they were created on.
+struct DxgiSession {
+    duplication: IDXGIOutputDuplication,
+    /// Holds the most recent frame; `None` until the first one arrives.
+    staging: Option<ID3D11Texture2D>,
+    context: ID3D11DeviceContext,
+    device: ID3D11Device,
+    /// Monitor top-left in virtual-desktop coordinates.
+    origin: (i32, i32),
+    width: u32,
+    height: u32,
+}
+
+/// Cached sessions keyed by monitor index.
+static DXGI_SESSIONS: OnceLock<Mutex<HashMap<u32, DxgiSession>>> = OnceLock::new();
+
+fn dxgi_sessions() -> &'static Mutex<HashMap<u32, DxgiSession>> {
+    DXGI_SESSIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Create the D3D11 device and output duplication for `monitor_index`.
+fn open_session(monitor_index: u32) -> Result<DxgiSession, WindowsMcpError> {
     // Retrieve the target adapter/output so we can bind DuplicateOutput to
     // the correct device.
     let (adapter, output1, desktop_rect) = get_dxgi_output(monitor_index)?;
@@ -441,49 +648,108 @@ fn capture_dxgi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
             })?
     };
 
-    // Capture one frame.
-    let pixels = read_frame(&device, &context, &duplication, width, height)?;
-
-    Ok(ScreenshotData {
+    Ok(DxgiSession {
+        duplication,
+        staging: None,
+        context,
+        device,
+        origin: (desktop_rect.left, desktop_rect.top),
         width,
         height,
-        data: pixels,
     })
 }
 
+/// Capture the desktop for `monitor_index` via DXGI Output Duplication.
+///
+/// `region` selects a monitor-relative sub-rectangle (`None` = whole
+/// monitor); it is validated against the monitor size.
+///
+/// Reuses the cached session for the monitor when there is one, waiting up
+/// to `timeout_ms` for a new frame and falling back to the session's last
+/// frame when the desktop is still (see [`read_frame`]).  If a reused
+/// session reports access lost, it is rebuilt once -- a fresh duplication
+/// delivers the current desktop as its first frame.
+///
+/// Returns raw BGRA pixel data plus the frame's top-left corner in
+/// virtual-desktop coordinates, or a [`WindowsMcpError::ScreenshotError`].
+/// This path requires a D3D11 device and fails in pure Remote Desktop
+/// sessions without GPU passthrough.  The public [`capture_raw`] falls
+/// back to GDI automatically.
+fn capture_dxgi(
+    monitor_index: u32,
+    region: Option<(i32, i32, u32, u32)>,
+    timeout_ms: u32,
+) -> Result<(ScreenshotData, (i32, i32)), WindowsMcpError> {
+    let mut sessions = dxgi_sessions().lock();
+
+    let mut reused = sessions.contains_key(&monitor_index);
+    loop {
+        let session = match sessions.entry(monitor_index) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => e.insert(open_session(monitor_index)?),
+        };
+
+        let region = match region {
+            Some((x, y, w, h)) => {
+                CaptureRegion::within(x, y, w, h, session.width, session.height)?
+            }
+            None => CaptureRegion::full(session.width, session.height),
+        };
+
+        match read_frame(session, region, timeout_ms) {
+            Ok(pixels) => {
+                let origin = (
+                    session.origin.0 + region.x as i32,
+                    session.origin.1 + region.y as i32,
+                );
+                let frame = ScreenshotData {
+                    width: region.width,
+                    height: region.height,
+                    data: pixels,
+                };
+                return Ok((frame, origin));
+            }
+            Err(FrameError::AccessLost | FrameError::Timeout) if reused => {
+                log::debug!("DXGI session for monitor {monitor_index} is stale; recreating");
+                sessions.remove(&monitor_index);
+                reused = false;
+            }
+            Err(err) => {
+                sessions.remove(&monitor_index);
+                return Err(match err {
+                    FrameError::AccessLost => WindowsMcpError::ScreenshotError(
+                        "AcquireNextFrame failed: DXGI_ERROR_ACCESS_LOST".into(),
+                    ),
+                    FrameError::Timeout => WindowsMcpError::ScreenshotError(format!(
+                        "AcquireNextFrame timed out after {timeout_ms}ms waiting for the \
+                         first frame"
+                    )),
+                    FrameError::Failed(e) => e,
+                });
+            }
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // GDI fallback capture
 // ---------------------------------------------------------------------------
 
-/// Capture the primary monitor using GDI `BitBlt`.
+/// Render `width` x `height` pixels into a memory bitmap and read them back
+/// as opaque BGRA.
 ///
-/// Used when DXGI Output Duplication is unavailable (Remote Desktop
-/// sessions, virtual machines without GPU access, Windows Server SKUs
-/// that lack a hardware display driver).  Only the primary monitor
-/// (`monitor_index == 0`) is supported.
+/// `draw` receives the memory DC (with the bitmap selected) and the screen
+/// DC, and returns `false` if drawing failed; `what` names the drawing call
+/// in the resulting error.
 ///
 /// GDI `BI_RGB` 32-bit mode stores pixels as BGRA with alpha == 0;
 /// this function sets alpha to 255 (fully opaque) before returning.
-fn capture_gdi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
-    if monitor_index > 0 {
-        return Err(WindowsMcpError::ScreenshotError(format!(
-            "GDI fallback does not support monitor index {monitor_index}; \
-             only monitor 0 (primary) is supported via GDI BitBlt"
-        )));
-    }
-
-    let width_i = unsafe { GetSystemMetrics(SM_CXSCREEN) };
-    let height_i = unsafe { GetSystemMetrics(SM_CYSCREEN) };
-
-    if width_i <= 0 || height_i <= 0 {
-        return Err(WindowsMcpError::ScreenshotError(format!(
-            "GetSystemMetrics returned invalid screen size: {width_i}x{height_i}"
-        )));
-    }
-
-    let width = width_i as u32;
-    let height = height_i as u32;
-
+fn gdi_render(
+    width: u32,
+    height: u32,
+    what: &str,
+    draw: impl FnOnce(HDC, HDC) -> bool,
+) -> Result<ScreenshotData, WindowsMcpError> {
     unsafe {
         let screen_dc = GetDC(HWND(std::ptr::null_mut()));
         if screen_dc.is_invalid() {
@@ -511,14 +777,11 @@ fn capture_gdi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
 
             let old_bitmap = SelectObject(mem_dc, bitmap);
 
-            let bitblt_result =
-                BitBlt(mem_dc, 0, 0, width as i32, height as i32, screen_dc, 0, 0, SRCCOPY);
-
-            if bitblt_result.is_err() {
+            if !draw(mem_dc, screen_dc) {
                 SelectObject(mem_dc, old_bitmap);
                 let _ = DeleteObject(bitmap);
                 let _ = DeleteDC(mem_dc);
-                return Err(WindowsMcpError::ScreenshotError("BitBlt failed".into()));
+                return Err(WindowsMcpError::ScreenshotError(format!("{what} failed")));
             }
 
             // GetDIBits expects *mut BITMAPINFO.
@@ -576,6 +839,316 @@ fn capture_gdi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
     }
 }
 
+/// Capture the primary monitor using GDI `BitBlt`.
+///
+/// Used when DXGI Output Duplication is unavailable (Remote Desktop
+/// sessions, virtual machines without GPU access, Windows Server SKUs
+/// that lack a hardware display driver).  Only the primary monitor
+/// (`monitor_index == 0`) is supported.  `region` and the returned origin
+/// are as for [`capture_dxgi`].
+fn capture_gdi(
+    monitor_index: u32,
+    region: Option<(i32, i32, u32, u32)>,
+) -> Result<(ScreenshotData, (i32, i32)), WindowsMcpError> {
+    if monitor_index > 0 {
+        return Err(WindowsMcpError::ScreenshotError(format!(
+            "GDI fallback does not support monitor index {monitor_index}; \
+             only monitor 0 (primary) is supported via GDI BitBlt"
+        )));
+    }
+
+    let width_i = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+    let height_i = unsafe { GetSystemMetrics(SM_CYSCREEN) };
+
+    if width_i <= 0 || height_i <= 0 {
+        return Err(WindowsMcpError::ScreenshotError(format!(
+            "GetSystemMetrics returned invalid screen size: {width_i}x{height_i}"
+        )));
+    }
+
+    let (width, height) = (width_i as u32, height_i as u32);
+    let region = match region {
+        Some((x, y, w, h)) => CaptureRegion::within(x, y, w, h, width, height)?,
+        None => CaptureRegion::full(width, height),
+    };
+
+    let frame = gdi_render(region.width, region.height, "BitBlt", |mem_dc, screen_dc| unsafe {
+        BitBlt(
+            mem_dc,
+            0,
+            0,
+            region.width as i32,
+            region.height as i32,
+            screen_dc,
+            region.x as i32,
+            region.y as i32,
+            SRCCOPY,
+        )
+        .is_ok()
+    })?;
+    // The primary monitor's top-left is the virtual-desktop origin.
+    Ok((frame, (region.x as i32, region.y as i32)))
+}
+
+// ---------------------------------------------------------------------------
+// Cursor overlay
+// ---------------------------------------------------------------------------
+
+/// The current mouse pointer rendered twice: over black and over white.
+///
+/// Comparing the two renderings recovers every cursor kind with one blend
+/// rule -- alpha cursors, masked colour cursors, and monochrome cursors
+/// whose XOR mask inverts the screen (the I-beam).
+struct CursorImage {
+    /// Top-left of the cursor image (position minus hotspot) in
+    /// virtual-desktop coordinates.
+    left: i32,
+    top: i32,
+    width: u32,
+    height: u32,
+    on_black: Vec<u8>,
+    on_white: Vec<u8>,
+}
+
+/// Pixel size of a GDI bitmap, or `None` if it cannot be queried.
+fn bitmap_size(bitmap: HBITMAP) -> Option<(u32, u32)> {
+    let mut bm = BITMAP::default();
+    let read = unsafe {
+        GetObjectW(
+            bitmap,
+            std::mem::size_of::<BITMAP>() as i32,
+            Some(std::ptr::addr_of_mut!(bm).cast()),
+        )
+    };
+    (read != 0 && bm.bmWidth > 0 && bm.bmHeight > 0)
+        .then_some((bm.bmWidth as u32, bm.bmHeight as u32))
+}
+
+/// Snapshot the mouse pointer, or `None` if it is hidden or cannot be read.
+///
+/// Failures are logged rather than returned: the cursor is decoration and
+/// should never fail a capture.
+fn current_cursor() -> Option<CursorImage> {
+    let mut info = CURSORINFO {
+        cbSize: std::mem::size_of::<CURSORINFO>() as u32,
+        ..Default::default()
+    };
+    if let Err(e) = unsafe { GetCursorInfo(&mut info) } {
+        log::debug!("GetCursorInfo failed: {e}");
+        return None;
+    }
+    if info.flags.0 & CURSOR_SHOWING.0 == 0 || info.hCursor.is_invalid() {
+        return None;
+    }
+
+    let icon = HICON(info.hCursor.0);
+    let mut icon_info = ICONINFO::default();
+    if let Err(e) = unsafe { GetIconInfo(icon, &mut icon_info) } {
+        log::debug!("GetIconInfo failed: {e}");
+        return None;
+    }
+
+    // Monochrome cursors have no colour bitmap; their mask stacks the AND
+    // and XOR halves vertically.
+    let size = if icon_info.hbmColor.is_invalid() {
+        bitmap_size(icon_info.hbmMask).map(|(w, h)| (w, h / 2))
+    } else {
+        bitmap_size(icon_info.hbmColor)
+    };
+    // GetIconInfo hands us copies of both bitmaps; we own them.
+    unsafe {
+        if !icon_info.hbmColor.is_invalid() {
+            let _ = DeleteObject(icon_info.hbmColor);
+        }
+        if !icon_info.hbmMask.is_invalid() {
+            let _ = DeleteObject(icon_info.hbmMask);
+        }
+    }
+    let (width, height) = size.filter(|&(_, h)| h > 0)?;
+
+    let render = |background: ROP_CODE| {
+        gdi_render(width, height, "DrawIconEx", |mem_dc, _| unsafe {
+            PatBlt(mem_dc, 0, 0, width as i32, height as i32, background).as_bool()
+                && DrawIconEx(mem_dc, 0, 0, icon, 0, 0, 0, HBRUSH::default(), DI_NORMAL).is_ok()
+        })
+    };
+    let (on_black, on_white) = match (render(BLACKNESS), render(WHITENESS)) {
+        (Ok(black), Ok(white)) => (black.data, white.data),
+        (Err(e), _) | (_, Err(e)) => {
+            log::debug!("Rendering the cursor failed: {e}");
+            return None;
+        }
+    };
+
+    Some(CursorImage {
+        left: info.ptScreenPos.x - icon_info.xHotspot as i32,
+        top: info.ptScreenPos.y - icon_info.yHotspot as i32,
+        width,
+        height,
+        on_black,
+        on_white,
+    })
+}
+
+/// Blend `cursor` into `frame`, whose top-left corner sits at `origin` in
+/// virtual-desktop coordinates.  Parts of the cursor outside the frame are
+/// clipped.
+///
+/// Per channel, with `b`/`w` the cursor rendered over black/white: when
+/// `w >= b` the cursor contributes `b` and lets `(w - b) / 255` of the
+/// screen through; when `w < b` the cursor inverts the screen.
+fn blend_cursor(frame: &mut ScreenshotData, origin: (i32, i32), cursor: &CursorImage) {
+    let left = i64::from(cursor.left) - i64::from(origin.0);
+    let top = i64::from(cursor.top) - i64::from(origin.1);
+
+    for cy in 0..i64::from(cursor.height) {
+        let fy = top + cy;
+        if fy < 0 || fy >= i64::from(frame.height) {
+            continue;
+        }
+        for cx in 0..i64::from(cursor.width) {
+            let fx = left + cx;
+            if fx < 0 || fx >= i64::from(frame.width) {
+                continue;
+            }
+            let src = ((cy * i64::from(cursor.width) + cx) * 4) as usize;
+            let dst = ((fy * i64::from(frame.width) + fx) * 4) as usize;
+            // Channels B, G, R; the frame stays opaque.
+            for c in 0..3 {
+                let b = u32::from(cursor.on_black[src + c]);
+                let w = u32::from(cursor.on_white[src + c]);
+                let screen = u32::from(frame.data[dst + c]);
+                frame.data[dst + c] = if w >= b {
+                    (b + (screen * (w - b) + 127) / 255).min(255) as u8
+                } else {
+                    (255 - screen) as u8
+                };
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scaling and encoding helpers
+// ---------------------------------------------------------------------------
+
+/// Dimensions of a `width` x `height` frame scaled so its longest side is
+/// `max_dimension`, preserving aspect ratio.  Frames that already fit are
+/// left alone (never upscaled); neither side drops below 1.
+fn scaled_dimensions(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    let max_dimension = max_dimension.max(1);
+    if longest <= max_dimension {
+        return (width, height);
+    }
+    let scale = |side: u32| -> u32 {
+        ((u64::from(side) * u64::from(max_dimension) + u64::from(longest) / 2)
+            / u64::from(longest))
+        .max(1) as u32
+    };
+    (scale(width), scale(height))
+}
+
+/// Downscale a BGRA frame so its longest side is at most `max_dimension`
+/// pixels, using a Lanczos3 filter.
+///
+/// Vision models typically cap input resolution anyway, so shrinking a
+/// 4K frame before encoding saves bandwidth and tokens without losing
+/// information the model would see.  Frames that already fit are returned
+/// unchanged.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if
+/// `frame.data` does not hold `width * height * 4` bytes.
+pub fn downscale(
+    frame: ScreenshotData,
+    max_dimension: u32,
+) -> Result<ScreenshotData, WindowsMcpError> {
+    let (new_width, new_height) = scaled_dimensions(frame.width, frame.height, max_dimension);
+    if (new_width, new_height) == (frame.width, frame.height) {
+        return Ok(frame);
+    }
+
+    // The filter treats all four channels alike, so BGRA can pass through
+    // an `Rgba` buffer without reordering.
+    let img = image::RgbaImage::from_raw(frame.width, frame.height, frame.data).ok_or_else(|| {
+        WindowsMcpError::ScreenshotError(
+            "image::RgbaImage::from_raw failed: pixel buffer size mismatch".into(),
+        )
+    })?;
+    let resized = image::imageops::resize(
+        &img,
+        new_width,
+        new_height,
+        image::imageops::FilterType::Lanczos3,
+    );
+
+    Ok(ScreenshotData {
+        width: new_width,
+        height: new_height,
+        data: resized.into_raw(),
+    })
+}
+
+/// Convert a BGRA frame into an `image` crate RGBA buffer.
+fn to_rgba_image(frame: ScreenshotData) -> Result<image::RgbaImage, WindowsMcpError> {
+    let mut pixels = frame.data;
+    // px layout: [B, G, R, A] -> [R, G, B, A]
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+
+    image::RgbaImage::from_raw(frame.width, frame.height, pixels).ok_or_else(|| {
+        WindowsMcpError::ScreenshotError(
+            "image::RgbaImage::from_raw failed: pixel buffer size mismatch".into(),
+        )
+    })
+}
+
+/// Convert `frame` to RGBA and run `write` to encode it into memory;
+/// `format` names the encoder in errors.
+fn encode_with(
+    frame: ScreenshotData,
+    format: &str,
+    write: impl FnOnce(&image::RgbaImage, &mut Vec<u8>) -> image::ImageResult<()>,
+) -> Result<Vec<u8>, WindowsMcpError> {
+    let img = to_rgba_image(frame)?;
+
+    let mut buf: Vec<u8> = Vec::new();
+    write(&img, &mut buf).map_err(|e| {
+        WindowsMcpError::ScreenshotError(format!("{format} encoding failed: {e}"))
+    })?;
+
+    Ok(buf)
+}
+
+/// Encode a BGRA frame as PNG bytes.
+///
+/// Use with [`capture_frame`] or [`capture_region_raw`] when the caller
+/// also needs the frame dimensions.
+pub fn encode_png(frame: ScreenshotData) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_with(frame, "PNG", |img, buf| {
+        img.write_to(&mut std::io::Cursor::new(buf), image::ImageFormat::Png)
+    })
+}
+
+/// Encode a BGRA frame as baseline JPEG; alpha is dropped and `quality`
+/// is clamped to `1..=100`.
+pub fn encode_jpeg(frame: ScreenshotData, quality: u8) -> Result<Vec<u8>, WindowsMcpError> {
+    let quality = quality.clamp(1, 100);
+    encode_with(frame, "JPEG", |img, buf| {
+        img.write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(buf, quality))
+    })
+}
+
+/// Encode a BGRA frame as lossless WebP.
+pub fn encode_webp(frame: ScreenshotData) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_with(frame, "WebP", |img, buf| {
+        img.write_with_encoder(image::codecs::webp::WebPEncoder::new_lossless(buf))
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
@@ -610,27 +1183,155 @@ fn capture_gdi(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
 /// assert_eq!(frame.data.len(), (frame.width * frame.height * 4) as usize);
 /// ```
 pub fn capture_raw(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError> {
-    match capture_dxgi(monitor_index) {
-        Ok(data) => Ok(data),
+    capture_with_fallback(monitor_index, None, &ScreenshotOptions::default())
+}
+
+/// DXGI first, GDI on failure, for the whole monitor or a region of it,
+/// then apply `options`.
+fn capture_with_fallback(
+    monitor_index: u32,
+    region: Option<(i32, i32, u32, u32)>,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotData, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let (mut frame, origin) = match capture_dxgi(monitor_index, region, options.timeout_ms) {
+        Ok(captured) => captured,
         Err(dxgi_err) => {
             log::warn!(
                 "DXGI capture failed for monitor {monitor_index} ({dxgi_err}); \
                  falling back to GDI BitBlt"
             );
-            capture_gdi(monitor_index)
+            capture_gdi(monitor_index, region)?
+        }
+    };
+
+    if options.include_cursor {
+        if let Some(cursor) = current_cursor() {
+            blend_cursor(&mut frame, origin, &cursor);
         }
     }
+
+    match options.max_dimension {
+        Some(max) => downscale(frame, max),
+        None => Ok(frame),
+    }
+}
+
+/// Drop every cached DXGI duplication session and D3D11 device.
+///
+/// The next capture rebuilds them.  Call this to release GPU resources
+/// after a burst of captures, or to force a clean session if captures
+/// start failing.  Caching saves a device creation, an adapter walk and a
+/// `DuplicateOutput` call per capture -- typically the bulk of a
+/// single-frame capture's cost -- so repeated captures run substantially
+/// faster than with a fresh session each time.  The ignored
+/// `bench_screenshot_session_cache` test prints per-frame cold (reset
+/// before every capture) and warm timings; run it on the target machine with
+/// `cargo test -p wmcp-core --release -- --ignored bench_screenshot_session_cache --nocapture`.
+pub fn reset_screenshot_cache() {
+    dxgi_sessions().lock().clear();
+}
+
+/// Capture `monitor_index` as raw BGRA, applying `options`.
+///
+/// The returned frame carries the scaled width and height when
+/// `max_dimension` is set.
+pub fn capture_frame(
+    monitor_index: u32,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotData, WindowsMcpError> {
+    capture_with_fallback(monitor_index, None, options)
+}
+
+/// Default JPEG quality used by callers that do not pick one.
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+/// Capture the desktop for `monitor_index` and encode it as a JPEG.
+///
+/// `quality` is clamped to `1..=100` and `options` applies as in
+/// [`capture_frame`].  JPEG is lossy: around 85 a desktop
+/// frame is typically several times smaller than the PNG with little
+/// visible loss on photos and gradients, but small text and sharp UI edges
+/// pick up ringing artefacts that get worse as quality drops.  Prefer PNG
+/// when exact pixels (OCR, pixel comparisons) matter.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if capture
+/// or encoding fails.
+pub fn capture_jpeg(
+    monitor_index: u32,
+    quality: u8,
+    options: &ScreenshotOptions,
+) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_jpeg(capture_frame(monitor_index, options)?, quality)
+}
+
+/// Capture the desktop for `monitor_index` and encode it as a WebP.
+///
+/// The `image` crate only ships a lossless WebP encoder, so output is
+/// pixel-exact like PNG and usually smaller for flat UI content, but it
+/// has no quality knob; use [`capture_jpeg`] when a lossy trade-off is
+/// wanted.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if capture
+/// or encoding fails.
+pub fn capture_webp(
+    monitor_index: u32,
+    options: &ScreenshotOptions,
+) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_webp(capture_frame(monitor_index, options)?)
+}
+
+/// Capture a `width` x `height` sub-rectangle of `monitor_index` whose
+/// top-left corner is at `(x, y)` relative to the monitor's origin.
+///
+/// Uses the same DXGI-then-GDI strategy as [`capture_raw`], copying only
+/// the requested pixels, so a small control can be sent to a vision model
+/// without shipping the whole frame.  `options` applies as in
+/// [`capture_frame`].
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if the
+/// region is empty or extends past the monitor bounds, or if capture fails.
+pub fn capture_region_raw(
+    monitor_index: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotData, WindowsMcpError> {
+    capture_with_fallback(monitor_index, Some((x, y, width, height)), options)
+}
+
+/// Capture a monitor sub-rectangle and encode it as a PNG.
+///
+/// See [`capture_region_raw`] for the coordinate convention and errors.
+pub fn capture_region_png(
+    monitor_index: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    options: &ScreenshotOptions,
+) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_png(capture_region_raw(monitor_index, x, y, width, height, options)?)
 }
 
 /// Capture the desktop for `monitor_index` and encode it as a PNG.
 ///
-/// Internally calls [`capture_raw`] and encodes the BGRA pixel data
+/// Internally calls [`capture_frame`] and encodes the BGRA pixel data
 /// using the [`image`] crate.  The PNG is returned as a `Vec<u8>` in
 /// memory -- write it to a file or transmit it directly.
 ///
 /// # Parameters
 ///
 /// - `monitor_index`: Zero-based index of the monitor to capture.
+/// - `options`: downscaling and cursor overlay (see [`ScreenshotOptions`]).
 ///
 /// # Returns
 ///
@@ -646,36 +1347,380 @@ pub fn capture_raw(monitor_index: u32) -> Result<ScreenshotData, WindowsMcpError
 /// ```no_run
 /// use wmcp_core::screenshot::capture_png;
 ///
-/// let png = capture_png(0).expect("PNG capture failed");
+/// let png = capture_png(0, &Default::default()).expect("PNG capture failed");
 /// std::fs::write("desktop.png", &png).unwrap();
 /// ```
-pub fn capture_png(monitor_index: u32) -> Result<Vec<u8>, WindowsMcpError> {
-    let frame = capture_raw(monitor_index)?;
-
-    // Convert BGRA -> RGBA for the `image` crate (its RgbaImage uses RGBA).
-    let rgba_pixels: Vec<u8> = frame
-        .data
-        .chunks_exact(4)
-        .flat_map(|px| {
-            // px layout: [B, G, R, A]
-            [px[2], px[1], px[0], px[3]]
-        })
-        .collect();
+pub fn capture_png(
+    monitor_index: u32,
+    options: &ScreenshotOptions,
+) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_png(capture_frame(monitor_index, options)?)
+}
 
-    let img = image::RgbaImage::from_raw(frame.width, frame.height, rgba_pixels)
+/// Paste each `(frame, origin)` into one BGRA buffer covering the union of
+/// their rectangles, with `origin` in virtual-desktop coordinates.
+///
+/// Areas no frame covers are zero-filled; where frames overlap, later ones
+/// win.
+fn composite_frames(
+    frames: &[(ScreenshotData, (i32, i32))],
+) -> Result<ScreenshotData, WindowsMcpError> {
+    if frames.is_empty() {
+        return Err(WindowsMcpError::ScreenshotError("No monitor frames to composite".into()));
+    }
+
+    let left = frames.iter().map(|(_, (x, _))| i64::from(*x)).min().unwrap_or(0);
+    let top = frames.iter().map(|(_, (_, y))| i64::from(*y)).min().unwrap_or(0);
+    let right = frames
+        .iter()
+        .map(|(f, (x, _))| i64::from(*x) + i64::from(f.width))
+        .max()
+        .unwrap_or(0);
+    let bottom = frames
+        .iter()
+        .map(|(f, (_, y))| i64::from(*y) + i64::from(f.height))
+        .max()
+        .unwrap_or(0);
+
+    let width = u32::try_from(right - left).unwrap_or(u32::MAX);
+    let height = u32::try_from(bottom - top).unwrap_or(u32::MAX);
+    let len = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|px| px.checked_mul(4))
         .ok_or_else(|| {
-            WindowsMcpError::ScreenshotError(
-                "image::RgbaImage::from_raw failed: pixel buffer size mismatch".into(),
-            )
+            WindowsMcpError::ScreenshotError(format!(
+                "Virtual desktop {width}x{height} is too large to composite"
+            ))
         })?;
 
-    let mut buf: Vec<u8> = Vec::new();
-    let mut cursor = std::io::Cursor::new(&mut buf);
+    let mut data = vec![0u8; len];
+    let stride = width as usize * 4;
+    for (frame, (x, y)) in frames {
+        let dx = (i64::from(*x) - left) as usize * 4;
+        let dy = (i64::from(*y) - top) as usize;
+        let row_bytes = frame.width as usize * 4;
+        for (row, src) in frame.data.chunks_exact(row_bytes.max(1)).enumerate() {
+            let start = (dy + row) * stride + dx;
+            data[start..start + row_bytes].copy_from_slice(src);
+        }
+    }
 
-    img.write_to(&mut cursor, image::ImageFormat::Png)
-        .map_err(|e| {
-            WindowsMcpError::ScreenshotError(format!("PNG encoding failed: {e}"))
-        })?;
+    Ok(ScreenshotData { width, height, data })
+}
 
-    Ok(buf)
+/// Capture every monitor and stitch them into one BGRA frame covering the
+/// virtual desktop bounds.
+///
+/// Each monitor is captured with [`capture_raw`] and placed at its DXGI
+/// `DesktopCoordinates` offset, so the image keeps the real spatial layout;
+/// areas between monitors of different sizes are zero-filled (transparent
+/// black).  If DXGI cannot enumerate monitors (e.g. Remote Desktop), only
+/// the primary monitor is captured.
+///
+/// This is slower than a single-monitor capture -- one capture per monitor
+/// plus a full-size copy -- and the buffer is `width * height * 4` bytes of
+/// the whole virtual desktop: about 66 MB for two 4K monitors side by side,
+/// on top of the per-monitor frames held while compositing.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if any
+/// monitor fails to capture.
+pub fn capture_all_monitors_raw() -> Result<ScreenshotData, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let rects = match dxgi_monitor_rects() {
+        Ok(rects) if !rects.is_empty() => rects,
+        Ok(_) => return capture_raw(0),
+        Err(e) => {
+            log::warn!("Monitor enumeration failed ({e}); capturing primary monitor only");
+            return capture_raw(0);
+        }
+    };
+
+    let frames = rects
+        .iter()
+        .enumerate()
+        .map(|(index, rect)| Ok((capture_raw(index as u32)?, (rect.left, rect.top))))
+        .collect::<Result<Vec<_>, WindowsMcpError>>()?;
+    composite_frames(&frames)
+}
+
+/// Capture every monitor stitched into one image and encode it as a PNG.
+///
+/// See [`capture_all_monitors_raw`] for layout, cost and errors.
+pub fn capture_all_monitors_png() -> Result<Vec<u8>, WindowsMcpError> {
+    encode_png(capture_all_monitors_raw()?)
+}
+
+/// Wrap encoded image bytes in a `data:<mime>;base64,...` URI.
+///
+/// This is the form most vision-model APIs accept inline, so JSON
+/// consumers need no encoding of their own.
+pub fn to_data_uri(mime: &str, bytes: &[u8]) -> String {
+    use base64::Engine as _;
+
+    format!(
+        "data:{mime};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// Capture `monitor_index` as a PNG and return it as a
+/// `data:image/png;base64,...` URI.
+///
+/// The base64 text is about a third larger than the PNG itself.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if capture
+/// or PNG encoding fails.
+pub fn capture_png_base64(monitor_index: u32) -> Result<String, WindowsMcpError> {
+    let png = capture_png(monitor_index, &ScreenshotOptions::default())?;
+    Ok(to_data_uri("image/png", &png))
+}
+
+/// Capture a single window by handle and return raw BGRA pixel data.
+///
+/// Uses `PrintWindow` with `PW_RENDERFULLCONTENT`, so the window is
+/// captured even when partly covered, and DirectComposition / GPU-rendered
+/// content (browsers, UWP apps) is included.  The frame covers the full
+/// window rectangle including the non-client area.
+///
+/// # Errors
+///
+/// Returns [`crate::errors::WindowsMcpError::ScreenshotError`] if `hwnd` is
+/// not a window, the window is minimized (it has no pixels to render), or
+/// rendering fails.
+pub fn capture_window_raw(hwnd: isize) -> Result<ScreenshotData, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let hwnd = HWND(hwnd as *mut core::ffi::c_void);
+
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+        return Err(WindowsMcpError::ScreenshotError(format!(
+            "Invalid window handle 0x{:X}",
+            hwnd.0 as usize
+        )));
+    }
+    if unsafe { IsIconic(hwnd) }.as_bool() {
+        return Err(WindowsMcpError::ScreenshotError(format!(
+            "Window 0x{:X} is minimized; restore it before capturing",
+            hwnd.0 as usize
+        )));
+    }
+
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.map_err(|e| {
+        WindowsMcpError::ScreenshotError(format!("GetWindowRect failed: {e}"))
+    })?;
+
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+    if width <= 0 || height <= 0 {
+        return Err(WindowsMcpError::ScreenshotError(format!(
+            "Window has zero-size rect ({width}x{height})"
+        )));
+    }
+
+    gdi_render(width as u32, height as u32, "PrintWindow", |mem_dc, _| unsafe {
+        PrintWindow(hwnd, mem_dc, PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT)).as_bool()
+    })
+}
+
+/// Capture a single window by handle and encode it as a PNG.
+///
+/// See [`capture_window_raw`] for capture semantics and errors.
+pub fn capture_window_png(hwnd: isize) -> Result<Vec<u8>, WindowsMcpError> {
+    encode_png(capture_window_raw(hwnd)?)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Manual benchmark: times cold (uncached) against warm (cached)
+    /// captures of the primary monitor.
+    #[test]
+    #[ignore = "needs an interactive desktop; run manually for timings"]
+    fn bench_screenshot_session_cache() {
+        const RUNS: u32 = 20;
+        let mut cold = std::time::Duration::ZERO;
+        for _ in 0..RUNS {
+            reset_screenshot_cache();
+            let start = std::time::Instant::now();
+            capture_raw(0).expect("capture failed");
+            cold += start.elapsed();
+        }
+        let start = std::time::Instant::now();
+        for _ in 0..RUNS {
+            capture_raw(0).expect("capture failed");
+        }
+        let warm = start.elapsed();
+        println!("cold: {:?}/frame, warm: {:?}/frame", cold / RUNS, warm / RUNS);
+    }
+
+    #[test]
+    fn test_region_within_monitor() {
+        let r = CaptureRegion::within(10, 20, 200, 100, 1920, 1080).unwrap();
+        assert_eq!(r, CaptureRegion { x: 10, y: 20, width: 200, height: 100 });
+        assert!(CaptureRegion::within(1720, 980, 200, 100, 1920, 1080).is_ok());
+    }
+
+    #[test]
+    fn test_region_out_of_bounds_rejected() {
+        assert!(CaptureRegion::within(-1, 0, 10, 10, 1920, 1080).is_err());
+        assert!(CaptureRegion::within(0, 0, 0, 10, 1920, 1080).is_err());
+        assert!(CaptureRegion::within(1721, 0, 200, 10, 1920, 1080).is_err());
+        assert!(CaptureRegion::within(0, 1000, 10, 81, 1920, 1080).is_err());
+        assert!(matches!(
+            CaptureRegion::within(0, 0, u32::MAX, 1, 1920, 1080),
+            Err(WindowsMcpError::ScreenshotError(_))
+        ));
+    }
+
+    fn solid_frame(width: u32, height: u32) -> ScreenshotData {
+        ScreenshotData {
+            width,
+            height,
+            data: [0x10, 0x20, 0x30, 0xFF].repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_encoders_emit_format_signatures() {
+        let png = encode_png(solid_frame(8, 8)).unwrap();
+        assert_eq!(&png[..4], b"\x89PNG");
+        let jpeg = encode_jpeg(solid_frame(8, 8), 0).unwrap();
+        assert_eq!(&jpeg[..2], [0xFF, 0xD8]);
+        let webp = encode_webp(solid_frame(8, 8)).unwrap();
+        assert_eq!(&webp[..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+    }
+
+    #[test]
+    fn test_scaled_dimensions_preserve_aspect() {
+        assert_eq!(scaled_dimensions(3840, 2160, 1280), (1280, 720));
+        assert_eq!(scaled_dimensions(1080, 1920, 960), (540, 960));
+        assert_eq!(scaled_dimensions(800, 600, 1024), (800, 600));
+        assert_eq!(scaled_dimensions(5000, 2, 100), (100, 1));
+    }
+
+    #[test]
+    fn test_downscale_updates_dimensions_and_buffer() {
+        let frame = downscale(solid_frame(40, 20), 10).unwrap();
+        assert_eq!((frame.width, frame.height), (10, 5));
+        assert_eq!(frame.data.len(), 10 * 5 * 4);
+        assert_eq!(&frame.data[..4], &[0x10, 0x20, 0x30, 0xFF]);
+    }
+
+    #[test]
+    fn test_to_rgba_swaps_red_and_blue() {
+        let img = to_rgba_image(solid_frame(1, 1)).unwrap();
+        assert_eq!(img.as_raw(), &[0x30, 0x20, 0x10, 0xFF]);
+    }
+
+    /// 2x1 cursor: pixel 0 is opaque red, pixel 1 inverts (XOR).
+    fn test_cursor(left: i32, top: i32) -> CursorImage {
+        CursorImage {
+            left,
+            top,
+            width: 2,
+            height: 1,
+            on_black: vec![0, 0, 255, 255, 255, 255, 255, 255],
+            on_white: vec![0, 0, 255, 255, 0, 0, 0, 255],
+        }
+    }
+
+    #[test]
+    fn test_blend_cursor_opaque_and_inverting_pixels() {
+        let mut frame = solid_frame(4, 2);
+        blend_cursor(&mut frame, (100, 50), &test_cursor(101, 51));
+        let px = |x: usize, y: usize| frame.data[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4].to_vec();
+        assert_eq!(px(1, 1), [0, 0, 255, 0xFF]);
+        assert_eq!(px(2, 1), [0xEF, 0xDF, 0xCF, 0xFF]);
+        assert_eq!(px(0, 0), [0x10, 0x20, 0x30, 0xFF]);
+    }
+
+    #[test]
+    fn test_blend_cursor_transparent_pixels_keep_screen() {
+        let mut frame = solid_frame(2, 1);
+        let cursor = CursorImage {
+            on_black: vec![0; 8],
+            on_white: vec![255; 8],
+            ..test_cursor(0, 0)
+        };
+        blend_cursor(&mut frame, (0, 0), &cursor);
+        assert_eq!(frame.data, solid_frame(2, 1).data);
+    }
+
+    #[test]
+    fn test_blend_cursor_clips_at_frame_edges() {
+        let mut frame = solid_frame(2, 2);
+        // Only the cursor's second (inverting) pixel lands on the frame.
+        blend_cursor(&mut frame, (0, 0), &test_cursor(-1, 1));
+        assert_eq!(&frame.data[8..12], &[0xEF, 0xDF, 0xCF, 0xFF]);
+        assert_eq!(frame.data.iter().filter(|&&b| b == 0xEF).count(), 1);
+
+        let untouched = solid_frame(2, 2);
+        let mut far = solid_frame(2, 2);
+        blend_cursor(&mut far, (0, 0), &test_cursor(5, -7));
+        assert_eq!(far.data, untouched.data);
+    }
+
+    #[test]
+    fn test_screenshot_options_default() {
+        let opts = ScreenshotOptions::default();
+        assert_eq!(opts.max_dimension, None);
+        assert!(!opts.include_cursor);
+        assert_eq!(opts.timeout_ms, DEFAULT_FRAME_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn test_to_data_uri() {
+        assert_eq!(
+            to_data_uri("image/png", b"abc"),
+            "data:image/png;base64,YWJj"
+        );
+        assert_eq!(to_data_uri("image/jpeg", b""), "data:image/jpeg;base64,");
+    }
+
+    #[test]
+    fn test_composite_frames_places_monitors_and_zero_fills_gaps() {
+        // A 2x2 primary at (0,0) and a 1x1 monitor to its left, one row down.
+        let primary = ScreenshotData { width: 2, height: 2, data: vec![1; 2 * 2 * 4] };
+        let left = ScreenshotData { width: 1, height: 1, data: vec![2; 4] };
+        let out = composite_frames(&[(primary, (0, 0)), (left, (-1, 1))]).unwrap();
+
+        assert_eq!((out.width, out.height), (3, 2));
+        let px = |x: usize, y: usize| out.data[(y * 3 + x) * 4];
+        assert_eq!([px(0, 0), px(1, 0), px(2, 0)], [0, 1, 1]);
+        assert_eq!([px(0, 1), px(1, 1), px(2, 1)], [2, 1, 1]);
+    }
+
+    #[test]
+    fn test_composite_frames_rejects_empty() {
+        assert!(composite_frames(&[]).is_err());
+    }
+
+    #[test]
+    fn test_copy_region_skips_pitch_padding() {
+        // 3x2 surface, 16-byte row pitch (4 bytes padding); pixel value = index
+        let mut src = vec![0xEE_u8; 16 * 2];
+        for row in 0..2 {
+            for col in 0..3 {
+                let px = (row * 3 + col) as u8;
+                src[row * 16 + col * 4..row * 16 + col * 4 + 4].fill(px);
+            }
+        }
+        let region = CaptureRegion { x: 1, y: 0, width: 2, height: 2 };
+        let out = copy_region(&src, 16, region);
+        assert_eq!(out, [1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 5, 5, 5, 5]);
+
+        let full = copy_region(&src, 16, CaptureRegion::full(3, 2));
+        assert_eq!(full.len(), 3 * 2 * 4);
+        assert!(!full.contains(&0xEE));
+    }
 }
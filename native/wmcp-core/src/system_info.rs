@@ -8,13 +8,28 @@
 //!
 //! `sysinfo::System` is wrapped in `parking_lot::Mutex` + `OnceLock` for
 //! safe concurrent access.
+//!
+//! # Refresh interval
+//!
+//! [`collect_system_info`] reuses its previous snapshot when called again
+//! within the refresh interval (250ms by default, see
+//! [`set_refresh_interval`]).  Rapid polling then costs nothing and gets
+//! steadier CPU percentages -- sysinfo measures usage between refreshes, so
+//! back-to-back refreshes yield noisy numbers -- at the price of data up to
+//! one interval old.
 
 use std::sync::OnceLock;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
-use serde::Serialize;
-use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
+use serde::{Deserialize, Serialize};
+use sysinfo::{
+    CpuRefreshKind, Disks, MemoryRefreshKind, Networks, ProcessRefreshKind, ProcessesToUpdate,
+    RefreshKind, System,
+};
+use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE};
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
 
 use crate::errors::WindowsMcpError;
 
@@ -22,18 +37,37 @@ use crate::errors::WindowsMcpError;
 // Singleton
 // ---------------------------------------------------------------------------
 
-static SYSTEM: OnceLock<Mutex<System>> = OnceLock::new();
+/// The shared `System` plus the last [`collect_system_info_opts`] result.
+struct SystemState {
+    system: System,
+    /// When `cached` was computed; `None` before the first collection.
+    last_refresh: Option<Instant>,
+    /// Last snapshot with the `(include_disks, include_networks)` it used.
+    cached: Option<((bool, bool), SystemSnapshot)>,
+}
+
+static SYSTEM: OnceLock<Mutex<SystemState>> = OnceLock::new();
 
 /// Tracks whether we've established a CPU baseline (first refresh returns 0%).
 static CPU_BASELINE_SET: AtomicBool = AtomicBool::new(false);
 
-fn get_system() -> &'static Mutex<System> {
+/// Default for [`set_refresh_interval`].
+pub const DEFAULT_REFRESH_INTERVAL_MS: u64 = 250;
+
+/// Minimum age, in milliseconds, before a snapshot is recomputed.
+static REFRESH_INTERVAL_MS: AtomicU64 = AtomicU64::new(DEFAULT_REFRESH_INTERVAL_MS);
+
+fn get_system() -> &'static Mutex<SystemState> {
     SYSTEM.get_or_init(|| {
-        Mutex::new(System::new_with_specifics(
-            RefreshKind::nothing()
-                .with_cpu(CpuRefreshKind::everything())
-                .with_memory(MemoryRefreshKind::everything()),
-        ))
+        Mutex::new(SystemState {
+            system: System::new_with_specifics(
+                RefreshKind::nothing()
+                    .with_cpu(CpuRefreshKind::everything())
+                    .with_memory(MemoryRefreshKind::everything()),
+            ),
+            last_refresh: None,
+            cached: None,
+        })
     })
 }
 
@@ -42,7 +76,7 @@ fn get_system() -> &'static Mutex<System> {
 // ---------------------------------------------------------------------------
 
 /// Owned snapshot of system state -- fully `Send` and serializable.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemSnapshot {
     pub os_name: String,
     pub os_version: String,
@@ -52,10 +86,14 @@ pub struct SystemSnapshot {
     pub total_memory_bytes: u64,
     pub used_memory_bytes: u64,
     pub disks: Vec<DiskSnapshot>,
+    pub gpus: Vec<GpuSnapshot>,
+    pub networks: Vec<NetworkSnapshot>,
+    /// Battery state; `None` on machines without a battery.
+    pub power: Option<PowerSnapshot>,
 }
 
 /// Owned snapshot of a single disk.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskSnapshot {
     pub name: String,
     pub mount_point: String,
@@ -63,17 +101,168 @@ pub struct DiskSnapshot {
     pub available_bytes: u64,
 }
 
+/// Owned snapshot of a single graphics adapter, as DXGI reports it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuSnapshot {
+    pub description: String,
+    pub dedicated_video_memory_bytes: u64,
+    pub shared_system_memory_bytes: u64,
+    /// A software rasterizer such as the Microsoft Basic Render Driver.
+    /// DXGI screen capture works on one but is CPU-bound.
+    pub is_software: bool,
+}
+
+/// Owned snapshot of a single network interface.
+///
+/// On Windows the byte counters are cumulative since the interface came up,
+/// not deltas since the previous snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    pub name: String,
+    pub mac_address: String,
+    pub total_received_bytes: u64,
+    pub total_transmitted_bytes: u64,
+    /// Assigned addresses in CIDR form, e.g. `"192.168.1.20/24"`.
+    pub ip_addresses: Vec<String>,
+}
+
+/// Battery and AC power state from `GetSystemPowerStatus`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerSnapshot {
+    pub ac_online: bool,
+    pub battery_percent: u8,
+    /// Estimated runtime left on battery; `None` while charging or when
+    /// Windows cannot estimate it.
+    pub seconds_remaining: Option<u32>,
+}
+
+/// Owned snapshot of a single running process.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessSnapshot {
+    pub pid: u32,
+    pub name: String,
+    pub parent_pid: Option<u32>,
+    pub memory_bytes: u64,
+    /// Usage since the previous [`list_processes`] call, where 100% is one
+    /// fully busy core.  0 on the first call for each process.
+    pub cpu_percent: f32,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Read battery state, or `None` if there is no battery or its state is
+/// unknown.
+fn collect_power() -> Option<PowerSnapshot> {
+    // BatteryFlag bit 128 is "no system battery"; 255 is "unknown status".
+    const NO_BATTERY: u8 = 128;
+    // Sentinels Windows uses for unknown percentage and lifetime.
+    const UNKNOWN_PERCENT: u8 = 255;
+    const UNKNOWN_SECONDS: u32 = u32::MAX;
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if let Err(e) = unsafe { GetSystemPowerStatus(&mut status) } {
+        log::debug!("GetSystemPowerStatus failed: {e}");
+        return None;
+    }
+    if status.BatteryFlag & NO_BATTERY != 0 || status.BatteryLifePercent == UNKNOWN_PERCENT {
+        return None;
+    }
+
+    Some(PowerSnapshot {
+        ac_online: status.ACLineStatus == 1,
+        battery_percent: status.BatteryLifePercent,
+        seconds_remaining: (status.BatteryLifeTime != UNKNOWN_SECONDS)
+            .then_some(status.BatteryLifeTime),
+    })
+}
+
+/// Enumerate graphics adapters via DXGI.
+///
+/// Returns an empty list if DXGI is unavailable (e.g. some remote or
+/// service sessions) rather than failing the whole snapshot.
+fn collect_gpus() -> Vec<GpuSnapshot> {
+    let factory: IDXGIFactory1 = match unsafe { CreateDXGIFactory1() } {
+        Ok(f) => f,
+        Err(e) => {
+            log::debug!("CreateDXGIFactory1 failed, reporting no GPUs: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut gpus = Vec::new();
+    // EnumAdapters1 returns DXGI_ERROR_NOT_FOUND past the last adapter.
+    for index in 0.. {
+        let Ok(adapter) = (unsafe { factory.EnumAdapters1(index) }) else {
+            break;
+        };
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+        let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+        gpus.push(GpuSnapshot {
+            description: String::from_utf16_lossy(&desc.Description[..len]),
+            dedicated_video_memory_bytes: desc.DedicatedVideoMemory as u64,
+            shared_system_memory_bytes: desc.SharedSystemMemory as u64,
+            is_software: desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0,
+        });
+    }
+    gpus
+}
+
 // ---------------------------------------------------------------------------
 // Public API
 // ---------------------------------------------------------------------------
 
+/// Set how long a [`collect_system_info`] snapshot is reused before the
+/// next call refreshes it.
+///
+/// Longer intervals make polling cheaper and CPU percentages smoother but
+/// return older data; `Duration::ZERO` refreshes on every call.  The
+/// default is [`DEFAULT_REFRESH_INTERVAL_MS`].
+pub fn set_refresh_interval(interval: Duration) {
+    let ms = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+    REFRESH_INTERVAL_MS.store(ms, Ordering::Relaxed);
+}
+
 /// Collect system information and return an owned snapshot.
 ///
 /// This function is blocking (holds the sysinfo mutex).  PyO3 callers
 /// should wrap it in `py.allow_threads()`.
 pub fn collect_system_info() -> Result<SystemSnapshot, WindowsMcpError> {
+    collect_system_info_opts(true, true)
+}
+
+/// [`collect_system_info`] with the slow enumerations made optional.
+///
+/// Disk enumeration queries every volume, including mapped network drives,
+/// and can block for seconds when one is unreachable.  Skipped sections
+/// come back as empty lists.
+///
+/// Within the refresh interval of the previous call with the same flags,
+/// returns that call's snapshot unchanged (see [`set_refresh_interval`]).
+pub fn collect_system_info_opts(
+    include_disks: bool,
+    include_networks: bool,
+) -> Result<SystemSnapshot, WindowsMcpError> {
+    let flags = (include_disks, include_networks);
     let mutex = get_system();
-    let mut sys = mutex.lock();
+
+    {
+        let state = mutex.lock();
+        let interval = Duration::from_millis(REFRESH_INTERVAL_MS.load(Ordering::Relaxed));
+        if let (Some(last), Some((cached_flags, snapshot))) = (state.last_refresh, &state.cached) {
+            if *cached_flags == flags && last.elapsed() < interval {
+                return Ok(snapshot.clone());
+            }
+        }
+    }
+
+    // DXGI needs no sysinfo state; enumerate before taking the lock.
+    let gpus = collect_gpus();
+
+    let mut state = mutex.lock();
 
     // sysinfo requires two refresh_cpu_usage() calls with a gap to compute
     // meaningful percentages.  The first call only establishes a baseline
@@ -82,31 +271,54 @@ pub fn collect_system_info() -> Result<SystemSnapshot, WindowsMcpError> {
     // Release the mutex during the 200ms sleep so concurrent callers aren't
     // blocked.  A double-init race is harmless (refresh_cpu_usage is idempotent).
     if !CPU_BASELINE_SET.load(Ordering::Relaxed) {
-        sys.refresh_cpu_usage();
-        drop(sys);
-        std::thread::sleep(std::time::Duration::from_millis(200));
+        state.system.refresh_cpu_usage();
+        drop(state);
+        std::thread::sleep(Duration::from_millis(200));
         CPU_BASELINE_SET.store(true, Ordering::Relaxed);
-        sys = mutex.lock();
+        state = mutex.lock();
     }
 
+    let sys = &mut state.system;
     sys.refresh_cpu_usage();
     sys.refresh_memory();
 
     let cpu_usage: Vec<f32> = sys.cpus().iter().map(|c| c.cpu_usage()).collect();
     let cpu_count = sys.cpus().len();
 
-    let disks = Disks::new_with_refreshed_list();
-    let disk_snapshots: Vec<DiskSnapshot> = disks
-        .iter()
-        .map(|d| DiskSnapshot {
-            name: d.name().to_string_lossy().into_owned(),
-            mount_point: d.mount_point().to_string_lossy().into_owned(),
-            total_bytes: d.total_space(),
-            available_bytes: d.available_space(),
-        })
-        .collect();
+    let disk_snapshots: Vec<DiskSnapshot> = if include_disks {
+        Disks::new_with_refreshed_list()
+            .iter()
+            .map(|d| DiskSnapshot {
+                name: d.name().to_string_lossy().into_owned(),
+                mount_point: d.mount_point().to_string_lossy().into_owned(),
+                total_bytes: d.total_space(),
+                available_bytes: d.available_space(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let network_snapshots: Vec<NetworkSnapshot> = if include_networks {
+        Networks::new_with_refreshed_list()
+            .iter()
+            .map(|(name, data)| NetworkSnapshot {
+                name: name.clone(),
+                mac_address: data.mac_address().to_string(),
+                total_received_bytes: data.total_received(),
+                total_transmitted_bytes: data.total_transmitted(),
+                ip_addresses: data
+                    .ip_networks()
+                    .iter()
+                    .map(|ip| format!("{}/{}", ip.addr, ip.prefix))
+                    .collect(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    Ok(SystemSnapshot {
+    let snapshot = SystemSnapshot {
         os_name: System::long_os_version().unwrap_or_else(|| "Unknown".to_owned()),
         os_version: System::os_version().unwrap_or_else(|| "Unknown".to_owned()),
         hostname: System::host_name().unwrap_or_else(|| "Unknown".to_owned()),
@@ -115,5 +327,66 @@ pub fn collect_system_info() -> Result<SystemSnapshot, WindowsMcpError> {
         total_memory_bytes: sys.total_memory(),
         used_memory_bytes: sys.used_memory(),
         disks: disk_snapshots,
-    })
+        gpus,
+        networks: network_snapshots,
+        power: collect_power(),
+    };
+
+    state.last_refresh = Some(Instant::now());
+    state.cached = Some((flags, snapshot.clone()));
+    Ok(snapshot)
+}
+
+/// Measure per-core CPU usage over a `sample_ms` window.
+///
+/// Refreshes CPU counters, sleeps `sample_ms`, refreshes again and returns
+/// the percentages from the second sample, so the result is accurate on
+/// the first call.  Blocks the calling thread for `sample_ms`; the sysinfo
+/// mutex is released while sleeping.  Windows below ~100ms give noisy
+/// readings.
+pub fn collect_cpu_usage(sample_ms: u64) -> Result<Vec<f32>, WindowsMcpError> {
+    let mutex = get_system();
+
+    mutex.lock().system.refresh_cpu_usage();
+    std::thread::sleep(Duration::from_millis(sample_ms));
+
+    let mut state = mutex.lock();
+    state.system.refresh_cpu_usage();
+    CPU_BASELINE_SET.store(true, Ordering::Relaxed);
+    Ok(state.system.cpus().iter().map(|c| c.cpu_usage()).collect())
+}
+
+/// List running processes, ordered by PID.
+///
+/// Kept separate from [`collect_system_info`] because a full process
+/// refresh costs several milliseconds.  CPU usage is measured between
+/// successive calls, so the first call reports 0% for every process.
+/// `limit` truncates the list after sorting; `None` returns all.
+pub fn list_processes(limit: Option<usize>) -> Result<Vec<ProcessSnapshot>, WindowsMcpError> {
+    let mut state = get_system().lock();
+    let sys = &mut state.system;
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_memory().with_cpu(),
+    );
+
+    let mut processes: Vec<ProcessSnapshot> = sys
+        .processes()
+        .values()
+        .map(|p| ProcessSnapshot {
+            pid: p.pid().as_u32(),
+            name: p.name().to_string_lossy().into_owned(),
+            parent_pid: p.parent().map(|pid| pid.as_u32()),
+            memory_bytes: p.memory(),
+            cpu_percent: p.cpu_usage(),
+        })
+        .collect();
+    drop(state);
+
+    processes.sort_by_key(|p| p.pid);
+    if let Some(limit) = limit {
+        processes.truncate(limit);
+    }
+    Ok(processes)
 }
@@ -1,16 +1,31 @@
 //! Data structures for a single captured accessibility-tree element.
 //!
-//! [`TreeElementSnapshot`] is an owned, heap-allocated copy of every UIA
-//! property read during a `BuildUpdatedCache` pass.  It is fully `Send`
-//! and `Serialize` -- no COM references are held.
+//! [`TreeElementSnapshot`] is an owned, heap-allocated copy of the UIA
+//! properties read during a `BuildUpdatedCache` pass.  It is fully `Send`
+//! and round-trips through serde -- no COM references are held -- so a
+//! capture saved as JSON can be reloaded with
+//! [`TreeElementSnapshot::from_json`], e.g. as a [`diff_trees`] baseline.
+//!
+//! [`FlatElement`] is the same data without `children`, linked to its
+//! parent by index instead, for consumers that prefer a flat table.
+//!
+//! [`TreeElementSnapshot::to_outline`] and [`render_outline`] render a
+//! capture as an indented text outline, far smaller than JSON when the
+//! tree is fed to a language model.
+//!
+//! [`diff_trees`] compares two captures of the same window and reports
+//! added, removed and changed nodes as a [`TreeDiff`].
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// An owned, COM-free snapshot of one UIA element and its entire subtree.
 ///
 /// All string fields are `String` (UTF-8).  `bounding_rect` stores
 /// `[left, top, right, bottom]` as `f64` to match the Python convention.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(clippy::struct_excessive_bools)] // Direct UIA property mapping
 pub struct TreeElementSnapshot {
     pub name: String,
@@ -25,6 +40,571 @@ pub struct TreeElementSnapshot {
     pub has_keyboard_focus: bool,
     pub is_keyboard_focusable: bool,
     pub accelerator_key: String,
+    /// Opt-in via [`super::UiaProperty::HelpText`]; empty otherwise.
+    pub help_text: String,
+    /// Opt-in via [`super::UiaProperty::AccessKey`]; empty otherwise.
+    pub access_key: String,
+    /// Locale identifier (LCID); `None` unless
+    /// [`super::UiaProperty::Culture`] was requested and reported.
+    pub culture: Option<i32>,
+    /// `ValuePattern.Value` (edit/document text); empty when unsupported or
+    /// when captured with `include_values` off.
+    pub value: String,
+    /// TogglePattern state ("on"/"off"/"indeterminate"); `None` when the
+    /// pattern is unsupported or captured without `include_state`.
+    pub toggle_state: Option<String>,
+    /// SelectionItemPattern `IsSelected`; `None` as for `toggle_state`.
+    pub is_selected: Option<bool>,
     pub depth: usize,
+    /// True when some of this node's children are missing from the
+    /// snapshot: clipped by the `max_children` cap, cut off when the
+    /// capture's `max_total_nodes` budget ran out, or left unvisited by the
+    /// node budget of a breadth-first capture.  Nodes at `max_depth` keep
+    /// `false` even though their children are not read.
+    pub truncated: bool,
     pub children: Vec<TreeElementSnapshot>,
 }
+
+impl TreeElementSnapshot {
+    /// Parse a snapshot previously serialized to JSON (e.g. by
+    /// `serde_json::to_string`), including its whole subtree.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Append this subtree to `buf` as an indented outline, one line per
+    /// element:
+    ///
+    /// ```text
+    /// Window "Save As" (enabled)
+    ///   Button "Save" [id=btnSave] (enabled, focused)
+    ///   Edit "File name" [id=1001] = "report.txt" (enabled)
+    /// ```
+    ///
+    /// Indentation is two spaces per `depth`.  The name, `[id=...]` and
+    /// `= value` parts are omitted when empty; the flags list `enabled` or
+    /// `disabled` followed by any of `focused`, `offscreen`, the toggle
+    /// state and `selected`.  Names and values are quoted with escapes so
+    /// each element stays on one line.
+    pub fn to_outline(&self, buf: &mut String) {
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            node.write_outline_line(buf);
+            stack.extend(node.children.iter().rev());
+        }
+    }
+
+    fn write_outline_line(&self, buf: &mut String) {
+        for _ in 0..self.depth {
+            buf.push_str("  ");
+        }
+        buf.push_str(&self.control_type);
+        if !self.name.is_empty() {
+            let _ = write!(buf, " {:?}", self.name);
+        }
+        if !self.automation_id.is_empty() {
+            let _ = write!(buf, " [id={}]", self.automation_id);
+        }
+        if !self.value.is_empty() {
+            let _ = write!(buf, " = {:?}", self.value);
+        }
+
+        let enabled = if self.is_enabled {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        let mut flags = vec![enabled];
+        if self.has_keyboard_focus {
+            flags.push("focused");
+        }
+        if self.is_offscreen {
+            flags.push("offscreen");
+        }
+        if let Some(state) = &self.toggle_state {
+            flags.push(state);
+        }
+        if self.is_selected == Some(true) {
+            flags.push("selected");
+        }
+        let _ = writeln!(buf, " ({})", flags.join(", "));
+    }
+}
+
+/// Render captured window trees as one outline (see
+/// [`TreeElementSnapshot::to_outline`]), windows in order.
+pub fn render_outline(snapshots: &[TreeElementSnapshot]) -> String {
+    let mut buf = String::new();
+    for snapshot in snapshots {
+        snapshot.to_outline(&mut buf);
+    }
+    buf
+}
+
+/// One node of a pre-order flattened tree.
+///
+/// `index` is the node's position in the flat list; `parent_index` is
+/// `None` for window roots.  Parents always precede their children.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // Direct UIA property mapping
+pub struct FlatElement {
+    pub index: usize,
+    pub parent_index: Option<usize>,
+    pub name: String,
+    pub automation_id: String,
+    pub control_type: String,
+    pub localized_control_type: String,
+    pub class_name: String,
+    pub bounding_rect: [f64; 4],
+    pub is_offscreen: bool,
+    pub is_enabled: bool,
+    pub is_control_element: bool,
+    pub has_keyboard_focus: bool,
+    pub is_keyboard_focusable: bool,
+    pub accelerator_key: String,
+    pub help_text: String,
+    pub access_key: String,
+    pub culture: Option<i32>,
+    pub value: String,
+    pub toggle_state: Option<String>,
+    pub is_selected: Option<bool>,
+    pub depth: usize,
+    pub truncated: bool,
+}
+
+impl FlatElement {
+    /// Move every property except `children` out of `snap`, handing the
+    /// detached children back to the caller.
+    pub fn from_snapshot(
+        snap: TreeElementSnapshot,
+        index: usize,
+        parent_index: Option<usize>,
+    ) -> (Self, Vec<TreeElementSnapshot>) {
+        let flat = Self {
+            index,
+            parent_index,
+            name: snap.name,
+            automation_id: snap.automation_id,
+            control_type: snap.control_type,
+            localized_control_type: snap.localized_control_type,
+            class_name: snap.class_name,
+            bounding_rect: snap.bounding_rect,
+            is_offscreen: snap.is_offscreen,
+            is_enabled: snap.is_enabled,
+            is_control_element: snap.is_control_element,
+            has_keyboard_focus: snap.has_keyboard_focus,
+            is_keyboard_focusable: snap.is_keyboard_focusable,
+            accelerator_key: snap.accelerator_key,
+            help_text: snap.help_text,
+            access_key: snap.access_key,
+            culture: snap.culture,
+            value: snap.value,
+            toggle_state: snap.toggle_state,
+            is_selected: snap.is_selected,
+            depth: snap.depth,
+            truncated: snap.truncated,
+        };
+        (flat, snap.children)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tree diff
+// ---------------------------------------------------------------------------
+
+/// A node reported by [`diff_trees`]: its identity plus where it is.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffNode {
+    pub automation_id: String,
+    pub control_type: String,
+    pub name: String,
+    pub depth: usize,
+    pub bounding_rect: [f64; 4],
+}
+
+impl DiffNode {
+    fn from_snapshot(snap: &TreeElementSnapshot) -> Self {
+        Self {
+            automation_id: snap.automation_id.clone(),
+            control_type: snap.control_type.clone(),
+            name: snap.name.clone(),
+            depth: snap.depth,
+            bounding_rect: snap.bounding_rect,
+        }
+    }
+}
+
+/// One property that differs between the two captures of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Snapshot field name, e.g. `"value"` or `"is_enabled"`.
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// A node present in both captures whose properties differ.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangedNode {
+    /// The node as seen in the `after` capture.
+    pub node: DiffNode,
+    pub changes: Vec<FieldChange>,
+}
+
+/// The difference between two captures, from [`diff_trees`].
+///
+/// Each list is in pre-order of the capture the nodes come from (`before`
+/// for `removed`, `after` otherwise).  A whole subtree that appears or
+/// disappears is reported node by node.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TreeDiff {
+    pub added: Vec<DiffNode>,
+    pub removed: Vec<DiffNode>,
+    pub changed: Vec<ChangedNode>,
+}
+
+impl TreeDiff {
+    /// True when the two captures match.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Text form of a field value for [`FieldChange`]; `None` renders empty.
+trait FieldText {
+    fn field_text(&self) -> String;
+}
+
+impl FieldText for String {
+    fn field_text(&self) -> String {
+        self.clone()
+    }
+}
+
+impl FieldText for bool {
+    fn field_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FieldText for i32 {
+    fn field_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FieldText for usize {
+    fn field_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl FieldText for [f64; 4] {
+    fn field_text(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+impl<T: FieldText> FieldText for Option<T> {
+    fn field_text(&self) -> String {
+        self.as_ref().map(FieldText::field_text).unwrap_or_default()
+    }
+}
+
+/// Push a [`FieldChange`] for every listed field that differs.
+macro_rules! compare_fields {
+    ($before:expr, $after:expr, $changes:expr; $($field:ident),* $(,)?) => {
+        $(
+            if $before.$field != $after.$field {
+                $changes.push(FieldChange {
+                    field: stringify!($field),
+                    before: $before.$field.field_text(),
+                    after: $after.$field.field_text(),
+                });
+            }
+        )*
+    };
+}
+
+/// The properties that differ between two captures of the same node.
+/// `children` and `truncated` are not compared.
+fn field_changes(before: &TreeElementSnapshot, after: &TreeElementSnapshot) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    compare_fields!(before, after, changes;
+        localized_control_type,
+        class_name,
+        bounding_rect,
+        is_offscreen,
+        is_enabled,
+        is_control_element,
+        has_keyboard_focus,
+        is_keyboard_focusable,
+        accelerator_key,
+        help_text,
+        access_key,
+        culture,
+        value,
+        toggle_state,
+        is_selected,
+        depth,
+    );
+    changes
+}
+
+/// Every node of `root` in pre-order.  Iterative, like `flatten_snapshots`.
+fn pre_order(root: &TreeElementSnapshot) -> Vec<&TreeElementSnapshot> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        out.push(node);
+        stack.extend(node.children.iter().rev());
+    }
+    out
+}
+
+/// The identity [`diff_trees`] matches nodes by.
+fn node_key(node: &TreeElementSnapshot) -> (&str, &str, &str) {
+    (&node.automation_id, &node.control_type, &node.name)
+}
+
+/// Compare two captures of the same window.
+///
+/// Nodes are matched by `(automation_id, control_type, name)`.  When
+/// several nodes share a key (e.g. unnamed panes), they are paired in
+/// pre-order: the first in `before` with the first in `after`, and so on.
+/// Unmatched `after` nodes are added, unmatched `before` nodes removed, and
+/// matched nodes whose other properties differ are changed.  Pure and
+/// COM-free.
+pub fn diff_trees(before: &TreeElementSnapshot, after: &TreeElementSnapshot) -> TreeDiff {
+    let before_nodes = pre_order(before);
+    let mut unmatched: HashMap<_, VecDeque<usize>> = HashMap::new();
+    for (index, node) in before_nodes.iter().enumerate() {
+        unmatched
+            .entry(node_key(node))
+            .or_default()
+            .push_back(index);
+    }
+
+    let mut matched = vec![false; before_nodes.len()];
+    let mut diff = TreeDiff::default();
+    for node in pre_order(after) {
+        let partner = unmatched
+            .get_mut(&node_key(node))
+            .and_then(VecDeque::pop_front);
+        match partner {
+            Some(index) => {
+                matched[index] = true;
+                let changes = field_changes(before_nodes[index], node);
+                if !changes.is_empty() {
+                    diff.changed.push(ChangedNode {
+                        node: DiffNode::from_snapshot(node),
+                        changes,
+                    });
+                }
+            }
+            None => diff.added.push(DiffNode::from_snapshot(node)),
+        }
+    }
+
+    diff.removed = before_nodes
+        .iter()
+        .zip(&matched)
+        .filter(|(_, &was_matched)| !was_matched)
+        .map(|(node, _)| DiffNode::from_snapshot(node))
+        .collect();
+    diff
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(
+        control_type: &str,
+        name: &str,
+        depth: usize,
+        children: Vec<TreeElementSnapshot>,
+    ) -> TreeElementSnapshot {
+        TreeElementSnapshot {
+            name: name.to_owned(),
+            automation_id: String::new(),
+            control_type: control_type.to_owned(),
+            localized_control_type: String::new(),
+            class_name: String::new(),
+            bounding_rect: [0.0; 4],
+            is_offscreen: false,
+            is_enabled: true,
+            is_control_element: true,
+            has_keyboard_focus: false,
+            is_keyboard_focusable: false,
+            accelerator_key: String::new(),
+            help_text: String::new(),
+            access_key: String::new(),
+            culture: None,
+            value: String::new(),
+            toggle_state: None,
+            is_selected: None,
+            depth,
+            truncated: false,
+            children,
+        }
+    }
+
+    fn dialog(children: Vec<TreeElementSnapshot>) -> TreeElementSnapshot {
+        node("Window", "Dialog", 0, children)
+    }
+
+    #[test]
+    fn test_outline_line_format() {
+        let mut button = node("Button", "Save", 1, vec![]);
+        button.automation_id = "btnSave".into();
+        button.has_keyboard_focus = true;
+        let mut edit = node("Edit", "File name", 1, vec![]);
+        edit.value = "report.txt".into();
+        edit.is_enabled = false;
+
+        let outline = render_outline(&[dialog(vec![button, edit])]);
+        let lines: Vec<&str> = outline.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"Window "Dialog" (enabled)"#,
+                r#"  Button "Save" [id=btnSave] (enabled, focused)"#,
+                r#"  Edit "File name" = "report.txt" (disabled)"#,
+            ]
+        );
+        assert!(outline.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_outline_indents_by_depth_and_escapes() {
+        let mut check = node("CheckBox", "", 2, vec![]);
+        check.toggle_state = Some("on".into());
+        check.is_selected = Some(true);
+        let group = node("Group", "", 1, vec![check]);
+        let tree = node("Pane", "a \"quoted\"\nname", 0, vec![group]);
+
+        let mut buf = String::new();
+        tree.to_outline(&mut buf);
+        let lines: Vec<&str> = buf.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"Pane "a \"quoted\"\nname" (enabled)"#,
+                "  Group (enabled)",
+                "    CheckBox (enabled, on, selected)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut edit = node("Edit", "File name", 2, vec![]);
+        edit.value = "report.txt".into();
+        edit.culture = Some(1033);
+        let mut check = node("CheckBox", "Remember", 2, vec![]);
+        check.toggle_state = Some("on".into());
+        check.is_selected = Some(false);
+        let tree = dialog(vec![node("Pane", "", 1, vec![edit, check])]);
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let restored = TreeElementSnapshot::from_json(&json).unwrap();
+        assert_eq!(restored, tree);
+        assert!(diff_trees(&tree, &restored).is_empty());
+        assert!(TreeElementSnapshot::from_json("{\"name\": \"x\"}").is_err());
+    }
+
+    #[test]
+    fn test_render_outline_empty() {
+        assert_eq!(render_outline(&[]), "");
+    }
+
+    #[test]
+    fn test_diff_identical_trees_is_empty() {
+        let tree = dialog(vec![
+            node("Button", "OK", 1, vec![]),
+            node("Edit", "Name", 1, vec![]),
+        ]);
+        assert!(diff_trees(&tree, &tree.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() {
+        let before = dialog(vec![node("Edit", "Name", 1, vec![])]);
+        let mut after = before.clone();
+        after.children[0].value = "Ada".into();
+        after.children[0].is_enabled = false;
+        after.children[0].toggle_state = Some("on".into());
+
+        let diff = diff_trees(&before, &after);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let changed = &diff.changed[0];
+        assert_eq!(changed.node.name, "Name");
+        let fields: Vec<&str> = changed.changes.iter().map(|c| c.field).collect();
+        assert_eq!(fields, ["is_enabled", "value", "toggle_state"]);
+        assert_eq!(changed.changes[1].before, "");
+        assert_eq!(changed.changes[1].after, "Ada");
+        assert_eq!(changed.changes[2].after, "on");
+    }
+
+    #[test]
+    fn test_diff_reports_added_and_removed_subtrees() {
+        let before = dialog(vec![
+            node("Button", "OK", 1, vec![]),
+            node(
+                "Group",
+                "Options",
+                1,
+                vec![node("CheckBox", "Verbose", 2, vec![])],
+            ),
+        ]);
+        let after = dialog(vec![
+            node("Button", "OK", 1, vec![]),
+            node("Text", "Saved", 1, vec![]),
+        ]);
+
+        let diff = diff_trees(&before, &after);
+        let added: Vec<&str> = diff.added.iter().map(|n| n.name.as_str()).collect();
+        let removed: Vec<&str> = diff.removed.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(added, ["Saved"]);
+        assert_eq!(removed, ["Options", "Verbose"]);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_pairs_duplicate_keys_in_order() {
+        let before = dialog(vec![
+            node("Pane", "", 1, vec![]),
+            node("Pane", "", 1, vec![]),
+            node("Pane", "", 1, vec![]),
+        ]);
+        let mut after = dialog(vec![
+            node("Pane", "", 1, vec![]),
+            node("Pane", "", 1, vec![]),
+        ]);
+        after.children[1].is_offscreen = true;
+
+        let diff = diff_trees(&before, &after);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changes[0].field, "is_offscreen");
+    }
+
+    #[test]
+    fn test_diff_distinguishes_automation_id() {
+        let before = dialog(vec![node("Button", "Save", 1, vec![])]);
+        let mut after = before.clone();
+        after.children[0].automation_id = "btnSave".into();
+
+        let diff = diff_trees(&before, &after);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.added[0].automation_id, "btnSave");
+    }
+}
@@ -3,25 +3,43 @@
 //! [`capture_tree_raw`] captures the accessibility tree for one or more
 //! windows using a single `BuildUpdatedCache(TreeScope_Subtree)` call per
 //! window, parallelised across Rayon worker threads.
+//! [`capture_tree_flat_raw`] returns the same data as a pre-order list of
+//! [`FlatElement`]s linked by parent index, and
+//! [`capture_subtree_from_point_raw`] captures below the element at a
+//! screen point instead of a window root.  [`capture_tree_bfs_raw`] walks
+//! breadth-first under a per-window node budget for cheap previews.
 //!
 //! # COM apartment model
 //!
 //! Each Rayon thread initialises its own MTA COM apartment via `COMGuard`.
 //! COM interfaces are never shared across thread boundaries.
+//!
+//! The apartment and the thread's `IUIAutomation` instance are created
+//! lazily on first use and then kept in a thread-local for the life of the
+//! worker thread, so repeated captures skip `CoCreateInstance`.
 
 pub mod element;
 
-use element::TreeElementSnapshot;
+use element::{FlatElement, TreeElementSnapshot};
+
+use std::cell::RefCell;
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rayon::prelude::*;
 use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
 use windows::Win32::UI::Accessibility::{
     CUIAutomation, IUIAutomation, IUIAutomationCacheRequest, IUIAutomationElement,
     IUIAutomationElementArray, TreeScope_Subtree, UIA_AcceleratorKeyPropertyId,
-    UIA_AutomationIdPropertyId, UIA_BoundingRectanglePropertyId, UIA_ClassNamePropertyId,
-    UIA_ControlTypePropertyId, UIA_HasKeyboardFocusPropertyId, UIA_IsControlElementPropertyId,
+    UIA_AccessKeyPropertyId, UIA_AutomationIdPropertyId, UIA_BoundingRectanglePropertyId,
+    UIA_ClassNamePropertyId, UIA_ControlTypePropertyId, UIA_CulturePropertyId,
+    UIA_HasKeyboardFocusPropertyId, UIA_HelpTextPropertyId, UIA_IsControlElementPropertyId,
     UIA_IsEnabledPropertyId, UIA_IsKeyboardFocusablePropertyId, UIA_IsOffscreenPropertyId,
-    UIA_LocalizedControlTypePropertyId, UIA_NamePropertyId,
+    UIA_LocalizedControlTypePropertyId, UIA_NamePropertyId, UIA_SelectionItemIsSelectedPropertyId,
+    UIA_ToggleToggleStatePropertyId, UIA_ValueValuePropertyId, UIA_PROPERTY_ID,
     UIA_AppBarControlTypeId, UIA_ButtonControlTypeId, UIA_CalendarControlTypeId,
     UIA_CheckBoxControlTypeId, UIA_ComboBoxControlTypeId, UIA_CustomControlTypeId,
     UIA_DataGridControlTypeId, UIA_DataItemControlTypeId, UIA_DocumentControlTypeId,
@@ -37,7 +55,7 @@ use windows::Win32::UI::Accessibility::{
     UIA_ToolBarControlTypeId, UIA_ToolTipControlTypeId, UIA_TreeControlTypeId,
     UIA_TreeItemControlTypeId, UIA_WindowControlTypeId, UIA_CONTROLTYPE_ID,
 };
-use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::{HWND, POINT};
 
 use crate::com::COMGuard;
 use crate::errors::WindowsMcpError;
@@ -46,6 +64,195 @@ use crate::errors::WindowsMcpError;
 /// thread stack (~1-2 KB per level, so 50 levels ≈ 50-100 KB).
 pub const MAX_TREE_DEPTH: usize = 50;
 
+/// A cacheable UIA element property, selected via
+/// [`CaptureOptions::properties`].
+///
+/// Each maps to one `UIA_*PropertyId` and fills the
+/// [`TreeElementSnapshot`] field of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiaProperty {
+    Name,
+    AutomationId,
+    ControlType,
+    LocalizedControlType,
+    ClassName,
+    BoundingRectangle,
+    IsOffscreen,
+    IsEnabled,
+    IsControlElement,
+    HasKeyboardFocus,
+    IsKeyboardFocusable,
+    AcceleratorKey,
+    HelpText,
+    AccessKey,
+    Culture,
+}
+
+impl UiaProperty {
+    /// The properties captured by default.
+    pub const DEFAULT: [Self; 12] = [
+        Self::Name,
+        Self::AutomationId,
+        Self::ControlType,
+        Self::LocalizedControlType,
+        Self::ClassName,
+        Self::BoundingRectangle,
+        Self::IsOffscreen,
+        Self::IsEnabled,
+        Self::IsControlElement,
+        Self::HasKeyboardFocus,
+        Self::IsKeyboardFocusable,
+        Self::AcceleratorKey,
+    ];
+
+    /// Every selectable property: the defaults plus the opt-in extras.
+    pub const ALL: [Self; 15] = [
+        Self::Name,
+        Self::AutomationId,
+        Self::ControlType,
+        Self::LocalizedControlType,
+        Self::ClassName,
+        Self::BoundingRectangle,
+        Self::IsOffscreen,
+        Self::IsEnabled,
+        Self::IsControlElement,
+        Self::HasKeyboardFocus,
+        Self::IsKeyboardFocusable,
+        Self::AcceleratorKey,
+        Self::HelpText,
+        Self::AccessKey,
+        Self::Culture,
+    ];
+
+    /// The snapshot field name, e.g. `"automation_id"` or `"bounding_rect"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::AutomationId => "automation_id",
+            Self::ControlType => "control_type",
+            Self::LocalizedControlType => "localized_control_type",
+            Self::ClassName => "class_name",
+            Self::BoundingRectangle => "bounding_rect",
+            Self::IsOffscreen => "is_offscreen",
+            Self::IsEnabled => "is_enabled",
+            Self::IsControlElement => "is_control_element",
+            Self::HasKeyboardFocus => "has_keyboard_focus",
+            Self::IsKeyboardFocusable => "is_keyboard_focusable",
+            Self::AcceleratorKey => "accelerator_key",
+            Self::HelpText => "help_text",
+            Self::AccessKey => "access_key",
+            Self::Culture => "culture",
+        }
+    }
+
+    /// Parse a snapshot field name as returned by [`UiaProperty::name`]
+    /// (case-insensitive).
+    pub fn from_name(name: &str) -> Result<Self, WindowsMcpError> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| WindowsMcpError::TreeError(format!("Unknown UIA property '{name}'")))
+    }
+
+    fn property_id(self) -> UIA_PROPERTY_ID {
+        match self {
+            Self::Name => UIA_NamePropertyId,
+            Self::AutomationId => UIA_AutomationIdPropertyId,
+            Self::ControlType => UIA_ControlTypePropertyId,
+            Self::LocalizedControlType => UIA_LocalizedControlTypePropertyId,
+            Self::ClassName => UIA_ClassNamePropertyId,
+            Self::BoundingRectangle => UIA_BoundingRectanglePropertyId,
+            Self::IsOffscreen => UIA_IsOffscreenPropertyId,
+            Self::IsEnabled => UIA_IsEnabledPropertyId,
+            Self::IsControlElement => UIA_IsControlElementPropertyId,
+            Self::HasKeyboardFocus => UIA_HasKeyboardFocusPropertyId,
+            Self::IsKeyboardFocusable => UIA_IsKeyboardFocusablePropertyId,
+            Self::AcceleratorKey => UIA_AcceleratorKeyPropertyId,
+            Self::HelpText => UIA_HelpTextPropertyId,
+            Self::AccessKey => UIA_AccessKeyPropertyId,
+            Self::Culture => UIA_CulturePropertyId,
+        }
+    }
+}
+
+/// Optional behaviour for [`capture_tree_raw`].
+///
+/// `CaptureOptions::default()` captures [`UiaProperty::DEFAULT`], which
+/// leaves out `HelpText`, `AccessKey` and `Culture`; set `properties` to
+/// [`UiaProperty::ALL`] to capture those too.
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    /// Cache `UIA_ValueValuePropertyId` and fill [`TreeElementSnapshot::value`].
+    /// Turning this off skips one property per node for faster captures.
+    pub include_values: bool,
+    /// Keep only nodes whose `control_type` is in this list (matched
+    /// case-insensitively).  The window root is always kept, and the
+    /// surviving descendants of a dropped node are re-parented to its
+    /// nearest kept ancestor.  `depth` still reports the original UIA depth.
+    pub control_type_filter: Option<Vec<String>>,
+    /// Approximate UIA's control view: drop nodes that are not control
+    /// elements and have an empty name, re-parenting their children the
+    /// same way as `control_type_filter`.
+    pub controls_only: bool,
+    /// Drop nodes that are offscreen or have an all-zero bounding rect
+    /// (collapsed menus, virtualized list items), re-parenting their
+    /// children the same way as `control_type_filter`.
+    pub skip_offscreen: bool,
+    /// Maximum children read per node; extra children are dropped and the
+    /// parent's `truncated` flag is set.
+    pub max_children: usize,
+    /// Ceiling on nodes read across all windows of one depth-first
+    /// capture call.  Once reached, no further children are walked and
+    /// each parent left incomplete is marked `truncated`.  Window roots are
+    /// always captured.  Which windows lose nodes depends on scheduling
+    /// when several are captured in parallel.
+    pub max_total_nodes: usize,
+    /// Cache the TogglePattern and SelectionItemPattern state properties and
+    /// fill [`TreeElementSnapshot::toggle_state`] / `is_selected`.
+    pub include_state: bool,
+    /// Run the capture on a dedicated pool of this many threads instead of
+    /// Rayon's global pool, bounding how many COM apartments are initialised
+    /// at once.  `None` uses the global pool.
+    pub max_threads: Option<usize>,
+    /// Element properties to cache and read; fields for the others keep
+    /// their empty/false/zero defaults.  Properties the node filters rely
+    /// on (`ControlType` for `control_type_filter`; `IsControlElement` and
+    /// `Name` for `controls_only`; `IsOffscreen` and `BoundingRectangle`
+    /// for `skip_offscreen`) are read regardless.  Defaults to
+    /// [`UiaProperty::DEFAULT`].
+    pub properties: Vec<UiaProperty>,
+}
+
+impl CaptureOptions {
+    /// Whether `property` should be cached and read: it was requested, or
+    /// a node filter needs it.
+    fn reads(&self, property: UiaProperty) -> bool {
+        self.properties.contains(&property)
+            || match property {
+                UiaProperty::ControlType => self.control_type_filter.is_some(),
+                UiaProperty::IsControlElement | UiaProperty::Name => self.controls_only,
+                UiaProperty::IsOffscreen | UiaProperty::BoundingRectangle => self.skip_offscreen,
+                _ => false,
+            }
+    }
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            include_values: true,
+            control_type_filter: None,
+            controls_only: false,
+            skip_offscreen: false,
+            max_children: MAX_CHILDREN_PER_NODE,
+            max_total_nodes: MAX_TOTAL_NODES,
+            include_state: false,
+            max_threads: None,
+            properties: UiaProperty::DEFAULT.to_vec(),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Control-type ID -> name mapping
 // ---------------------------------------------------------------------------
@@ -103,6 +310,7 @@ pub fn control_type_name(id: UIA_CONTROLTYPE_ID) -> &'static str {
 
 unsafe fn build_cache_request(
     uia: &IUIAutomation,
+    options: &CaptureOptions,
 ) -> Result<IUIAutomationCacheRequest, WindowsMcpError> {
     let req = uia
         .CreateCacheRequest()
@@ -111,23 +319,24 @@ unsafe fn build_cache_request(
     req.SetTreeScope(TreeScope_Subtree)
         .map_err(|e| WindowsMcpError::ComError(format!("SetTreeScope: {e}")))?;
 
-    let properties = [
-        UIA_NamePropertyId,
-        UIA_AutomationIdPropertyId,
-        UIA_ControlTypePropertyId,
-        UIA_LocalizedControlTypePropertyId,
-        UIA_ClassNamePropertyId,
-        UIA_BoundingRectanglePropertyId,
-        UIA_IsOffscreenPropertyId,
-        UIA_IsEnabledPropertyId,
-        UIA_IsControlElementPropertyId,
-        UIA_HasKeyboardFocusPropertyId,
-        UIA_IsKeyboardFocusablePropertyId,
-        UIA_AcceleratorKeyPropertyId,
-    ];
-    for prop in properties {
-        req.AddProperty(prop)
-            .map_err(|e| WindowsMcpError::ComError(format!("AddProperty({prop:?}): {e}")))?;
+    for property in UiaProperty::ALL.into_iter().filter(|&p| options.reads(p)) {
+        req.AddProperty(property.property_id()).map_err(|e| {
+            WindowsMcpError::ComError(format!("AddProperty({}): {e}", property.name()))
+        })?;
+    }
+
+    if options.include_values {
+        req.AddProperty(UIA_ValueValuePropertyId)
+            .map_err(|e| WindowsMcpError::ComError(format!("AddProperty(Value): {e}")))?;
+    }
+
+    // Pattern properties are cacheable like any other, so state stays on the
+    // single BuildCache round trip instead of needing live pattern queries.
+    if options.include_state {
+        for prop in [UIA_ToggleToggleStatePropertyId, UIA_SelectionItemIsSelectedPropertyId] {
+            req.AddProperty(prop)
+                .map_err(|e| WindowsMcpError::ComError(format!("AddProperty({prop:?}): {e}")))?;
+        }
     }
 
     Ok(req)
@@ -143,6 +352,14 @@ macro_rules! bstr_or_empty {
             .map(|b: windows::core::BSTR| b.to_string())
             .unwrap_or_default()
     };
+    // Read only when `$wanted`; the property is not cached otherwise.
+    ($wanted:expr, $expr:expr) => {
+        if $wanted {
+            bstr_or_empty!($expr)
+        } else {
+            String::new()
+        }
+    };
 }
 
 macro_rules! bool_or_false {
@@ -153,39 +370,92 @@ macro_rules! bool_or_false {
     };
 }
 
-unsafe fn walk_element(
+/// Read the cached properties of one element into a childless snapshot.
+unsafe fn read_element(
     element: &IUIAutomationElement,
     depth: usize,
-    max_depth: usize,
+    options: &CaptureOptions,
 ) -> TreeElementSnapshot {
-    let name = bstr_or_empty!(element.CachedName());
-    let automation_id = bstr_or_empty!(element.CachedAutomationId());
-    let localized_control_type = bstr_or_empty!(element.CachedLocalizedControlType());
-    let class_name = bstr_or_empty!(element.CachedClassName());
-    let accelerator_key = bstr_or_empty!(element.CachedAcceleratorKey());
-
-    let control_type = element
-        .CachedControlType()
-        .map(|id| control_type_name(id).to_owned())
-        .unwrap_or_else(|_| "Unknown".to_owned());
-
-    let bounding_rect = element
-        .CachedBoundingRectangle()
-        .map(|r| [r.left as f64, r.top as f64, r.right as f64, r.bottom as f64])
-        .unwrap_or([0.0, 0.0, 0.0, 0.0]);
+    // Unselected properties are not in the cache, so skip reading them
+    // rather than paying for a failed lookup on every node.
+    let reads = |property| options.reads(property);
+    let name = bstr_or_empty!(reads(UiaProperty::Name), element.CachedName());
+    let automation_id = bstr_or_empty!(
+        reads(UiaProperty::AutomationId),
+        element.CachedAutomationId()
+    );
+    let localized_control_type = bstr_or_empty!(
+        reads(UiaProperty::LocalizedControlType),
+        element.CachedLocalizedControlType()
+    );
+    let class_name = bstr_or_empty!(reads(UiaProperty::ClassName), element.CachedClassName());
+    let accelerator_key = bstr_or_empty!(
+        reads(UiaProperty::AcceleratorKey),
+        element.CachedAcceleratorKey()
+    );
+    let help_text = bstr_or_empty!(reads(UiaProperty::HelpText), element.CachedHelpText());
+    let access_key = bstr_or_empty!(reads(UiaProperty::AccessKey), element.CachedAccessKey());
+    let culture = if reads(UiaProperty::Culture) {
+        element.CachedCulture().ok()
+    } else {
+        None
+    };
+
+    // Elements without a ValuePattern report a "not supported" sentinel,
+    // which fails the BSTR conversion and falls back to an empty string.
+    let value = if options.include_values {
+        element
+            .GetCachedPropertyValue(UIA_ValueValuePropertyId)
+            .ok()
+            .and_then(|v| windows::core::BSTR::try_from(&v).ok())
+            .map(|b| b.to_string())
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
 
-    let is_offscreen = bool_or_false!(element.CachedIsOffscreen());
-    let is_enabled = bool_or_false!(element.CachedIsEnabled());
-    let is_control_element = bool_or_false!(element.CachedIsControlElement());
-    let has_keyboard_focus = bool_or_false!(element.CachedHasKeyboardFocus());
-    let is_keyboard_focusable = bool_or_false!(element.CachedIsKeyboardFocusable());
+    // Unsupported patterns yield the "not supported" sentinel, which fails
+    // the numeric/bool conversion and maps to `None`.
+    let (toggle_state, is_selected) = if options.include_state {
+        let toggle = element
+            .GetCachedPropertyValue(UIA_ToggleToggleStatePropertyId)
+            .ok()
+            .and_then(|v| i32::try_from(&v).ok())
+            .map(|s| crate::pattern::toggle_state_name(s).to_owned());
+        let selected = element
+            .GetCachedPropertyValue(UIA_SelectionItemIsSelectedPropertyId)
+            .ok()
+            .and_then(|v| bool::try_from(&v).ok());
+        (toggle, selected)
+    } else {
+        (None, None)
+    };
 
-    let children = if depth < max_depth {
-        collect_children(element, depth, max_depth)
+    let control_type = if reads(UiaProperty::ControlType) {
+        element
+            .CachedControlType()
+            .map(|id| control_type_name(id).to_owned())
+            .unwrap_or_else(|_| "Unknown".to_owned())
     } else {
-        Vec::new()
+        String::new()
     };
 
+    let bounding_rect = reads(UiaProperty::BoundingRectangle)
+        .then(|| element.CachedBoundingRectangle().ok())
+        .flatten()
+        .map(|r| [r.left as f64, r.top as f64, r.right as f64, r.bottom as f64])
+        .unwrap_or([0.0, 0.0, 0.0, 0.0]);
+
+    let is_offscreen =
+        reads(UiaProperty::IsOffscreen) && bool_or_false!(element.CachedIsOffscreen());
+    let is_enabled = reads(UiaProperty::IsEnabled) && bool_or_false!(element.CachedIsEnabled());
+    let is_control_element =
+        reads(UiaProperty::IsControlElement) && bool_or_false!(element.CachedIsControlElement());
+    let has_keyboard_focus =
+        reads(UiaProperty::HasKeyboardFocus) && bool_or_false!(element.CachedHasKeyboardFocus());
+    let is_keyboard_focusable = reads(UiaProperty::IsKeyboardFocusable)
+        && bool_or_false!(element.CachedIsKeyboardFocusable());
+
     TreeElementSnapshot {
         name,
         automation_id,
@@ -199,65 +469,343 @@ unsafe fn walk_element(
         has_keyboard_focus,
         is_keyboard_focusable,
         accelerator_key,
+        help_text,
+        access_key,
+        culture,
+        value,
+        toggle_state,
+        is_selected,
         depth,
-        children,
+        truncated: false,
+        children: Vec::new(),
     }
 }
 
-/// Maximum children per node to prevent memory exhaustion on
-/// pathological trees (e.g. a grid with 100k cells).
-const MAX_CHILDREN_PER_NODE: i32 = 512;
+/// Default maximum children per node, to prevent memory exhaustion on
+/// pathological trees (e.g. a grid with 100k cells).  Override with
+/// [`CaptureOptions::max_children`].
+pub const MAX_CHILDREN_PER_NODE: usize = 512;
 
-unsafe fn collect_children(
-    parent: &IUIAutomationElement,
+/// Default ceiling on nodes per capture call, so a runaway tree (deep
+/// *and* wide) stops allocating long before it reaches gigabytes.
+/// Override with [`CaptureOptions::max_total_nodes`].
+pub const MAX_TOTAL_NODES: usize = 50_000;
+
+/// Count of nodes read so far against [`CaptureOptions::max_total_nodes`],
+/// shared by the Rayon tasks of one capture call.
+struct NodeBudget {
+    used: AtomicUsize,
+    limit: usize,
+}
+
+impl NodeBudget {
+    fn new(limit: usize) -> Self {
+        Self {
+            used: AtomicUsize::new(0),
+            limit,
+        }
+    }
+
+    /// Claim one node; false once the limit has been reached.
+    fn take(&self) -> bool {
+        self.used.fetch_add(1, Ordering::Relaxed) < self.limit
+    }
+}
+
+/// Walk depth-first from `node` down to `max_depth`, claiming one unit of
+/// `budget` per child before reading it.
+///
+/// `children_of` returns a node's children plus whether any were clipped;
+/// `read` produces a node's childless snapshot at the given depth.  A node
+/// whose children were clipped, or cut off because the budget ran out, is
+/// marked `truncated`.  Generic over the node type so the traversal can be
+/// tested without COM.
+fn walk_tree<N>(
+    node: &N,
     depth: usize,
     max_depth: usize,
-) -> Vec<TreeElementSnapshot> {
+    budget: &NodeBudget,
+    children_of: &impl Fn(&N) -> (Vec<N>, bool),
+    read: &impl Fn(&N, usize) -> TreeElementSnapshot,
+) -> TreeElementSnapshot {
+    let mut snapshot = read(node, depth);
+    if depth < max_depth {
+        let (children, clipped) = children_of(node);
+        snapshot.truncated = clipped;
+        snapshot.children.reserve(children.len());
+        for child in &children {
+            if !budget.take() {
+                snapshot.truncated = true;
+                break;
+            }
+            snapshot
+                .children
+                .push(walk_tree(child, depth + 1, max_depth, budget, children_of, read));
+        }
+    }
+    snapshot
+}
+
+unsafe fn walk_element(
+    element: &IUIAutomationElement,
+    max_depth: usize,
+    options: &CaptureOptions,
+    budget: &NodeBudget,
+) -> TreeElementSnapshot {
+    // The root is always read, but still counts towards the budget.
+    budget.take();
+    walk_tree(
+        element,
+        0,
+        max_depth,
+        budget,
+        &|parent| unsafe { cached_children(parent, options.max_children) },
+        &|element, depth| unsafe { read_element(element, depth, options) },
+    )
+}
+
+/// Read up to `max_children` cached children of `parent`; the flag is true
+/// when more children exist than were returned.
+unsafe fn cached_children(
+    parent: &IUIAutomationElement,
+    max_children: usize,
+) -> (Vec<IUIAutomationElement>, bool) {
     let array: IUIAutomationElementArray = match parent.GetCachedChildren() {
         Ok(arr) => arr,
-        Err(_) => return Vec::new(),
+        Err(_) => return (Vec::new(), false),
     };
 
-    let len = match array.Length() {
-        Ok(n) if n > 0 => n.min(MAX_CHILDREN_PER_NODE),
-        _ => return Vec::new(),
+    let total = match array.Length() {
+        Ok(n) if n > 0 => n,
+        _ => return (Vec::new(), false),
     };
+    let cap = i32::try_from(max_children).unwrap_or(i32::MAX);
+    let len = total.min(cap);
+
+    let children = (0..len).filter_map(|i| array.GetElement(i).ok()).collect();
+    (children, len < total)
+}
+
+// ---------------------------------------------------------------------------
+// Node filtering
+// ---------------------------------------------------------------------------
 
-    let mut children = Vec::with_capacity(len as usize);
-    for i in 0..len {
-        if let Ok(child) = array.GetElement(i) {
-            children.push(walk_element(&child, depth + 1, max_depth));
+fn is_retained(snap: &TreeElementSnapshot, options: &CaptureOptions) -> bool {
+    if options.controls_only && !snap.is_control_element && snap.name.is_empty() {
+        return false;
+    }
+    if options.skip_offscreen && (snap.is_offscreen || snap.bounding_rect == [0.0; 4]) {
+        return false;
+    }
+    match &options.control_type_filter {
+        Some(types) => types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&snap.control_type)),
+        None => true,
+    }
+}
+
+/// Drop non-retained nodes from `children`, splicing each dropped node's
+/// (already filtered) children into its place so document order is kept.
+fn prune_children(
+    children: Vec<TreeElementSnapshot>,
+    options: &CaptureOptions,
+) -> Vec<TreeElementSnapshot> {
+    let mut kept = Vec::with_capacity(children.len());
+    for mut child in children {
+        child.children = prune_children(std::mem::take(&mut child.children), options);
+        if is_retained(&child, options) {
+            kept.push(child);
+        } else {
+            kept.append(&mut child.children);
         }
     }
-    children
+    kept
+}
+
+/// Apply the node filters in `options` below `root`; the root itself is
+/// always kept so each window still yields exactly one snapshot.
+fn prune_tree(mut root: TreeElementSnapshot, options: &CaptureOptions) -> TreeElementSnapshot {
+    if options.control_type_filter.is_some() || options.controls_only || options.skip_offscreen {
+        root.children = prune_children(std::mem::take(&mut root.children), options);
+    }
+    root
 }
 
 // ---------------------------------------------------------------------------
 // Per-window traversal (runs inside a Rayon task)
 // ---------------------------------------------------------------------------
 
-fn capture_window(handle: isize, max_depth: usize) -> Option<TreeElementSnapshot> {
-    let _com_guard = COMGuard::init()
-        .map_err(|e| log::error!("capture_window: COMGuard::init failed for handle {handle}: {e}"))
-        .ok()?;
+/// A thread's UIA client together with the apartment it lives in.
+///
+/// Fields drop in declaration order, so `uia` is released before `_com`
+/// calls `CoUninitialize` -- the interface is never used after apartment
+/// teardown.
+struct ThreadUia {
+    uia: IUIAutomation,
+    _com: COMGuard,
+}
 
-    let uia: IUIAutomation = unsafe {
-        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
-            .map_err(|e| {
-                log::error!("capture_window: CoCreateInstance failed for handle {handle}: {e}")
-            })
-            .ok()?
-    };
+thread_local! {
+    static THREAD_UIA: RefCell<Option<ThreadUia>> = const { RefCell::new(None) };
+}
 
-    let cache_req = unsafe { build_cache_request(&uia).ok()? };
+/// Run `f` with this thread's cached `IUIAutomation`, creating it (and the
+/// thread's MTA apartment) on first use.  Scoped pool threads clear it
+/// with [`release_thread_uia`] before exiting rather than leaving it to
+/// the `THREAD_UIA` destructor.
+///
+/// Reusing the instance removes one `CoCreateInstance` (and apartment
+/// init/teardown) per window from every capture after the first on each
+/// worker.  `tests::bench_thread_uia_reuse` times up to 50 windows on one
+/// thread with a fresh instance per window (the old behaviour) against the
+/// reused one; run it on a Windows host with
+/// `cargo test -p wmcp-core --release -- --ignored bench_thread_uia_reuse --nocapture`.
+fn with_thread_uia<R>(f: impl FnOnce(&IUIAutomation) -> R) -> Result<R, WindowsMcpError> {
+    THREAD_UIA.with(|cell| {
+        // Take the state out rather than holding the borrow across `f`.
+        let cached = cell.borrow_mut().take();
+        let state = match cached {
+            Some(state) => state,
+            None => {
+                let com = COMGuard::init()?;
+                let uia: IUIAutomation =
+                    unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER) }
+                        .map_err(|e| WindowsMcpError::ComError(format!("CoCreateInstance: {e}")))?;
+                ThreadUia { uia, _com: com }
+            }
+        };
+        let result = f(&state.uia);
+        *cell.borrow_mut() = Some(state);
+        Ok(result)
+    })
+}
 
-    let root: IUIAutomationElement = unsafe {
-        uia.ElementFromHandleBuildCache(HWND(handle as *mut core::ffi::c_void), &cache_req)
-            .ok()?
-    };
+/// Drop this thread's cached `IUIAutomation` and leave its apartment.
+///
+/// Scoped pool threads call this from their exit handler, so the release
+/// happens as ordinary thread code rather than in the TLS destructor,
+/// which on Windows runs under the loader lock where COM must not be
+/// torn down.  Global pool threads live for the process and keep theirs.
+fn release_thread_uia() {
+    THREAD_UIA.with(|cell| cell.borrow_mut().take());
+}
 
-    let snapshot = unsafe { walk_element(&root, 0, max_depth) };
-    Some(snapshot)
+/// Build the cache for `handle`'s root element on this thread's UIA
+/// instance, hand it to `walk`, and apply the node filters.
+///
+/// Returns a description of the failing step on error (null handle,
+/// UIA setup, cache request, or `ElementFromHandleBuildCache`).
+fn capture_window_with(
+    handle: isize,
+    options: &CaptureOptions,
+    walk: impl FnOnce(&IUIAutomationElement) -> Option<TreeElementSnapshot>,
+) -> Result<TreeElementSnapshot, String> {
+    if handle == 0 {
+        return Err("null window handle".into());
+    }
+
+    with_thread_uia(|uia| {
+        let cache_req = unsafe { build_cache_request(uia, options) }
+            .map_err(|e| format!("cache request setup failed: {e}"))?;
+
+        let root: IUIAutomationElement = unsafe {
+            uia.ElementFromHandleBuildCache(HWND(handle as *mut core::ffi::c_void), &cache_req)
+                .map_err(|e| format!("ElementFromHandleBuildCache failed: {e}"))?
+        };
+
+        walk(&root)
+            .map(|snapshot| prune_tree(snapshot, options))
+            .ok_or_else(|| "tree walk produced no root element".to_string())
+    })
+    .map_err(|e| {
+        log::error!("capture_window: UIA setup failed for handle {handle}: {e}");
+        format!("UIA setup failed: {e}")
+    })
+    .and_then(|result| result)
+    .inspect_err(|e| log::debug!("capture_window: skipping handle {handle}: {e}"))
+}
+
+fn capture_window(
+    handle: isize,
+    max_depth: usize,
+    options: &CaptureOptions,
+    budget: &NodeBudget,
+) -> Result<TreeElementSnapshot, String> {
+    capture_window_with(handle, options, |root| unsafe {
+        Some(walk_element(root, max_depth, options, budget))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Breadth-first walk
+// ---------------------------------------------------------------------------
+
+/// Walk breadth-first from `root`, visiting at most `max_nodes` nodes
+/// (minimum 1), and assemble the visited nodes into a snapshot tree.
+///
+/// `children_of` returns a node's children plus whether any were clipped;
+/// `read` produces a node's childless snapshot at the given depth.  A node
+/// whose children were clipped or left unvisited by the budget is marked
+/// `truncated`.  Generic over the node type so the traversal can be tested
+/// without COM.
+fn bfs_snapshot<N>(
+    root: N,
+    max_nodes: usize,
+    mut children_of: impl FnMut(&N) -> (Vec<N>, bool),
+    mut read: impl FnMut(&N, usize) -> TreeElementSnapshot,
+) -> Option<TreeElementSnapshot> {
+    let max_nodes = max_nodes.max(1);
+    let mut nodes: Vec<TreeElementSnapshot> = Vec::new();
+    let mut parents: Vec<Option<usize>> = Vec::new();
+    let mut queue = VecDeque::from([(root, 0_usize, None::<usize>)]);
+
+    while nodes.len() < max_nodes {
+        let Some((node, depth, parent)) = queue.pop_front() else {
+            break;
+        };
+        let index = nodes.len();
+        let mut snapshot = read(&node, depth);
+        let (children, clipped) = children_of(&node);
+        snapshot.truncated = clipped;
+        nodes.push(snapshot);
+        parents.push(parent);
+        queue.extend(children.into_iter().map(|c| (c, depth + 1, Some(index))));
+    }
+
+    // Anything still queued was cut by the budget.
+    for (_, _, parent) in queue {
+        if let Some(p) = parent {
+            nodes[p].truncated = true;
+        }
+    }
+
+    // Children always have higher indices than their parent, so popping from
+    // the back attaches every subtree before its parent is itself attached.
+    // Children arrive last-first and are reversed once complete.
+    while let Some(mut node) = nodes.pop() {
+        node.children.reverse();
+        match parents[nodes.len()] {
+            Some(p) => nodes[p].children.push(node),
+            None => return Some(node),
+        }
+    }
+    None
+}
+
+fn capture_window_bfs(
+    handle: isize,
+    max_nodes: usize,
+    options: &CaptureOptions,
+) -> Result<TreeElementSnapshot, String> {
+    capture_window_with(handle, options, |root| {
+        bfs_snapshot(
+            root.clone(),
+            max_nodes,
+            |element| unsafe { cached_children(element, options.max_children) },
+            |element, depth| unsafe { read_element(element, depth, options) },
+        )
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -268,19 +816,220 @@ fn capture_window(handle: isize, max_depth: usize) -> Option<TreeElementSnapshot
 /// windows, returning owned snapshots.
 ///
 /// Windows are traversed in parallel using Rayon.  Each thread initialises
-/// its own COM apartment.  Invalid/inaccessible handles are silently skipped.
+/// its own COM apartment.  Invalid/inaccessible handles, and windows whose
+/// capture panics (logged), are silently skipped; use
+/// [`capture_tree_detailed_raw`] to see which failed and why.
+/// With `options.max_threads` set, a scoped pool of that size is built for
+/// this call; its threads release their UIA instance and apartment in the
+/// pool's exit handler once it is dropped.
 ///
 /// `max_depth` is clamped to [`MAX_TREE_DEPTH`] to stay within Rayon's
-/// ~2MB thread stack.
-pub fn capture_tree_raw(window_handles: &[isize], max_depth: usize) -> Vec<TreeElementSnapshot> {
+/// ~2MB thread stack.  `options` selects which properties are read; pass
+/// `&CaptureOptions::default()` for the standard set.  The whole call reads
+/// at most `options.max_total_nodes` nodes ([`MAX_TOTAL_NODES`] by default).
+pub fn capture_tree_raw(
+    window_handles: &[isize],
+    max_depth: usize,
+    options: &CaptureOptions,
+) -> Vec<TreeElementSnapshot> {
     let max_depth = max_depth.min(MAX_TREE_DEPTH);
+    let budget = NodeBudget::new(options.max_total_nodes);
+    capture_each(window_handles, options, |handle| {
+        capture_window(handle, max_depth, options, &budget).ok()
+    })
+}
 
-    window_handles
-        .par_iter()
-        .copied()
-        .filter(|&handle| handle != 0)
-        .filter_map(|handle| capture_window(handle, max_depth))
-        .collect()
+/// Like [`capture_tree_raw`], but keeps every handle paired with its
+/// outcome instead of silently dropping failures.
+///
+/// Returns one `(handle, result)` per input handle, in input order.  The
+/// error string names the failing step (e.g. a null handle, or
+/// `ElementFromHandleBuildCache` failing because the window closed or
+/// access was denied), for diagnosing why a window is missing from a
+/// capture.
+pub fn capture_tree_detailed_raw(
+    window_handles: &[isize],
+    max_depth: usize,
+    options: &CaptureOptions,
+) -> Vec<(isize, Result<TreeElementSnapshot, String>)> {
+    let max_depth = max_depth.min(MAX_TREE_DEPTH);
+    let budget = NodeBudget::new(options.max_total_nodes);
+    capture_each(window_handles, options, |handle| {
+        let outcome =
+            catch_window_panic(handle, || capture_window(handle, max_depth, options, &budget));
+        Some((handle, outcome.and_then(|result| result)))
+    })
+}
+
+/// Run `f` for one window, turning a panic into an error string (logged)
+/// so it cannot unwind through the Rayon collect and lose the whole batch.
+fn catch_window_panic<T>(handle: isize, f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".to_string());
+        log::error!("capture_tree_raw: capture of handle {handle} panicked: {message}");
+        format!("capture panicked: {message}")
+    })
+}
+
+/// Run `per_window` over the handles in parallel, on a scoped pool when
+/// `options.max_threads` is set, keeping the `Some` results in input order.
+/// A window whose capture panics is logged and skipped.
+fn capture_each<T: Send>(
+    window_handles: &[isize],
+    options: &CaptureOptions,
+    per_window: impl Fn(isize) -> Option<T> + Sync,
+) -> Vec<T> {
+    crate::dpi::ensure_per_monitor_aware();
+    let capture = || -> Vec<T> {
+        window_handles
+            .par_iter()
+            .copied()
+            .filter_map(|handle| catch_window_panic(handle, || per_window(handle)).ok().flatten())
+            .collect()
+    };
+
+    match options.max_threads {
+        Some(threads) => match rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .exit_handler(|_| release_thread_uia())
+            .build()
+        {
+            Ok(pool) => pool.install(capture),
+            Err(e) => {
+                log::warn!("capture_tree_raw: thread pool build failed ({e}), using global pool");
+                capture()
+            }
+        },
+        None => capture(),
+    }
+}
+
+/// Capture each window breadth-first, stopping after `max_nodes` nodes per
+/// window so a preview covers every top-level branch before any deep one.
+///
+/// Returns the same nested [`TreeElementSnapshot`] shape as
+/// [`capture_tree_raw`], with children populated only for visited nodes;
+/// nodes with unvisited children are marked `truncated`.  The budget counts
+/// nodes before `options` filters are applied.  The UIA subtree cache is
+/// still built in one call per window, so this bounds output size rather
+/// than COM cost.
+pub fn capture_tree_bfs_raw(
+    window_handles: &[isize],
+    max_nodes: usize,
+    options: &CaptureOptions,
+) -> Vec<TreeElementSnapshot> {
+    capture_each(window_handles, options, |handle| {
+        capture_window_bfs(handle, max_nodes, options).ok()
+    })
+}
+
+/// Capture the subtree rooted at the UIA element under screen point
+/// `(x, y)`, e.g. a dialog's content pane rather than its whole window.
+///
+/// Runs on the calling thread with its own COM apartment.  Returns `None`
+/// when no element is found at the point or UIA fails (logged).
+pub fn capture_subtree_from_point_raw(
+    x: i32,
+    y: i32,
+    max_depth: usize,
+    options: &CaptureOptions,
+) -> Option<TreeElementSnapshot> {
+    let max_depth = max_depth.min(MAX_TREE_DEPTH);
+    crate::dpi::ensure_per_monitor_aware();
+
+    let _com_guard = COMGuard::init()
+        .map_err(|e| log::error!("capture_subtree_from_point: COMGuard::init failed: {e}"))
+        .ok()?;
+
+    let uia: IUIAutomation = unsafe {
+        CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| log::error!("capture_subtree_from_point: CoCreateInstance failed: {e}"))
+            .ok()?
+    };
+
+    let cache_req = unsafe { build_cache_request(&uia, options).ok()? };
+
+    let anchor: IUIAutomationElement = unsafe { uia.ElementFromPoint(POINT { x, y }).ok()? };
+    let root: IUIAutomationElement = unsafe { anchor.BuildUpdatedCache(&cache_req).ok()? };
+
+    let budget = NodeBudget::new(options.max_total_nodes);
+    let snapshot = unsafe { walk_element(&root, max_depth, options, &budget) };
+    Some(prune_tree(snapshot, options))
+}
+
+/// Capture the tree of the current foreground window, resolving its handle
+/// internally so callers need not call `get_foreground_hwnd` first.
+///
+/// Returns `None` when no window is in the foreground or its capture fails,
+/// exactly as a skipped handle in [`capture_tree_raw`].
+pub fn capture_foreground_tree_raw(
+    max_depth: usize,
+    options: &CaptureOptions,
+) -> Option<TreeElementSnapshot> {
+    let handle = crate::window::get_foreground_hwnd();
+    if handle == 0 {
+        log::debug!("capture_foreground_tree: no foreground window");
+        return None;
+    }
+    capture_tree_raw(&[handle], max_depth, options).pop()
+}
+
+/// Flatten snapshot trees into a single pre-order list.
+///
+/// Roots get `parent_index: None`; indices are contiguous across all
+/// roots.  Iterative, so arbitrarily deep input cannot overflow the stack.
+pub fn flatten_snapshots(roots: Vec<TreeElementSnapshot>) -> Vec<FlatElement> {
+    let mut out = Vec::new();
+    let mut stack: Vec<(TreeElementSnapshot, Option<usize>)> =
+        roots.into_iter().rev().map(|root| (root, None)).collect();
+
+    while let Some((snap, parent_index)) = stack.pop() {
+        let index = out.len();
+        let (flat, children) = FlatElement::from_snapshot(snap, index, parent_index);
+        out.push(flat);
+        // Reverse so the first child is popped (and numbered) first
+        stack.extend(children.into_iter().rev().map(|child| (child, Some(index))));
+    }
+    out
+}
+
+/// Capture the accessibility tree like [`capture_tree_raw`], returned as a
+/// flat pre-order list with parent indices instead of nested children.
+pub fn capture_tree_flat_raw(
+    window_handles: &[isize],
+    max_depth: usize,
+    options: &CaptureOptions,
+) -> Vec<FlatElement> {
+    flatten_snapshots(capture_tree_raw(window_handles, max_depth, options))
+}
+
+/// Capture the accessibility tree like [`capture_tree_raw`] and serialize
+/// the snapshot array as JSON straight into `writer`.
+///
+/// Unlike `serde_json::to_string`, this never holds the whole document in
+/// memory alongside the snapshots, which roughly halves peak memory for
+/// large multi-window captures.  `pretty` selects indented output.  Wrap
+/// unbuffered writers (files, stdout) in a `BufWriter`.
+///
+/// Returns a `TreeError` if serialization or a write fails.
+pub fn write_tree_json<W: Write>(
+    window_handles: &[isize],
+    max_depth: usize,
+    options: &CaptureOptions,
+    writer: &mut W,
+    pretty: bool,
+) -> Result<(), WindowsMcpError> {
+    let snapshots = capture_tree_raw(window_handles, max_depth, options);
+    let written = if pretty {
+        serde_json::to_writer_pretty(writer, &snapshots)
+    } else {
+        serde_json::to_writer(writer, &snapshots)
+    };
+    written.map_err(|e| WindowsMcpError::TreeError(format!("JSON write failed: {e}")))
 }
 
 // ---------------------------------------------------------------------------
@@ -311,17 +1060,45 @@ mod tests {
 
     #[test]
     fn test_capture_tree_raw_empty_handles() {
-        let result = capture_tree_raw(&[], 50);
+        let result = capture_tree_raw(&[], 50, &CaptureOptions::default());
         assert!(result.is_empty());
     }
 
     #[test]
     fn test_capture_tree_raw_zero_handle_filtered() {
         // Handle 0 should be filtered out (no COM calls attempted)
-        let result = capture_tree_raw(&[0, 0, 0], 50);
+        let result = capture_tree_raw(&[0, 0, 0], 50, &CaptureOptions::default());
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_capture_each_survives_panicking_window() {
+        let result = capture_each(&[1, 2, 3, 4], &CaptureOptions::default(), |handle| {
+            if handle == 3 {
+                panic!("simulated COM failure");
+            }
+            Some(handle * 10)
+        });
+        assert_eq!(result, vec![10, 20, 40]);
+    }
+
+    #[test]
+    fn test_catch_window_panic_reports_payload() {
+        assert_eq!(catch_window_panic(7, || 42), Ok(42));
+        let err = catch_window_panic(7, || -> i32 { panic!("boom {}", 1) }).unwrap_err();
+        assert_eq!(err, "capture panicked: boom 1");
+    }
+
+    #[test]
+    fn test_capture_tree_detailed_raw_reports_zero_handle() {
+        let result = capture_tree_detailed_raw(&[0, 0], 50, &CaptureOptions::default());
+        assert_eq!(result.len(), 2);
+        for (handle, outcome) in &result {
+            assert_eq!(*handle, 0);
+            assert_eq!(outcome.as_ref().unwrap_err(), "null window handle");
+        }
+    }
+
     #[test]
     fn test_max_tree_depth_clamping() {
         // Depth > MAX_TREE_DEPTH should be clamped
@@ -330,6 +1107,389 @@ mod tests {
         assert_eq!(MAX_TREE_DEPTH, 50);
     }
 
+    #[test]
+    fn test_capture_options_default_includes_values() {
+        assert!(CaptureOptions::default().include_values);
+    }
+
+    #[test]
+    fn test_capture_options_default_skips_state() {
+        assert!(!CaptureOptions::default().include_state);
+    }
+
+    #[test]
+    fn test_capture_tree_raw_bounded_pool_zero_handles() {
+        let options = CaptureOptions {
+            max_threads: Some(2),
+            ..CaptureOptions::default()
+        };
+        assert!(capture_tree_raw(&[0, 0], 50, &options).is_empty());
+    }
+
+    #[test]
+    fn test_capture_options_default_properties() {
+        assert_eq!(CaptureOptions::default().properties, UiaProperty::DEFAULT);
+    }
+
+    #[test]
+    fn test_uia_property_name_round_trip() {
+        for property in UiaProperty::ALL {
+            assert_eq!(UiaProperty::from_name(property.name()).unwrap(), property);
+        }
+        assert_eq!(UiaProperty::from_name("Help_Text").unwrap(), UiaProperty::HelpText);
+        assert!(UiaProperty::from_name("tooltip").is_err());
+    }
+
+    #[test]
+    fn test_filters_force_their_properties() {
+        let bare = CaptureOptions {
+            properties: vec![UiaProperty::BoundingRectangle],
+            ..CaptureOptions::default()
+        };
+        assert!(bare.reads(UiaProperty::BoundingRectangle));
+        assert!(!bare.reads(UiaProperty::ControlType));
+        assert!(!bare.reads(UiaProperty::Name));
+
+        let filtered = CaptureOptions {
+            control_type_filter: Some(vec!["Button".into()]),
+            controls_only: true,
+            ..bare
+        };
+        assert!(filtered.reads(UiaProperty::ControlType));
+        assert!(filtered.reads(UiaProperty::IsControlElement));
+        assert!(filtered.reads(UiaProperty::Name));
+        assert!(!filtered.reads(UiaProperty::HelpText));
+    }
+
+    #[test]
+    fn test_capture_options_default_max_children() {
+        assert_eq!(CaptureOptions::default().max_children, MAX_CHILDREN_PER_NODE);
+        assert_eq!(CaptureOptions::default().max_total_nodes, MAX_TOTAL_NODES);
+    }
+
+    fn snap(name: &str, depth: usize, children: Vec<TreeElementSnapshot>) -> TreeElementSnapshot {
+        typed(name, "Pane", depth, children)
+    }
+
+    fn typed(
+        name: &str,
+        control_type: &str,
+        depth: usize,
+        children: Vec<TreeElementSnapshot>,
+    ) -> TreeElementSnapshot {
+        TreeElementSnapshot {
+            name: name.to_owned(),
+            automation_id: String::new(),
+            control_type: control_type.to_owned(),
+            localized_control_type: String::new(),
+            class_name: String::new(),
+            bounding_rect: [0.0; 4],
+            is_offscreen: false,
+            is_enabled: true,
+            is_control_element: true,
+            has_keyboard_focus: false,
+            is_keyboard_focusable: false,
+            accelerator_key: String::new(),
+            help_text: String::new(),
+            access_key: String::new(),
+            culture: None,
+            value: String::new(),
+            toggle_state: None,
+            is_selected: None,
+            depth,
+            truncated: false,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_flatten_snapshots_pre_order_with_parents() {
+        let tree = snap(
+            "root",
+            0,
+            vec![
+                snap("a", 1, vec![snap("a1", 2, vec![]), snap("a2", 2, vec![])]),
+                snap("b", 1, vec![]),
+            ],
+        );
+        let flat = flatten_snapshots(vec![tree, snap("other", 0, vec![])]);
+
+        let names: Vec<&str> = flat.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["root", "a", "a1", "a2", "b", "other"]);
+        let parents: Vec<Option<usize>> = flat.iter().map(|f| f.parent_index).collect();
+        assert_eq!(parents, [None, Some(0), Some(1), Some(1), Some(0), None]);
+        assert!(flat.iter().enumerate().all(|(i, f)| f.index == i));
+    }
+
+    #[test]
+    fn test_control_type_filter_reparents_to_kept_ancestor() {
+        // Window > Pane > [Button ok, Group > Edit name], Text label
+        let tree = typed(
+            "win",
+            "Window",
+            0,
+            vec![
+                typed(
+                    "pane",
+                    "Pane",
+                    1,
+                    vec![
+                        typed("ok", "Button", 2, vec![]),
+                        typed("grp", "Group", 2, vec![typed("name", "Edit", 3, vec![])]),
+                    ],
+                ),
+                typed("label", "Text", 1, vec![]),
+            ],
+        );
+        let options = CaptureOptions {
+            control_type_filter: Some(vec!["button".into(), "Edit".into()]),
+            ..CaptureOptions::default()
+        };
+
+        let pruned = prune_tree(tree, &options);
+        assert_eq!(pruned.name, "win");
+        let names: Vec<&str> = pruned.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["ok", "name"]);
+        assert_eq!(pruned.children[1].depth, 3);
+    }
+
+    #[test]
+    fn test_controls_only_drops_unnamed_non_controls() {
+        let mut clutter = snap("", 1, vec![snap("btn", 2, vec![])]);
+        clutter.is_control_element = false;
+        let mut named = snap("Heading", 1, vec![]);
+        named.is_control_element = false;
+        let mut nested = snap("", 3, vec![]);
+        nested.is_control_element = false;
+        let tree = snap(
+            "root",
+            0,
+            vec![clutter, named, snap("list", 1, vec![nested])],
+        );
+        let options = CaptureOptions {
+            controls_only: true,
+            ..CaptureOptions::default()
+        };
+
+        let pruned = prune_tree(tree, &options);
+        let names: Vec<&str> = pruned.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["btn", "Heading", "list"]);
+        assert!(pruned.children[2].children.is_empty());
+    }
+
+    #[test]
+    fn test_skip_offscreen_reparents_visible_descendants() {
+        let visible = |name: &str, depth: usize, children| {
+            let mut node = snap(name, depth, children);
+            node.bounding_rect = [0.0, 0.0, 10.0, 10.0];
+            node
+        };
+        // root > [menu (offscreen) > [item], list > [row (zero rect) > [cell]], ok]
+        let mut menu = visible("menu", 1, vec![visible("item", 2, vec![])]);
+        menu.is_offscreen = true;
+        let row = snap("row", 2, vec![visible("cell", 3, vec![])]);
+        let mut root = snap(
+            "root",
+            0,
+            vec![menu, visible("list", 1, vec![row]), visible("ok", 1, vec![])],
+        );
+        root.is_offscreen = true;
+        let options = CaptureOptions {
+            skip_offscreen: true,
+            ..CaptureOptions::default()
+        };
+
+        let pruned = prune_tree(root, &options);
+        assert_eq!(pruned.name, "root");
+        let names: Vec<&str> = pruned.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["item", "list", "ok"]);
+        let list_kids: Vec<&str> =
+            pruned.children[1].children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(list_kids, ["cell"]);
+        assert_eq!(pruned.children[1].children[0].depth, 3);
+    }
+
+    #[test]
+    fn test_skip_offscreen_forces_its_properties() {
+        let options = CaptureOptions {
+            properties: vec![UiaProperty::Name],
+            skip_offscreen: true,
+            ..CaptureOptions::default()
+        };
+        assert!(options.reads(UiaProperty::IsOffscreen));
+        assert!(options.reads(UiaProperty::BoundingRectangle));
+    }
+
+    #[test]
+    fn test_no_filter_keeps_tree_intact() {
+        let tree = snap("root", 0, vec![snap("a", 1, vec![snap("b", 2, vec![])])]);
+        let pruned = prune_tree(tree, &CaptureOptions::default());
+        assert_eq!(pruned.children.len(), 1);
+        assert_eq!(pruned.children[0].children.len(), 1);
+    }
+
+    /// BFS over a synthetic tree: the source node is the snapshot itself.
+    fn bfs_over(tree: &TreeElementSnapshot, max_nodes: usize) -> TreeElementSnapshot {
+        bfs_snapshot(
+            tree,
+            max_nodes,
+            |n| (n.children.iter().collect(), false),
+            |n, depth| TreeElementSnapshot {
+                depth,
+                children: Vec::new(),
+                ..(*n).clone()
+            },
+        )
+        .expect("root is always visited")
+    }
+
+    /// Synthetic tree where every node has `fanout` children, down to
+    /// `levels` below the root; nodes are `(depth, index)` pairs.
+    fn synthetic_walk(fanout: usize, levels: usize, limit: usize) -> TreeElementSnapshot {
+        let budget = NodeBudget::new(limit);
+        budget.take(); // the root, as walk_element does
+        walk_tree(
+            &(0_usize, 0_usize),
+            0,
+            levels,
+            &budget,
+            &|&(depth, index)| {
+                let children = (0..fanout).map(|i| (depth + 1, index * fanout + i));
+                (children.collect(), false)
+            },
+            &|&(_, index), depth| snap(&index.to_string(), depth, vec![]),
+        )
+    }
+
+    #[test]
+    fn test_walk_tree_stops_at_total_node_limit() {
+        // 10-ary, 6 levels: over a million nodes without the ceiling.
+        let out = synthetic_walk(10, 6, 100);
+        let flat = flatten_snapshots(vec![out.clone()]);
+        assert_eq!(flat.len(), 100);
+
+        // Depth-first: the first branch is walked to the bottom first.
+        let mut node = &out;
+        for depth in 1..=6 {
+            node = &node.children[0];
+            assert_eq!(node.depth, depth);
+        }
+        assert!(out.truncated);
+        assert!(out.children.len() < 10);
+    }
+
+    #[test]
+    fn test_walk_tree_within_limit_is_complete() {
+        let out = synthetic_walk(3, 3, MAX_TOTAL_NODES);
+        assert_eq!(flatten_snapshots(vec![out.clone()]).len(), 1 + 3 + 9 + 27);
+        assert!(!out.truncated);
+        assert!(out.children.iter().all(|c| !c.truncated));
+    }
+
+    #[test]
+    fn test_walk_tree_zero_limit_keeps_root() {
+        let out = synthetic_walk(4, 2, 0);
+        assert!(out.children.is_empty());
+        assert!(out.truncated);
+    }
+
+    #[test]
+    fn test_bfs_visits_levels_before_descending() {
+        // root > [a > [a1 > [a1x], a2], b > [b1]]
+        let tree = snap(
+            "root",
+            0,
+            vec![
+                snap(
+                    "a",
+                    1,
+                    vec![snap("a1", 2, vec![snap("a1x", 3, vec![])]), snap("a2", 2, vec![])],
+                ),
+                snap("b", 1, vec![snap("b1", 2, vec![])]),
+            ],
+        );
+
+        // Budget 4: root, a, b, a1 (DFS would have taken root, a, a1, a1x)
+        let out = bfs_over(&tree, 4);
+        let level1: Vec<&str> = out.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(level1, ["a", "b"]);
+        let a_kids: Vec<&str> = out.children[0].children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(a_kids, ["a1"]);
+        assert!(out.children[1].children.is_empty());
+        assert!(out.children[0].truncated);
+        assert!(out.children[1].truncated);
+        assert!(out.children[0].children[0].truncated);
+        assert!(!out.truncated);
+
+        let flat = flatten_snapshots(vec![out]);
+        assert_eq!(flat.len(), 4);
+    }
+
+    #[test]
+    fn test_bfs_full_budget_matches_tree() {
+        let tree = snap(
+            "root",
+            0,
+            vec![snap("a", 1, vec![snap("a1", 2, vec![])]), snap("b", 1, vec![])],
+        );
+        let out = bfs_over(&tree, 100);
+        let names: Vec<String> = flatten_snapshots(vec![out])
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(names, ["root", "a", "a1", "b"]);
+    }
+
+    #[test]
+    fn test_bfs_zero_budget_keeps_root() {
+        let tree = snap("root", 0, vec![snap("a", 1, vec![])]);
+        let out = bfs_over(&tree, 0);
+        assert_eq!(out.name, "root");
+        assert!(out.children.is_empty());
+        assert!(out.truncated);
+    }
+
+    #[test]
+    fn test_write_tree_json_empty_handles() {
+        let mut out = Vec::new();
+        write_tree_json(&[0], 50, &CaptureOptions::default(), &mut out, false).unwrap();
+        assert_eq!(out, b"[]");
+    }
+
+    #[test]
+    fn test_capture_tree_flat_raw_empty_handles() {
+        assert!(capture_tree_flat_raw(&[], 50, &CaptureOptions::default()).is_empty());
+    }
+
+    /// Manual benchmark: captures up to 50 visible windows on this thread,
+    /// first creating a fresh UIA instance and apartment per window (as
+    /// before the thread-local cache), then reusing the cached one.
+    #[test]
+    #[ignore = "needs an interactive desktop; run manually for timings"]
+    fn bench_thread_uia_reuse() {
+        let mut handles = crate::window::enumerate_visible_windows().unwrap_or_default();
+        handles.truncate(50);
+        let options = CaptureOptions::default();
+        for (pass, fresh) in [("before (fresh instance)", true), ("after (reused)", false)] {
+            let budget = NodeBudget::new(usize::MAX);
+            let start = std::time::Instant::now();
+            let mut captured = 0;
+            for &handle in &handles {
+                if fresh {
+                    release_thread_uia();
+                }
+                if capture_window(handle, MAX_TREE_DEPTH, &options, &budget).is_ok() {
+                    captured += 1;
+                }
+            }
+            println!(
+                "{pass}: {captured} of {} windows in {:?}",
+                handles.len(),
+                start.elapsed()
+            );
+        }
+    }
+
     #[test]
     fn test_max_children_per_node_constant() {
         assert_eq!(MAX_CHILDREN_PER_NODE, 512);
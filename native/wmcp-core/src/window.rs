@@ -4,17 +4,28 @@
 //! require Python `win32gui` or ctypes calls.  All functions return owned
 //! structs, never raw handles.
 
+use std::collections::HashSet;
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-use serde::Serialize;
-use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, TRUE};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use windows::Win32::Foundation::{BOOL, FALSE, HWND, LPARAM, POINT, RECT, TRUE, WPARAM};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, MonitorFromWindow, HDC, HMONITOR, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
 use windows::Win32::UI::WindowsAndMessaging::{
-    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowRect,
+    BringWindowToTop, EnumWindows, GetAncestor, GetClassNameW, GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowRect,
     GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindow,
-    IsWindowVisible, IsZoomed, GWL_EXSTYLE, GWL_STYLE, GW_OWNER, WS_EX_APPWINDOW,
-    WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_VISIBLE,
+    IsWindowVisible, IsZoomed, PostMessageW, SetForegroundWindow, SetWindowPos, ShowWindow, GWL_EXSTYLE, GWL_STYLE,
+    GW_HWNDPREV, GW_OWNER, SHOW_WINDOW_CMD, SWP_NOACTIVATE, SWP_NOZORDER, SW_HIDE, SW_MAXIMIZE, SW_MINIMIZE,
+    SW_RESTORE, SW_SHOW, WM_CLOSE, WS_EX_APPWINDOW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+    WindowFromPoint, GA_ROOT, WS_VISIBLE, SendMessageTimeoutW, SMTO_ABORTIFHUNG, WM_GETTEXT,
+    HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOMOVE, SWP_NOSIZE, WS_EX_TOPMOST,
 };
 
 use crate::errors::WindowsMcpError;
@@ -24,7 +35,7 @@ use crate::errors::WindowsMcpError;
 // ---------------------------------------------------------------------------
 
 /// Owned snapshot of a visible window.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowInfo {
     pub hwnd: isize,
     pub title: String,
@@ -34,10 +45,24 @@ pub struct WindowInfo {
     pub is_minimized: bool,
     pub is_maximized: bool,
     pub is_visible: bool,
+    /// Hidden by DWM despite being "visible": on another virtual desktop,
+    /// or a suspended UWP app.
+    pub is_cloaked: bool,
+    /// Always-on-top (`WS_EX_TOPMOST`); see [`set_window_topmost`].
+    pub is_topmost: bool,
+    /// Position among the listed (visible, titled, Alt+Tab) windows from
+    /// the top of the Z order: 0 is topmost.  Lower values overlap higher.
+    pub z_order: usize,
+    /// Index of the monitor holding most of the window (the nearest one
+    /// if it is off-screen), in DXGI output order -- the `monitor_index`
+    /// to pass when screenshotting it.  This is the screenshot order, not
+    /// `EnumDisplayMonitors` order, since the two can disagree.  0 if DXGI
+    /// is unavailable or the monitor is not one of its outputs.
+    pub monitor_index: u32,
 }
 
 /// Window bounding rectangle in screen coordinates.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowRect {
     pub left: i32,
     pub top: i32,
@@ -49,8 +74,39 @@ pub struct WindowRect {
 // Helpers
 // ---------------------------------------------------------------------------
 
-/// Read the window title (up to 512 chars).
+/// Convert `handle` to an `HWND`, erroring if it is not an existing window.
+fn checked_hwnd(handle: isize) -> Result<HWND, WindowsMcpError> {
+    let hwnd = HWND(handle as *mut core::ffi::c_void);
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+        return Err(WindowsMcpError::ComError(format!(
+            "Invalid window handle: {handle}"
+        )));
+    }
+    Ok(hwnd)
+}
+
+/// Longest `WM_GETTEXT` reply [`read_window_title`] accepts, in UTF-16 units.
+const WM_GETTEXT_MAX_CHARS: usize = 512;
+
+/// How long [`read_window_title`] waits for a `WM_GETTEXT` reply.
+const WM_GETTEXT_TIMEOUT_MS: u32 = 100;
+
+/// Read the window title.
+///
+/// Tries `GetWindowTextW` first.  Some custom-drawn or cross-process
+/// windows only answer `WM_GETTEXT` sent to their thread, so an empty
+/// result falls back to `SendMessageTimeoutW` (up to 512 chars), which
+/// gives up after a short timeout or at once if the owner is hung.
 fn read_window_title(hwnd: HWND) -> String {
+    let title = read_window_text_fast(hwnd);
+    if title.is_empty() {
+        read_window_text_by_message(hwnd)
+    } else {
+        title
+    }
+}
+
+fn read_window_text_fast(hwnd: HWND) -> String {
     let len = unsafe { GetWindowTextLengthW(hwnd) };
     if len <= 0 {
         return String::new();
@@ -65,6 +121,28 @@ fn read_window_title(hwnd: HWND) -> String {
         .into_owned()
 }
 
+fn read_window_text_by_message(hwnd: HWND) -> String {
+    let mut buf = vec![0u16; WM_GETTEXT_MAX_CHARS + 1];
+    let mut copied: usize = 0;
+    let sent = unsafe {
+        SendMessageTimeoutW(
+            hwnd,
+            WM_GETTEXT,
+            WPARAM(buf.len()),
+            LPARAM(buf.as_mut_ptr() as isize),
+            SMTO_ABORTIFHUNG,
+            WM_GETTEXT_TIMEOUT_MS,
+            Some(&mut copied),
+        )
+    };
+    if sent.0 == 0 || copied == 0 {
+        return String::new();
+    }
+    OsString::from_wide(&buf[..copied.min(WM_GETTEXT_MAX_CHARS)])
+        .to_string_lossy()
+        .into_owned()
+}
+
 /// Read the window class name (up to 256 chars).
 fn read_class_name(hwnd: HWND) -> String {
     let mut buf = [0u16; 256];
@@ -151,6 +229,34 @@ fn is_alt_tab_window(hwnd: HWND) -> bool {
     true
 }
 
+/// Map a state name accepted by [`set_window_state`] to its `SW_*` command.
+fn show_command(state: &str) -> Option<SHOW_WINDOW_CMD> {
+    match state.to_ascii_lowercase().as_str() {
+        "minimize" => Some(SW_MINIMIZE),
+        "maximize" => Some(SW_MAXIMIZE),
+        "restore" => Some(SW_RESTORE),
+        "hide" => Some(SW_HIDE),
+        "show" => Some(SW_SHOW),
+        _ => None,
+    }
+}
+
+/// Whether a window with `title`, `class` and `pid` passes the
+/// [`find_windows`] filters.  `title_lower` is the title needle already
+/// lowercased; `None` filters match anything.
+fn matches_filters(
+    title: &str,
+    class: &str,
+    pid: u32,
+    title_lower: Option<&str>,
+    class_name: Option<&str>,
+    want_pid: Option<u32>,
+) -> bool {
+    title_lower.map_or(true, |needle| title.to_lowercase().contains(needle))
+        && class_name.map_or(true, |c| class == c)
+        && want_pid.map_or(true, |p| pid == p)
+}
+
 /// Callback for EnumWindows that collects visible window handles.
 ///
 /// # Safety
@@ -162,53 +268,130 @@ fn is_alt_tab_window(hwnd: HWND) -> bool {
 unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
     let handles = unsafe { &mut *(lparam.0 as *mut Vec<HWND>) };
 
-    if unsafe { IsWindowVisible(hwnd) }.as_bool() && is_alt_tab_window(hwnd) {
-        // Skip windows with no title
-        let title_len = unsafe { GetWindowTextLengthW(hwnd) };
-        if title_len > 0 {
-            handles.push(hwnd);
-        }
+    if is_listed_window(hwnd) {
+        handles.push(hwnd);
     }
 
     TRUE // continue enumeration
 }
 
-// ---------------------------------------------------------------------------
-// Public API
-// ---------------------------------------------------------------------------
+/// Callback for EnumWindows that collects every top-level window handle.
+///
+/// # Safety
+///
+/// As for [`enum_callback`].
+unsafe extern "system" fn enum_all_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let handles = unsafe { &mut *(lparam.0 as *mut Vec<HWND>) };
+    handles.push(hwnd);
+    TRUE // continue enumeration
+}
 
-/// Enumerate all visible top-level windows.
+/// Whether [`enumerate_visible_windows`] reports `hwnd`: visible, passes
+/// the Alt+Tab filter, and has a title.
 ///
-/// Returns a list of window handles for windows that are visible, have a
-/// title, and appear in the Alt+Tab list (not tool windows, not cloaked).
-pub fn enumerate_visible_windows() -> Result<Vec<isize>, WindowsMcpError> {
-    let mut handles: Vec<HWND> = Vec::with_capacity(64);
-    let result = unsafe {
-        EnumWindows(
-            Some(enum_callback),
-            LPARAM(&mut handles as *mut Vec<HWND> as isize),
-        )
-    };
+/// The title check is the in-process `GetWindowTextLengthW`, not
+/// [`read_window_title`]: this runs inside `EnumWindows` and for every
+/// window in a [`get_window_z_order`] walk, where a cross-process
+/// `WM_GETTEXT` per untitled window would add up to 100ms each.  Windows
+/// whose title only answers `WM_GETTEXT` are therefore not listed.
+fn is_listed_window(hwnd: HWND) -> bool {
+    passes_list_filter(
+        unsafe { IsWindowVisible(hwnd) }.as_bool(),
+        || is_alt_tab_window(hwnd),
+        || unsafe { GetWindowTextLengthW(hwnd) } > 0,
+    )
+}
 
-    result.map_err(|e| {
-        WindowsMcpError::ComError(format!("EnumWindows failed: {e}"))
-    })?;
+/// The [`is_listed_window`] rule.  Each check only runs for windows that
+/// passed the cheaper ones before it.
+fn passes_list_filter(
+    visible: bool,
+    alt_tab: impl FnOnce() -> bool,
+    has_title: impl FnOnce() -> bool,
+) -> bool {
+    visible && alt_tab() && has_title()
+}
 
-    Ok(handles.iter().map(|h| h.0 as isize).collect())
+/// `EnumDisplayMonitors` callback collecting raw `HMONITOR` values.
+unsafe extern "system" fn monitor_enum_callback(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    lparam: LPARAM,
+) -> BOOL {
+    let monitors = unsafe { &mut *(lparam.0 as *mut Vec<isize>) };
+    monitors.push(monitor.0 as isize);
+    TRUE
 }
 
-/// Get detailed information about a window by its handle.
+/// Screenshot monitor order as raw `HMONITOR` values, cached because DXGI
+/// enumeration creates a factory and walks every adapter.
+static SCREENSHOT_MONITORS: OnceLock<Mutex<Vec<isize>>> = OnceLock::new();
+
+/// Whether `cached` holds exactly the monitors in `current`, in any order.
+fn same_monitor_set(cached: &[isize], current: &[isize]) -> bool {
+    cached.len() == current.len() && current.iter().all(|m| cached.contains(m))
+}
+
+/// Monitor handles in screenshot `monitor_index` order (DXGI adapter and
+/// output order, which can differ from `EnumDisplayMonitors` order).
 ///
-/// Returns an error if the handle is invalid or the window has been destroyed.
-pub fn get_window_info(handle: isize) -> Result<WindowInfo, WindowsMcpError> {
-    let hwnd = HWND(handle as *mut core::ffi::c_void);
+/// The DXGI walk is only repeated when the cheap `EnumDisplayMonitors`
+/// set no longer matches the cached one, i.e. after a display change.
+/// Empty if DXGI is unavailable, e.g. in a session without a display.
+fn screenshot_monitors() -> Vec<HMONITOR> {
+    let mut current: Vec<isize> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(monitor_enum_callback),
+            LPARAM(&mut current as *mut Vec<isize> as isize),
+        );
+    }
 
-    // Validate that the handle refers to an existing window
-    if !unsafe { IsWindow(hwnd) }.as_bool() {
-        return Err(WindowsMcpError::ComError(format!(
-            "Invalid window handle: {handle}"
-        )));
+    let mut cached = SCREENSHOT_MONITORS
+        .get_or_init(|| Mutex::new(Vec::new()))
+        .lock();
+    if !same_monitor_set(&cached, &current) {
+        *cached = match crate::screenshot::dxgi_monitors() {
+            Ok(monitors) => monitors.iter().map(|(m, _)| m.0 as isize).collect(),
+            Err(e) => {
+                log::warn!("window monitor index: DXGI outputs unavailable: {e}");
+                Vec::new()
+            }
+        };
+    }
+    cached.iter().map(|&m| HMONITOR(m as _)).collect()
+}
+
+/// Position of the monitor `MonitorFromWindow` picks for `hwnd` in
+/// `monitors` (from [`screenshot_monitors`]).  Falls back to 0, the
+/// primary output, with a debug log if it is not found.
+fn monitor_index_of(hwnd: HWND, monitors: &[HMONITOR]) -> u32 {
+    let target = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+    match monitors.iter().position(|&m| m == target) {
+        Some(index) => index as u32,
+        None => {
+            log::debug!(
+                "window monitor index: monitor of {:?} is not a DXGI output; using 0",
+                hwnd.0
+            );
+            0
+        }
     }
+}
+
+/// Gather a [`WindowInfo`] for `handle` with a known `z_order`.
+///
+/// `monitors` is [`screenshot_monitors`], read once per listing.
+fn read_window_info(
+    handle: isize,
+    z_order: usize,
+    monitors: &[HMONITOR],
+) -> Result<WindowInfo, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let hwnd = checked_hwnd(handle)?;
 
     let title = read_window_title(hwnd);
     let class_name = read_class_name(hwnd);
@@ -222,6 +405,10 @@ pub fn get_window_info(handle: isize) -> Result<WindowInfo, WindowsMcpError> {
     let is_minimized = unsafe { IsIconic(hwnd) }.as_bool();
     let is_maximized = unsafe { IsZoomed(hwnd) }.as_bool();
     let is_visible = unsafe { IsWindowVisible(hwnd) }.as_bool();
+    let is_cloaked = is_cloaked(hwnd);
+    let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+    let is_topmost = ex_style & WS_EX_TOPMOST.0 != 0;
+    let monitor_index = monitor_index_of(hwnd, monitors);
 
     Ok(WindowInfo {
         hwnd: handle,
@@ -237,9 +424,101 @@ pub fn get_window_info(handle: isize) -> Result<WindowInfo, WindowsMcpError> {
         is_minimized,
         is_maximized,
         is_visible,
+        is_cloaked,
+        is_topmost,
+        z_order,
+        monitor_index,
     })
 }
 
+/// How often [`click_and_wait_for_window`] re-enumerates windows.
+const WINDOW_POLL_MS: u64 = 50;
+
+/// The first handle in `current` that is not in `before`.
+fn first_new_window(before: &HashSet<isize>, current: &[isize]) -> Option<isize> {
+    current.iter().copied().find(|h| !before.contains(h))
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// Enumerate all visible top-level windows.
+///
+/// Returns a list of window handles for windows that are visible, have a
+/// title, and appear in the Alt+Tab list (not tool windows, not cloaked),
+/// in Z order from topmost to bottom -- the order `EnumWindows` visits
+/// top-level windows in.
+pub fn enumerate_visible_windows() -> Result<Vec<isize>, WindowsMcpError> {
+    let mut handles: Vec<HWND> = Vec::with_capacity(64);
+    let result = unsafe {
+        EnumWindows(
+            Some(enum_callback),
+            LPARAM(&mut handles as *mut Vec<HWND> as isize),
+        )
+    };
+
+    result.map_err(|e| {
+        WindowsMcpError::ComError(format!("EnumWindows failed: {e}"))
+    })?;
+
+    Ok(handles.iter().map(|h| h.0 as isize).collect())
+}
+
+/// Every visible top-level window in Z order, without the Alt+Tab filter
+/// (so owned dialogs and tool windows are included).
+fn visible_top_level_windows(include_cloaked: bool) -> Result<Vec<HWND>, WindowsMcpError> {
+    let mut handles: Vec<HWND> = Vec::with_capacity(256);
+    unsafe {
+        EnumWindows(
+            Some(enum_all_callback),
+            LPARAM(&mut handles as *mut Vec<HWND> as isize),
+        )
+    }
+    .map_err(|e| WindowsMcpError::ComError(format!("EnumWindows failed: {e}")))?;
+
+    handles.retain(|&hwnd| {
+        unsafe { IsWindowVisible(hwnd) }.as_bool() && (include_cloaked || !is_cloaked(hwnd))
+    });
+    Ok(handles)
+}
+
+/// Get detailed information about a window by its handle.
+///
+/// Returns an error if the handle is invalid or the window has been destroyed.
+/// `z_order` is computed with [`get_window_z_order`].
+pub fn get_window_info(handle: isize) -> Result<WindowInfo, WindowsMcpError> {
+    read_window_info(handle, get_window_z_order(handle)?, &screenshot_monitors())
+}
+
+/// Count the listed windows above `handle` in the Z order by walking
+/// `GetWindow(GW_HWNDPREV)`; 0 means no listed window is above it.
+///
+/// For a listed window this equals its index in [`list_windows`].  For a
+/// child window the walk runs over its siblings instead.
+pub fn get_window_z_order(handle: isize) -> Result<usize, WindowsMcpError> {
+    let mut current = checked_hwnd(handle)?;
+    let mut above = 0;
+    while let Ok(prev) = unsafe { GetWindow(current, GW_HWNDPREV) } {
+        if prev.is_invalid() {
+            break;
+        }
+        if is_listed_window(prev) {
+            above += 1;
+        }
+        current = prev;
+    }
+    Ok(above)
+}
+
+/// Index of the monitor a window is mostly on, as in
+/// [`WindowInfo::monitor_index`].
+pub fn get_window_monitor_index(handle: isize) -> Result<u32, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let hwnd = checked_hwnd(handle)?;
+    Ok(monitor_index_of(hwnd, &screenshot_monitors()))
+}
+
 /// Get the foreground (active) window handle.
 ///
 /// Returns 0 if no window is in the foreground.
@@ -248,19 +527,351 @@ pub fn get_foreground_hwnd() -> isize {
     hwnd.0 as isize
 }
 
-/// Get information about all visible windows.
+/// Get information about all visible windows, in Z order from topmost
+/// (`z_order` 0) to bottom.
 ///
 /// Convenience function that enumerates windows and collects info for each.
 /// Windows that become invalid between enumeration and info-gathering are
-/// silently skipped (TOCTOU race inherent to Win32 window enumeration).
+/// silently skipped (TOCTOU race inherent to Win32 window enumeration);
+/// `z_order` keeps the enumeration position, so it can skip a value.
 pub fn list_windows() -> Result<Vec<WindowInfo>, WindowsMcpError> {
     let handles = enumerate_visible_windows()?;
+    let monitors = screenshot_monitors();
     let mut windows = Vec::with_capacity(handles.len());
-    for handle in handles {
-        match get_window_info(handle) {
+    for (z_order, handle) in handles.into_iter().enumerate() {
+        match read_window_info(handle, z_order, &monitors) {
             Ok(info) => windows.push(info),
             Err(_) => continue, // skip inaccessible windows (TOCTOU race)
         }
     }
     Ok(windows)
 }
+
+/// Get the top-level window under screen point `(x, y)` (physical pixels).
+///
+/// `WindowFromPoint` returns the deepest child window at the point; this
+/// climbs to its root with `GetAncestor(GA_ROOT)` so the result matches
+/// the windows [`list_windows`] reports.  Returns a `ComError` if there is
+/// no window at the point.
+pub fn window_from_point(x: i32, y: i32) -> Result<WindowInfo, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let child = unsafe { WindowFromPoint(POINT { x, y }) };
+    if child.is_invalid() {
+        return Err(WindowsMcpError::ComError(format!("No window at ({x},{y})")));
+    }
+    let root = unsafe { GetAncestor(child, GA_ROOT) };
+    let hwnd = if root.is_invalid() { child } else { root };
+    get_window_info(hwnd.0 as isize)
+}
+
+/// Get information about every visible top-level window, without the
+/// Alt+Tab filter that [`list_windows`] applies.
+///
+/// Tool windows, owned popups and untitled windows are included.  Cloaked
+/// windows -- those on other virtual desktops, or suspended UWP apps --
+/// are included only with `include_cloaked`; check
+/// [`WindowInfo::is_cloaked`] to tell them apart.  Windows are returned
+/// in Z order, and `z_order` is the position in this unfiltered list.
+pub fn list_all_windows(include_cloaked: bool) -> Result<Vec<WindowInfo>, WindowsMcpError> {
+    let monitors = screenshot_monitors();
+    let mut windows = Vec::new();
+    for hwnd in visible_top_level_windows(include_cloaked)? {
+        if let Ok(info) = read_window_info(hwnd.0 as isize, windows.len(), &monitors) {
+            windows.push(info); // skip windows closed since enumeration
+        }
+    }
+    Ok(windows)
+}
+
+/// Find visible top-level windows by title substring (case-insensitive),
+/// exact class name, and/or owning process ID.
+///
+/// Filters left as `None` match everything, so `find_windows(None, None,
+/// None)` is equivalent to [`list_windows`].  Windows are matched on their
+/// title, class and PID before the full [`WindowInfo`] is gathered, so
+/// non-matching windows cost only those three reads.
+pub fn find_windows(
+    title_substr: Option<&str>,
+    class_name: Option<&str>,
+    pid: Option<u32>,
+) -> Result<Vec<WindowInfo>, WindowsMcpError> {
+    let title_lower = title_substr.map(str::to_lowercase);
+    let handles = enumerate_visible_windows()?;
+    let monitors = screenshot_monitors();
+
+    let mut windows = Vec::new();
+    for (z_order, handle) in handles.into_iter().enumerate() {
+        let hwnd = HWND(handle as *mut core::ffi::c_void);
+        let matched = matches_filters(
+            &read_window_title(hwnd),
+            &read_class_name(hwnd),
+            read_pid(hwnd),
+            title_lower.as_deref(),
+            class_name,
+            pid,
+        );
+        if matched {
+            if let Ok(info) = read_window_info(handle, z_order, &monitors) {
+                windows.push(info); // skip windows closed since enumeration
+            }
+        }
+    }
+    Ok(windows)
+}
+
+/// Move and resize a window to `(x, y)` with outer size `width` x `height`,
+/// all in physical screen pixels (the same space as [`WindowRect`]).
+///
+/// Neither the Z order nor the focus changes.  A maximized window keeps
+/// its maximized state; restore it first to get a normal window at the
+/// new rectangle.
+pub fn set_window_rect(
+    handle: isize,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+) -> Result<(), WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let hwnd = checked_hwnd(handle)?;
+
+    unsafe { SetWindowPos(hwnd, None, x, y, width, height, SWP_NOZORDER | SWP_NOACTIVATE) }
+        .map_err(|e| {
+            WindowsMcpError::ComError(format!("SetWindowPos failed for handle {handle}: {e}"))
+        })
+}
+
+/// Make a window always-on-top (`topmost = true`) or return it to the
+/// normal Z band, via `SetWindowPos` with `HWND_TOPMOST` /
+/// `HWND_NOTOPMOST`.
+///
+/// Position, size and focus are unchanged.  Returns an error for an
+/// invalid handle or if `SetWindowPos` fails (e.g. a window of a more
+/// privileged process).
+pub fn set_window_topmost(handle: isize, topmost: bool) -> Result<(), WindowsMcpError> {
+    let hwnd = checked_hwnd(handle)?;
+    let insert_after = if topmost {
+        HWND_TOPMOST
+    } else {
+        HWND_NOTOPMOST
+    };
+
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            insert_after,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        )
+    }
+    .map_err(|e| {
+        WindowsMcpError::ComError(format!(
+            "SetWindowPos (topmost) failed for handle {handle}: {e}"
+        ))
+    })
+}
+
+/// Change a window's show state: `"minimize"`, `"maximize"`, `"restore"`,
+/// `"hide"` or `"show"` (case-insensitive), via `ShowWindow`.
+///
+/// Returns an error for an invalid handle or an unknown state name.
+pub fn set_window_state(handle: isize, state: &str) -> Result<(), WindowsMcpError> {
+    let command = show_command(state).ok_or_else(|| {
+        WindowsMcpError::ComError(format!(
+            "Unknown window state '{state}'; expected minimize, maximize, restore, hide or show"
+        ))
+    })?;
+    let hwnd = checked_hwnd(handle)?;
+
+    // ShowWindow's return value is the previous visibility, not a status.
+    let _ = unsafe { ShowWindow(hwnd, command) };
+    Ok(())
+}
+
+/// Bring a window to the foreground and give it keyboard focus, so that
+/// `SendInput` keystrokes reach it.  A minimized window is restored first.
+///
+/// Windows only lets the process that owns the foreground window (or one
+/// that received the last input event) call `SetForegroundWindow`
+/// successfully.  To get past this foreground lock the calling thread
+/// temporarily attaches its input queue to the current foreground
+/// window's thread, which lets it share that thread's foreground rights.
+///
+/// Returns an error if the window is still not in the foreground
+/// afterwards -- e.g. when a full-screen app or the secure desktop holds
+/// the lock.
+pub fn focus_window(handle: isize) -> Result<(), WindowsMcpError> {
+    let hwnd = checked_hwnd(handle)?;
+
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+
+        let foreground = GetForegroundWindow();
+        if foreground == hwnd {
+            return Ok(());
+        }
+
+        let this_thread = GetCurrentThreadId();
+        let foreground_thread = if foreground.is_invalid() {
+            0
+        } else {
+            GetWindowThreadProcessId(foreground, None)
+        };
+        let attached = foreground_thread != 0
+            && foreground_thread != this_thread
+            && AttachThreadInput(this_thread, foreground_thread, TRUE).as_bool();
+
+        let _ = BringWindowToTop(hwnd);
+        let _ = SetForegroundWindow(hwnd);
+
+        if attached {
+            let _ = AttachThreadInput(this_thread, foreground_thread, FALSE);
+        }
+    }
+
+    if unsafe { GetForegroundWindow() } != hwnd {
+        return Err(WindowsMcpError::ComError(format!(
+            "Could not bring window {handle} to the foreground (foreground lock)"
+        )));
+    }
+    Ok(())
+}
+
+/// Ask a window to close by posting `WM_CLOSE`.
+///
+/// This is a polite request, equivalent to clicking the title-bar close
+/// button: the target application may ignore it, or prompt (e.g. to save
+/// changes) and stay open.  Success only means the message was queued;
+/// check [`get_window_info`] afterwards to see whether the window is gone.
+pub fn close_window(handle: isize) -> Result<(), WindowsMcpError> {
+    let hwnd = checked_hwnd(handle)?;
+
+    unsafe { PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)) }.map_err(|e| {
+        WindowsMcpError::ComError(format!("PostMessageW(WM_CLOSE) failed for handle {handle}: {e}"))
+    })
+}
+
+/// Click at `(x, y)` with `button` and wait for a new top-level window,
+/// e.g. the dialog a toolbar button opens.
+///
+/// Records the visible top-level windows, clicks, then re-enumerates every
+/// 50ms until a window not in the original set appears or `timeout_ms`
+/// elapses.  Unlike [`enumerate_visible_windows`], the comparison includes
+/// owned windows, since most dialogs are owned by the window that opened
+/// them.  When several appear at once the topmost is returned.  At least
+/// one check is made after the click.
+///
+/// Returns `None` on timeout.  Returns an error if the click cannot be
+/// injected or windows cannot be enumerated.
+pub fn click_and_wait_for_window(
+    x: i32,
+    y: i32,
+    button: &str,
+    timeout_ms: u64,
+) -> Result<Option<WindowInfo>, WindowsMcpError> {
+    crate::dpi::ensure_per_monitor_aware();
+    let handles = || -> Result<Vec<isize>, WindowsMcpError> {
+        Ok(visible_top_level_windows(false)?
+            .into_iter()
+            .map(|hwnd| hwnd.0 as isize)
+            .collect())
+    };
+
+    let before: HashSet<isize> = handles()?.into_iter().collect();
+    crate::input::send_click_raw(x, y, button)?;
+
+    let timeout = Duration::from_millis(timeout_ms);
+    let start = Instant::now();
+    loop {
+        if let Some(handle) = first_new_window(&before, &handles()?) {
+            // A window that closed again right away is skipped.
+            if let Ok(info) = get_window_info(handle) {
+                return Ok(Some(info));
+            }
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(WINDOW_POLL_MS).min(timeout - elapsed));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_show_command_names() {
+        assert_eq!(show_command("minimize"), Some(SW_MINIMIZE));
+        assert_eq!(show_command("Maximize"), Some(SW_MAXIMIZE));
+        assert_eq!(show_command("RESTORE"), Some(SW_RESTORE));
+        assert_eq!(show_command("hide"), Some(SW_HIDE));
+        assert_eq!(show_command("show"), Some(SW_SHOW));
+        assert_eq!(show_command("close"), None);
+        assert_eq!(show_command(""), None);
+    }
+
+    #[test]
+    fn test_matches_filters() {
+        let m = |title, class, pid| {
+            matches_filters("Inbox - Google Chrome", "Chrome_WidgetWin_1", 42, title, class, pid)
+        };
+        assert!(m(None, None, None));
+        assert!(m(Some("google chrome"), None, None));
+        assert!(!m(Some("firefox"), None, None));
+        assert!(m(None, Some("Chrome_WidgetWin_1"), Some(42)));
+        assert!(!m(None, Some("chrome_widgetwin_1"), None)); // class is exact
+        assert!(!m(Some("inbox"), None, Some(7)));
+    }
+
+    #[test]
+    fn test_set_window_state_rejects_unknown_state() {
+        let err = set_window_state(0, "fullscreen").unwrap_err();
+        assert!(matches!(err, WindowsMcpError::ComError(ref m) if m.contains("fullscreen")));
+    }
+
+    #[test]
+    fn test_invalid_handle_rejected() {
+        assert!(matches!(set_window_state(0, "show"), Err(WindowsMcpError::ComError(_))));
+        assert!(matches!(close_window(0), Err(WindowsMcpError::ComError(_))));
+    }
+
+    #[test]
+    fn test_passes_list_filter() {
+        assert!(passes_list_filter(true, || true, || true));
+        assert!(!passes_list_filter(true, || true, || false));
+        assert!(!passes_list_filter(true, || false, || panic!("title read on a tool window")));
+        assert!(!passes_list_filter(
+            false,
+            || panic!("alt-tab check on a hidden window"),
+            || panic!("title read on a hidden window"),
+        ));
+    }
+
+    #[test]
+    fn test_same_monitor_set() {
+        assert!(same_monitor_set(&[1, 2, 3], &[3, 1, 2]));
+        assert!(same_monitor_set(&[], &[]));
+        assert!(!same_monitor_set(&[], &[1]));
+        assert!(!same_monitor_set(&[1, 2], &[1, 3]));
+        assert!(!same_monitor_set(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn test_first_new_window() {
+        let before: HashSet<isize> = [10, 20, 30].into_iter().collect();
+        assert_eq!(first_new_window(&before, &[10, 20, 30]), None);
+        assert_eq!(first_new_window(&before, &[40, 10, 50, 20]), Some(40));
+        assert_eq!(first_new_window(&before, &[20]), None);
+    }
+}
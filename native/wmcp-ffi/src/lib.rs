@@ -3,30 +3,175 @@
 //! All exported functions follow the convention:
 //! - Return `i32` status code: `WMCP_OK=0`, `WMCP_ERROR=-1`
 //! - String outputs allocated by Rust, freed via `wmcp_free_string()`
+//! - Byte buffer outputs (images) freed via `wmcp_free_buffer()`
 //! - Last error retrievable via `wmcp_last_error()`
 
 use std::ffi::{c_char, CStr, CString};
 use std::ptr;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+
+use wmcp_core::errors::WindowsMcpError;
 
 pub const WMCP_OK: i32 = 0;
 pub const WMCP_ERROR: i32 = -1;
 
+/// Error categories for `wmcp_last_error_code`.  Codes 1-5, 7 and 8
+/// correspond to the `WindowsMcpError` variants; the values are stable.
+pub const WMCP_ERR_NONE: i32 = 0;
+pub const WMCP_ERR_SYSTEM_INFO: i32 = 1;
+pub const WMCP_ERR_COM: i32 = 2;
+pub const WMCP_ERR_TREE: i32 = 3;
+pub const WMCP_ERR_INPUT: i32 = 4;
+pub const WMCP_ERR_SCREENSHOT: i32 = 5;
+/// Rejected by the FFI layer itself: a null or malformed argument, or a
+/// result that could not be marshalled back to the caller.
+pub const WMCP_ERR_FFI: i32 = 6;
+pub const WMCP_ERR_CLIPBOARD: i32 = 7;
+pub const WMCP_ERR_LOGGING: i32 = 8;
+
+/// Window show states for `wmcp_set_window_state`.
+pub const WMCP_WINDOW_MINIMIZE: i32 = 0;
+pub const WMCP_WINDOW_MAXIMIZE: i32 = 1;
+pub const WMCP_WINDOW_RESTORE: i32 = 2;
+pub const WMCP_WINDOW_HIDE: i32 = 3;
+pub const WMCP_WINDOW_SHOW: i32 = 4;
+
 /// Maximum handles to process in `wmcp_capture_tree` to prevent
 /// unreasonable allocations from corrupted input.
 const MAX_HANDLE_COUNT: usize = 256;
 
-/// Maximum text length for `wmcp_send_text`.
+/// Maximum text length (UTF-16 code units) for `wmcp_send_text`.
 const MAX_TEXT_LENGTH: usize = 10_000;
 
+/// `wmcp_find_elements` result limit when the criteria omit `limit`.
+const DEFAULT_FIND_LIMIT: usize = 20;
+
 thread_local! {
     static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_ERROR_CODE: Cell<i32> = const { Cell::new(WMCP_ERR_NONE) };
 }
 
-fn set_last_error(msg: &str) {
+fn set_error(code: i32, msg: &str) {
     LAST_ERROR.with(|e| {
         *e.borrow_mut() = CString::new(msg).ok();
     });
+    LAST_ERROR_CODE.with(|c| c.set(code));
+}
+
+/// Record an error raised by the FFI layer itself (`WMCP_ERR_FFI`).
+fn set_last_error(msg: &str) {
+    set_error(WMCP_ERR_FFI, msg);
+}
+
+/// Record a core library error with the code for its variant.
+fn set_last_core_error(err: &WindowsMcpError) {
+    let code = match err {
+        WindowsMcpError::SystemInfoError(_) => WMCP_ERR_SYSTEM_INFO,
+        WindowsMcpError::ComError(_) => WMCP_ERR_COM,
+        WindowsMcpError::TreeError(_) => WMCP_ERR_TREE,
+        WindowsMcpError::InputError(_) => WMCP_ERR_INPUT,
+        WindowsMcpError::ScreenshotError(_) => WMCP_ERR_SCREENSHOT,
+        WindowsMcpError::ClipboardError(_) => WMCP_ERR_CLIPBOARD,
+        WindowsMcpError::LoggingError(_) => WMCP_ERR_LOGGING,
+    };
+    set_error(code, &err.to_string());
+}
+
+/// Map a `WMCP_WINDOW_*` code to the state name `set_window_state` takes.
+fn window_state_name(state: i32) -> Option<&'static str> {
+    match state {
+        WMCP_WINDOW_MINIMIZE => Some("minimize"),
+        WMCP_WINDOW_MAXIMIZE => Some("maximize"),
+        WMCP_WINDOW_RESTORE => Some("restore"),
+        WMCP_WINDOW_HIDE => Some("hide"),
+        WMCP_WINDOW_SHOW => Some("show"),
+        _ => None,
+    }
+}
+
+/// Map an FFI button code (0 = left, 1 = right, 2 = middle) to its name.
+fn button_name(button: i32) -> &'static str {
+    match button {
+        1 => "right",
+        2 => "middle",
+        _ => "left",
+    }
+}
+
+/// Convert an input-simulation result to a status code, recording any
+/// error for `wmcp_last_error()`.
+fn input_status(result: Result<u32, wmcp_core::errors::WindowsMcpError>) -> i32 {
+    match result {
+        Ok(_) => WMCP_OK,
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Convert a unit result (window management, clipboard) to a status code,
+/// recording any error for `wmcp_last_error()`.
+fn unit_status(result: Result<(), wmcp_core::errors::WindowsMcpError>) -> i32 {
+    match result {
+        Ok(()) => WMCP_OK,
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Hand an encoded image to the caller as a boxed byte buffer, recording
+/// any error for `wmcp_last_error()`.  The caller frees the buffer with
+/// `wmcp_free_buffer()`.
+///
+/// # Safety
+///
+/// `out_buf` and `out_len` must be valid, non-null pointers.
+unsafe fn write_buffer(
+    result: Result<Vec<u8>, wmcp_core::errors::WindowsMcpError>,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    match result {
+        Ok(bytes) => {
+            let len = bytes.len();
+            let ptr = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+            unsafe {
+                *out_buf = ptr;
+                *out_len = len;
+            }
+            WMCP_OK
+        }
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Return the library version as a static null-terminated string.
+///
+/// The pointer is valid for the lifetime of the DLL; do **not** free it.
+#[no_mangle]
+pub extern "C" fn wmcp_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Optional one-time setup.  Calling it is never required.
+///
+/// When `enable_logging` is nonzero, routes the library's `log` output to
+/// stderr through `env_logger` at level `info`, refined by `RUST_LOG` if
+/// set.  Idempotent: the first nonzero call decides the outcome and later
+/// calls return the same status.  Returns `WMCP_ERROR` (code
+/// `WMCP_ERR_LOGGING`) if the process already has a logger installed.
+#[no_mangle]
+pub extern "C" fn wmcp_init(enable_logging: i32) -> i32 {
+    if enable_logging == 0 {
+        return WMCP_OK;
+    }
+    unit_status(wmcp_core::logging::init_logging("info"))
 }
 
 /// Retrieve the last error message (thread-local).
@@ -48,6 +193,16 @@ pub extern "C" fn wmcp_last_error() -> *mut c_char {
     })
 }
 
+/// Retrieve the category of the last error (thread-local).
+///
+/// Returns one of the `WMCP_ERR_*` codes, or `WMCP_ERR_NONE` if no error
+/// has occurred.  Like `wmcp_last_error()`, it is only meaningful right
+/// after a call returned `WMCP_ERROR`; successful calls do not reset it.
+#[no_mangle]
+pub extern "C" fn wmcp_last_error_code() -> i32 {
+    LAST_ERROR_CODE.with(Cell::get)
+}
+
 /// Free a string previously allocated by a wmcp_* function.
 ///
 /// # Safety
@@ -92,7 +247,33 @@ pub unsafe extern "C" fn wmcp_system_info(out_json: *mut *mut c_char) -> i32 {
             }
         },
         Err(e) => {
-            set_last_error(&e.to_string());
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// List running processes as a JSON array ordered by PID.
+///
+/// Each element has `pid`, `name`, `parent_pid` (nullable),
+/// `memory_bytes` and `cpu_percent`.  `limit` caps the number returned;
+/// 0 means no limit.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_list_processes(limit: usize, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let limit = (limit != 0).then_some(limit);
+    match wmcp_core::system_info::list_processes(limit) {
+        Ok(processes) => unsafe { write_json(serde_json::to_string(&processes), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
             WMCP_ERROR
         }
     }
@@ -119,19 +300,26 @@ pub unsafe extern "C" fn wmcp_send_text(text: *const c_char, out_count: *mut u32
         }
     };
 
-    if text_str.len() > MAX_TEXT_LENGTH {
+    let text_units = text_str.encode_utf16().count();
+    if text_units > MAX_TEXT_LENGTH {
         set_last_error(&format!(
-            "text length {} exceeds maximum {MAX_TEXT_LENGTH}",
-            text_str.len()
+            "text length {text_units} UTF-16 units exceeds maximum {MAX_TEXT_LENGTH}"
         ));
         return WMCP_ERROR;
     }
 
-    let count = wmcp_core::input::send_text_raw(text_str);
-    if !out_count.is_null() {
-        unsafe { *out_count = count };
+    match wmcp_core::input::send_text_raw(text_str) {
+        Ok(count) => {
+            if !out_count.is_null() {
+                unsafe { *out_count = count };
+            }
+            WMCP_OK
+        }
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
     }
-    WMCP_OK
 }
 
 /// Click the mouse at absolute screen coordinates.
@@ -139,27 +327,107 @@ pub unsafe extern "C" fn wmcp_send_text(text: *const c_char, out_count: *mut u32
 /// Returns `WMCP_OK` on success, `WMCP_ERROR` if SendInput failed.
 #[no_mangle]
 pub extern "C" fn wmcp_send_click(x: i32, y: i32, button: i32) -> i32 {
-    let button_str = match button {
-        1 => "right",
-        2 => "middle",
-        _ => "left",
-    };
-    let count = wmcp_core::input::send_click_raw(x, y, button_str);
-    if count == 0 {
-        set_last_error("SendInput returned 0 events for click");
-        WMCP_ERROR
-    } else {
-        WMCP_OK
+    input_status(wmcp_core::input::send_click_raw(x, y, button_name(button)))
+}
+
+/// Click at client-area coordinates `(x, y)` of window `hwnd`.
+///
+/// The point is converted with `ClientToScreen`, so it follows the window
+/// wherever it sits.  `button`: 0 = left, 1 = right, 2 = middle.
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if `hwnd` is invalid or
+/// SendInput failed.
+#[no_mangle]
+pub extern "C" fn wmcp_send_click_in_window(hwnd: isize, x: i32, y: i32, button: i32) -> i32 {
+    input_status(wmcp_core::input::send_click_in_window_raw(
+        hwnd,
+        x,
+        y,
+        button_name(button),
+    ))
+}
+
+/// Double-click the mouse at absolute screen coordinates.
+///
+/// `button`: 0 = left, 1 = right, 2 = middle.
+/// `out_count` is optional (may be null); receives the events injected (5).
+///
+/// # Safety
+///
+/// `out_count` must be null or a valid pointer to a `u32`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_send_double_click(
+    x: i32,
+    y: i32,
+    button: i32,
+    out_count: *mut u32,
+) -> i32 {
+    match wmcp_core::input::send_double_click_raw(x, y, button_name(button)) {
+        Ok(count) => {
+            if !out_count.is_null() {
+                unsafe { *out_count = count };
+            }
+            WMCP_OK
+        }
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
     }
 }
 
+/// Press a mouse button at absolute screen coordinates without releasing.
+///
+/// `button`: 0 = left, 1 = right, 2 = middle.
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if SendInput failed.
+#[no_mangle]
+pub extern "C" fn wmcp_send_mouse_down(x: i32, y: i32, button: i32) -> i32 {
+    input_status(wmcp_core::input::send_mouse_down_raw(x, y, button_name(button)))
+}
+
+/// Release a mouse button at absolute screen coordinates.
+///
+/// `button`: 0 = left, 1 = right, 2 = middle.
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if SendInput failed.
+#[no_mangle]
+pub extern "C" fn wmcp_send_mouse_up(x: i32, y: i32, button: i32) -> i32 {
+    input_status(wmcp_core::input::send_mouse_up_raw(x, y, button_name(button)))
+}
+
 /// Move the mouse cursor to absolute screen coordinates.
 ///
-/// Returns `WMCP_OK` on success.
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if SendInput failed.
 #[no_mangle]
 pub extern "C" fn wmcp_send_mouse_move(x: i32, y: i32) -> i32 {
-    wmcp_core::input::send_mouse_move_raw(x, y);
-    WMCP_OK
+    input_status(wmcp_core::input::send_mouse_move_raw(x, y))
+}
+
+/// Get the current cursor position in screen pixels.
+///
+/// On success, writes the position to `*out_x` and `*out_y`.
+///
+/// # Safety
+///
+/// `out_x` and `out_y` must be valid, non-null pointers to `i32`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_get_cursor_pos(out_x: *mut i32, out_y: *mut i32) -> i32 {
+    if out_x.is_null() || out_y.is_null() {
+        set_last_error("out_x or out_y is null");
+        return WMCP_ERROR;
+    }
+
+    match wmcp_core::input::get_cursor_pos() {
+        Ok((x, y)) => {
+            unsafe {
+                *out_x = x;
+                *out_y = y;
+            }
+            WMCP_OK
+        }
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
 }
 
 /// Scroll the mouse wheel at absolute screen coordinates.
@@ -168,8 +436,31 @@ pub extern "C" fn wmcp_send_mouse_move(x: i32, y: i32) -> i32 {
 /// `horizontal`: 0 = vertical, nonzero = horizontal.
 #[no_mangle]
 pub extern "C" fn wmcp_send_scroll(x: i32, y: i32, delta: i32, horizontal: i32) -> i32 {
-    wmcp_core::input::send_scroll_raw(x, y, delta, horizontal != 0);
-    WMCP_OK
+    input_status(wmcp_core::input::send_scroll_raw(x, y, delta, horizontal != 0))
+}
+
+/// Drag the mouse from (`from_x`, `from_y`) to (`to_x`, `to_y`).
+///
+/// `steps` is the number of interpolated moves (clamped to 1..=500).
+/// `button`: 0 = left, 1 = right, 2 = middle (same as `wmcp_send_click`).
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if SendInput failed.
+#[no_mangle]
+pub extern "C" fn wmcp_send_drag_from(
+    from_x: i32,
+    from_y: i32,
+    to_x: i32,
+    to_y: i32,
+    steps: u32,
+    button: i32,
+) -> i32 {
+    input_status(wmcp_core::input::send_drag_from_raw(
+        from_x,
+        from_y,
+        to_x,
+        to_y,
+        steps,
+        button_name(button),
+    ))
 }
 
 /// Send a key combination (e.g. Ctrl+C).
@@ -188,8 +479,7 @@ pub unsafe extern "C" fn wmcp_send_hotkey(vk_codes: *const u16, count: usize) ->
         return WMCP_ERROR;
     }
     let codes = unsafe { std::slice::from_raw_parts(vk_codes, count) };
-    wmcp_core::input::send_hotkey_raw(codes);
-    WMCP_OK
+    input_status(wmcp_core::input::send_hotkey_raw(codes))
 }
 
 /// Enumerate visible windows as a JSON array of handle integers.
@@ -221,7 +511,7 @@ pub unsafe extern "C" fn wmcp_enumerate_windows(out_json: *mut *mut c_char) -> i
             }
         },
         Err(e) => {
-            set_last_error(&e.to_string());
+            set_last_core_error(&e);
             WMCP_ERROR
         }
     }
@@ -256,128 +546,1136 @@ pub unsafe extern "C" fn wmcp_list_windows(out_json: *mut *mut c_char) -> i32 {
             }
         },
         Err(e) => {
-            set_last_error(&e.to_string());
+            set_last_core_error(&e);
             WMCP_ERROR
         }
     }
 }
 
-/// Capture a screenshot as PNG bytes.
+/// List every visible top-level window, without the Alt+Tab filter, as a
+/// JSON array shaped like `wmcp_list_windows`.
+///
+/// A non-zero `include_cloaked` also returns DWM-cloaked windows (other
+/// virtual desktops, suspended UWP apps).
 ///
 /// # Safety
 ///
-/// `out_buf` must be a valid pointer to a `*mut u8`.
-/// `out_len` must be a valid pointer to a `usize`.
-/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
-/// Caller must free the buffer with `wmcp_free_string()` (cast to `*mut c_char`).
+/// `out_json` must be a valid pointer. Caller must free with `wmcp_free_string()`.
 #[no_mangle]
-pub unsafe extern "C" fn wmcp_capture_screenshot_png(
-    monitor_index: u32,
-    out_buf: *mut *mut u8,
-    out_len: *mut usize,
+pub unsafe extern "C" fn wmcp_list_all_windows(
+    include_cloaked: i32,
+    out_json: *mut *mut c_char,
 ) -> i32 {
-    if out_buf.is_null() || out_len.is_null() {
-        set_last_error("null pointer argument");
+    if out_json.is_null() {
+        set_last_error("out_json is null");
         return WMCP_ERROR;
     }
-    match wmcp_core::screenshot::capture_png(monitor_index) {
-        Ok(png_bytes) => {
-            let len = png_bytes.len();
-            let boxed = png_bytes.into_boxed_slice();
-            let ptr = Box::into_raw(boxed) as *mut u8;
-            unsafe {
-                *out_buf = ptr;
-                *out_len = len;
-            }
-            WMCP_OK
-        }
+    match wmcp_core::window::list_all_windows(include_cloaked != 0) {
+        Ok(windows) => unsafe { write_json(serde_json::to_string(&windows), out_json) },
         Err(e) => {
-            set_last_error(&e.to_string());
+            set_last_core_error(&e);
             WMCP_ERROR
         }
     }
 }
 
-/// Free a byte buffer allocated by `wmcp_capture_screenshot_png`.
-///
-/// # Safety
+/// Find visible windows by title substring (case-insensitive), exact class
+/// name, and/or process ID, as a JSON array shaped like `wmcp_list_windows`.
 ///
-/// `ptr` must be a buffer returned by `wmcp_capture_screenshot_png` or null.
-/// `len` must be the corresponding length.
-#[no_mangle]
-pub unsafe extern "C" fn wmcp_free_buffer(ptr: *mut u8, len: usize) {
-    if !ptr.is_null() && len > 0 {
-        drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) });
-    }
-}
-
-/// Capture the UIA tree for window handles as a JSON string.
+/// `title` and `class_name` are optional null-terminated UTF-8 strings
+/// (null = any); `pid` 0 matches any process.
 ///
 /// # Safety
 ///
-/// `handles` must point to `handle_count` contiguous, initialized `isize` values.
-/// `*out_json` will be set to a heap-allocated string; free with
+/// `title` and `class_name` must each be null or a valid null-terminated
+/// C string.  `out_json` must be a valid pointer; free the result with
 /// `wmcp_free_string()`.
 #[no_mangle]
-pub unsafe extern "C" fn wmcp_capture_tree(
-    handles: *const isize,
-    handle_count: usize,
-    max_depth: usize,
+pub unsafe extern "C" fn wmcp_find_windows(
+    title: *const c_char,
+    class_name: *const c_char,
+    pid: u32,
     out_json: *mut *mut c_char,
 ) -> i32 {
-    if handles.is_null() || out_json.is_null() {
-        set_last_error("null pointer argument");
+    if out_json.is_null() {
+        set_last_error("out_json is null");
         return WMCP_ERROR;
     }
+    let (Some(title), Some(class_name)) =
+        (unsafe { optional_str(title) }, unsafe { optional_str(class_name) })
+    else {
+        return WMCP_ERROR;
+    };
 
-    if handle_count == 0 {
-        // Return empty JSON array for zero handles
-        match CString::new("[]") {
-            Ok(cstr) => {
-                unsafe { *out_json = cstr.into_raw() };
-                return WMCP_OK;
-            }
-            Err(_) => {
-                set_last_error("CString allocation failed");
-                return WMCP_ERROR;
-            }
+    let pid = (pid != 0).then_some(pid);
+    match wmcp_core::window::find_windows(title, class_name, pid) {
+        Ok(windows) => unsafe { write_json(serde_json::to_string(&windows), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
         }
     }
+}
 
-    if handle_count > MAX_HANDLE_COUNT {
-        set_last_error(&format!(
-            "handle_count {handle_count} exceeds maximum {MAX_HANDLE_COUNT}"
-        ));
+/// Get the top-level window under screen point `(x, y)` as a JSON object
+/// shaped like one `wmcp_list_windows` entry.
+///
+/// Returns `WMCP_ERROR` if no window is at the point.
+///
+/// # Safety
+///
+/// `out_json` must be a valid pointer. Caller must free with `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_window_from_point(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
         return WMCP_ERROR;
     }
+    match wmcp_core::window::window_from_point(x, y) {
+        Ok(info) => unsafe { write_json(serde_json::to_string(&info), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
 
-    // Validate pointer alignment
-    if (handles as usize) % std::mem::align_of::<isize>() != 0 {
-        set_last_error("handles pointer is not properly aligned");
+/// Get the index of the monitor a window is mostly on, usable as the
+/// `monitor_index` of the screenshot functions.  The `wmcp_list_windows`
+/// JSON carries the same value as `monitor_index`.
+///
+/// # Safety
+///
+/// `out_index` must be a valid, non-null pointer to `u32`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_get_window_monitor_index(hwnd: isize, out_index: *mut u32) -> i32 {
+    if out_index.is_null() {
+        set_last_error("out_index is null");
         return WMCP_ERROR;
     }
 
-    let handle_slice = unsafe { std::slice::from_raw_parts(handles, handle_count) };
-    let snapshots = wmcp_core::tree::capture_tree_raw(handle_slice, max_depth);
-
-    match serde_json::to_string(&snapshots) {
-        Ok(json) => {
-            // Sanitize null bytes that would break CString
-            let json_sanitized = json.replace('\0', "\\u0000");
-            match CString::new(json_sanitized) {
-                Ok(cstr) => {
-                    unsafe { *out_json = cstr.into_raw() };
-                    WMCP_OK
-                }
-                Err(e) => {
-                    set_last_error(&format!("CString conversion failed: {e}"));
-                    WMCP_ERROR
-                }
-            }
-        },
+    match wmcp_core::window::get_window_monitor_index(hwnd) {
+        Ok(index) => {
+            unsafe { *out_index = index };
+            WMCP_OK
+        }
         Err(e) => {
-            set_last_error(&format!("JSON serialization failed: {e}"));
+            set_last_core_error(&e);
             WMCP_ERROR
         }
     }
 }
+
+/// Move and resize a window; coordinates are physical screen pixels.
+///
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if the handle is invalid or
+/// `SetWindowPos` failed.
+#[no_mangle]
+pub extern "C" fn wmcp_set_window_rect(hwnd: isize, x: i32, y: i32, w: i32, h: i32) -> i32 {
+    unit_status(wmcp_core::window::set_window_rect(hwnd, x, y, w, h))
+}
+
+/// Make a window always-on-top (non-zero `topmost`) or return it to the
+/// normal Z band (zero).  The `wmcp_list_windows` JSON reports the current
+/// state as `is_topmost`.
+///
+/// Returns `WMCP_OK` on success, `WMCP_ERROR` if the handle is invalid or
+/// `SetWindowPos` failed.
+#[no_mangle]
+pub extern "C" fn wmcp_set_window_topmost(hwnd: isize, topmost: i32) -> i32 {
+    unit_status(wmcp_core::window::set_window_topmost(hwnd, topmost != 0))
+}
+
+/// Change a window's show state.
+///
+/// `state` is one of the `WMCP_WINDOW_*` constants.  Returns `WMCP_ERROR`
+/// for an unknown state or an invalid handle.
+#[no_mangle]
+pub extern "C" fn wmcp_set_window_state(hwnd: isize, state: i32) -> i32 {
+    let Some(state) = window_state_name(state) else {
+        set_last_error(&format!("Unknown window state {state}"));
+        return WMCP_ERROR;
+    };
+    unit_status(wmcp_core::window::set_window_state(hwnd, state))
+}
+
+/// Get the foreground (active) window handle, or 0 if there is none.
+#[no_mangle]
+pub extern "C" fn wmcp_get_foreground_window() -> isize {
+    wmcp_core::window::get_foreground_hwnd()
+}
+
+/// Bring a window to the foreground and give it keyboard focus.
+///
+/// Returns `WMCP_ERROR` if the handle is invalid or the window is still not
+/// in the foreground after the attempt.
+#[no_mangle]
+pub extern "C" fn wmcp_focus_window(hwnd: isize) -> i32 {
+    unit_status(wmcp_core::window::focus_window(hwnd))
+}
+
+/// Ask a window to close by posting `WM_CLOSE`.
+///
+/// A polite request: the application may ignore it or prompt the user, so
+/// `WMCP_OK` only means the message was posted.
+#[no_mangle]
+pub extern "C" fn wmcp_close_window(hwnd: isize) -> i32 {
+    unit_status(wmcp_core::window::close_window(hwnd))
+}
+
+/// Query the UIA element at screen coordinates as a JSON object.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_element_from_point(
+    x: i32,
+    y: i32,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    match wmcp_core::query::element_from_point(x, y) {
+        Ok(info) => unsafe { write_json(serde_json::to_string(&info), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// List the control patterns supported by the element at screen
+/// coordinates as a JSON array of names, e.g. `["InvokePattern"]`.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_get_supported_patterns_at(
+    x: i32,
+    y: i32,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    match wmcp_core::query::get_supported_patterns_at(x, y) {
+        Ok(patterns) => unsafe { write_json(serde_json::to_string(&patterns), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Build [`wmcp_core::query::FindCriteria`] from a JSON object.
+///
+/// Keys mirror the Python `find_elements` keyword arguments: `name`,
+/// `name_regex`, `control_type`, `automation_id`, `class_name` and `scope`
+/// (strings), `substring` (bool), `window_handle` and `limit` (integers).
+/// All are optional; `null` means unset.  Unknown keys are rejected so a
+/// typo does not silently widen the search.
+fn find_criteria_from_json(json: &str) -> Result<wmcp_core::query::FindCriteria, String> {
+    use serde_json::Value;
+
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid criteria JSON: {e}"))?;
+    let Value::Object(fields) = value else {
+        return Err("criteria must be a JSON object".into());
+    };
+
+    let mut criteria = wmcp_core::query::FindCriteria {
+        limit: DEFAULT_FIND_LIMIT,
+        ..Default::default()
+    };
+    for (key, value) in &fields {
+        if value.is_null() {
+            continue;
+        }
+        let string = || {
+            value
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| format!("criteria.{key} must be a string"))
+        };
+        let integer = || value.as_i64().ok_or_else(|| format!("criteria.{key} must be an integer"));
+        match key.as_str() {
+            "name" => criteria.name = Some(string()?),
+            "name_regex" => criteria.name_regex = Some(string()?),
+            "control_type" => criteria.control_type = Some(string()?),
+            "automation_id" => criteria.automation_id = Some(string()?),
+            "class_name" => criteria.class_name = Some(string()?),
+            "substring" => {
+                criteria.substring = value
+                    .as_bool()
+                    .ok_or_else(|| format!("criteria.{key} must be a bool"))?;
+            }
+            "window_handle" => {
+                let handle = integer()?;
+                criteria.window_handle = Some(
+                    isize::try_from(handle)
+                        .map_err(|_| format!("criteria.window_handle {handle} out of range"))?,
+                );
+            }
+            "scope" => {
+                criteria.scope =
+                    wmcp_core::query::FindScope::from_name(&string()?).map_err(|e| e.to_string())?;
+            }
+            "limit" => {
+                let limit = integer()?;
+                criteria.limit = usize::try_from(limit)
+                    .map_err(|_| format!("criteria.limit {limit} must not be negative"))?;
+            }
+            _ => return Err(format!("Unknown criteria key '{key}'")),
+        }
+    }
+    Ok(criteria)
+}
+
+/// Search for UIA elements matching `criteria_json` and return them as a
+/// JSON array of the objects `wmcp_element_from_point` returns.
+///
+/// See `find_criteria_from_json` for the criteria keys; `limit` defaults
+/// to 20.  Returns `WMCP_ERROR` for malformed criteria.
+///
+/// # Safety
+///
+/// `criteria_json` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_find_elements(
+    criteria_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(criteria_json) = (unsafe { required_str(criteria_json, "criteria_json") }) else {
+        return WMCP_ERROR;
+    };
+    let criteria = match find_criteria_from_json(criteria_json) {
+        Ok(criteria) => criteria,
+        Err(e) => {
+            set_last_error(&e);
+            return WMCP_ERROR;
+        }
+    };
+    match wmcp_core::query::find_elements(&criteria) {
+        Ok(elements) => unsafe { write_json(serde_json::to_string(&elements), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Store a pattern action's outcome in `*out_json` as a JSON object with
+/// `element_name`, `element_type`, `success` and `detail`.
+///
+/// # Safety
+///
+/// `out_json` must be a valid, non-null pointer to a `*mut c_char`.
+unsafe fn write_pattern_result(
+    result: Result<wmcp_core::pattern::PatternResult, wmcp_core::errors::WindowsMcpError>,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    match result {
+        Ok(result) => unsafe { write_json(serde_json::to_string(&result), out_json) },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Invoke the `InvokePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`).
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_invoke_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::invoke_at(x, y), out_json) }
+}
+
+/// Toggle the `TogglePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`). `detail` reports the new
+/// state.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_toggle_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::toggle_at(x, y), out_json) }
+}
+
+/// Set a value via `ValuePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`).
+///
+/// # Safety
+///
+/// `value` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_set_value_at(
+    x: i32,
+    y: i32,
+    value: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(value) = (unsafe { required_str(value, "value") }) else {
+        return WMCP_ERROR;
+    };
+    unsafe { write_pattern_result(wmcp_core::pattern::set_value_at(x, y, value), out_json) }
+}
+
+/// Focus the element at `(x, y)` and type `text` into it.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`). `success` is
+/// false, and no text is sent, if the element cannot take keyboard focus.
+///
+/// # Safety
+///
+/// `text` must be a valid null-terminated UTF-8 C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_send_text_to_element(
+    x: i32,
+    y: i32,
+    text: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(text) = (unsafe { required_str(text, "text") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::send_text_to_element(x, y, text);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Expand via `ExpandCollapsePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`).
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_expand_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::expand_at(x, y), out_json) }
+}
+
+/// Collapse via `ExpandCollapsePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`).
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_collapse_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::collapse_at(x, y), out_json) }
+}
+
+/// Select via `SelectionItemPattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`).
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_select_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::select_at(x, y), out_json) }
+}
+
+/// Read the current value via `ValuePattern` on the element at `(x, y)`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`) with the value
+/// in `detail` and an extra `is_read_only` key when the pattern reports it.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_get_value_at(x: i32, y: i32, out_json: *mut *mut c_char) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    unsafe { write_pattern_result(wmcp_core::pattern::get_value_at(x, y), out_json) }
+}
+
+/// Maximize, minimize, restore or close the window containing `(x, y)` via
+/// UIA `WindowPattern`.
+///
+/// `action` is "maximize", "minimize", "normal" or "close".  Writes the
+/// outcome as JSON (see `write_pattern_result`); `detail` reports the
+/// window's interaction state afterwards.  Returns `WMCP_ERROR` for an
+/// unknown action.
+///
+/// # Safety
+///
+/// `action` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_window_pattern_action_at(
+    x: i32,
+    y: i32,
+    action: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(action) = (unsafe { required_str(action, "action") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::window_pattern_action_at(x, y, action);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Invoke the `InvokePattern` on the first element in window `hwnd` whose
+/// AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_invoke_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::invoke_by_automation_id(hwnd, id);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Toggle the `TogglePattern` on the first element in window `hwnd` whose
+/// AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_toggle_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::toggle_by_automation_id(hwnd, id);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Set a value via `ValuePattern` on the first element in window `hwnd` whose
+/// AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` and `value` must be valid null-terminated C strings.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_set_value_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    value: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let Some(value) = (unsafe { required_str(value, "value") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::set_value_by_automation_id(hwnd, id, value);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Expand via `ExpandCollapsePattern` on the first element in window `hwnd`
+/// whose AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_expand_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::expand_by_automation_id(hwnd, id);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Collapse via `ExpandCollapsePattern` on the first element in window `hwnd`
+/// whose AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_collapse_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::collapse_by_automation_id(hwnd, id);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Select via `SelectionItemPattern` on the first element in window `hwnd`
+/// whose AutomationId is `automation_id`.
+///
+/// Writes the outcome as JSON (see `write_pattern_result`); `success` is
+/// false when the element lacks the pattern.  Returns `WMCP_ERROR` if no
+/// element matches.
+///
+/// # Safety
+///
+/// `automation_id` must be a valid null-terminated C string.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_select_by_automation_id(
+    hwnd: isize,
+    automation_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+    let Some(id) = (unsafe { required_str(automation_id, "automation_id") }) else {
+        return WMCP_ERROR;
+    };
+    let result = wmcp_core::pattern::select_by_automation_id(hwnd, id);
+    unsafe { write_pattern_result(result, out_json) }
+}
+
+/// Read the clipboard as text.
+///
+/// Writes an empty string when the clipboard holds no text.
+///
+/// # Safety
+///
+/// `*out_text` will be set to a heap-allocated UTF-8 string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_get_clipboard_text(out_text: *mut *mut c_char) -> i32 {
+    if out_text.is_null() {
+        set_last_error("out_text is null");
+        return WMCP_ERROR;
+    }
+    match wmcp_core::clipboard::get_clipboard_text() {
+        // Clipboard text stops at the first NUL, so CString::new cannot fail.
+        Ok(text) => match CString::new(text) {
+            Ok(cstr) => {
+                unsafe { *out_text = cstr.into_raw() };
+                WMCP_OK
+            }
+            Err(e) => {
+                set_last_error(&format!("CString conversion failed: {e}"));
+                WMCP_ERROR
+            }
+        },
+        Err(e) => {
+            set_last_core_error(&e);
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Replace the clipboard contents with `text`.
+///
+/// # Safety
+///
+/// `text` must be a valid null-terminated UTF-8 C string.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_set_clipboard_text(text: *const c_char) -> i32 {
+    let Some(text) = (unsafe { required_str(text, "text") }) else {
+        return WMCP_ERROR;
+    };
+    unit_status(wmcp_core::clipboard::set_clipboard_text(text))
+}
+
+/// Capture a screenshot as PNG bytes.
+///
+/// # Safety
+///
+/// `out_buf` must be a valid pointer to a `*mut u8`.
+/// `out_len` must be a valid pointer to a `usize`.
+/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
+/// Caller must free the buffer with `wmcp_free_buffer()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_screenshot_png(
+    monitor_index: u32,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+    let result = wmcp_core::screenshot::capture_png(monitor_index, &Default::default());
+    unsafe { write_buffer(result, out_buf, out_len) }
+}
+
+/// Release cached screenshot sessions; they are rebuilt on the next capture.
+#[no_mangle]
+pub extern "C" fn wmcp_reset_screenshot_cache() {
+    wmcp_core::screenshot::reset_screenshot_cache();
+}
+
+/// Capture a screenshot as JPEG bytes.
+///
+/// `quality` is clamped to 1-100.  A non-zero `max_dimension` downscales
+/// the frame so its longest side is at most that many pixels; 0 keeps the
+/// native resolution.
+///
+/// # Safety
+///
+/// `out_buf` must be a valid pointer to a `*mut u8`.
+/// `out_len` must be a valid pointer to a `usize`.
+/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
+/// Caller must free the buffer with `wmcp_free_buffer()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_screenshot_jpeg(
+    monitor_index: u32,
+    quality: u8,
+    max_dimension: u32,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        max_dimension: (max_dimension > 0).then_some(max_dimension),
+        ..Default::default()
+    };
+    let result = wmcp_core::screenshot::capture_jpeg(monitor_index, quality, &options);
+    unsafe { write_buffer(result, out_buf, out_len) }
+}
+
+/// Capture a screenshot as lossless WebP bytes.
+///
+/// # Safety
+///
+/// `out_buf` must be a valid pointer to a `*mut u8`.
+/// `out_len` must be a valid pointer to a `usize`.
+/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
+/// Caller must free the buffer with `wmcp_free_buffer()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_screenshot_webp(
+    monitor_index: u32,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+    let result = wmcp_core::screenshot::capture_webp(monitor_index, &Default::default());
+    unsafe { write_buffer(result, out_buf, out_len) }
+}
+
+/// Capture a sub-rectangle of a monitor as PNG bytes.
+///
+/// `(x, y)` is relative to the monitor's top-left corner; the region must
+/// lie entirely within the monitor.
+///
+/// # Safety
+///
+/// `out_buf` must be a valid pointer to a `*mut u8`.
+/// `out_len` must be a valid pointer to a `usize`.
+/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
+/// Caller must free the buffer with `wmcp_free_buffer()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_region_png(
+    monitor_index: u32,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+    let result = wmcp_core::screenshot::capture_region_png(
+        monitor_index,
+        x,
+        y,
+        width,
+        height,
+        &Default::default(),
+    );
+    unsafe { write_buffer(result, out_buf, out_len) }
+}
+
+/// Capture a single window by HWND as PNG bytes.
+///
+/// Fails if `hwnd` is not a window or the window is minimized.
+///
+/// # Safety
+///
+/// `out_buf` must be a valid pointer to a `*mut u8`.
+/// `out_len` must be a valid pointer to a `usize`.
+/// On success, `*out_buf` is set to a heap-allocated buffer and `*out_len` to its length.
+/// Caller must free the buffer with `wmcp_free_buffer()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_window_png(
+    hwnd: isize,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+    unsafe { write_buffer(wmcp_core::screenshot::capture_window_png(hwnd), out_buf, out_len) }
+}
+
+/// Free a byte buffer returned through `out_buf`/`out_len` by any of the
+/// image capture functions (`wmcp_capture_screenshot_png`, `_jpeg`,
+/// `_webp`, `wmcp_capture_region_png`, `wmcp_capture_window_png`).
+///
+/// # Safety
+///
+/// `ptr` must be null or a buffer returned by one of those functions and
+/// not yet freed; `len` must be the length returned with it.  Never pass
+/// these buffers to `wmcp_free_string()`, nor strings to this function.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_free_buffer(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() && len > 0 {
+        drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) });
+    }
+}
+
+/// Borrow an optional C string argument: `Some(None)` for null,
+/// `Some(Some(s))` for valid UTF-8, and `None` (error recorded for
+/// `wmcp_last_error()`) for invalid UTF-8.
+///
+/// # Safety
+///
+/// `ptr` must be null or a valid null-terminated C string.
+unsafe fn optional_str<'a>(ptr: *const c_char) -> Option<Option<&'a str>> {
+    if ptr.is_null() {
+        return Some(None);
+    }
+    match unsafe { CStr::from_ptr(ptr) }.to_str() {
+        Ok(s) => Some(Some(s)),
+        Err(e) => {
+            set_last_error(&format!("Invalid UTF-8: {e}"));
+            None
+        }
+    }
+}
+
+/// Borrow a required C string argument, recording an error for
+/// `wmcp_last_error()` and returning `None` if it is null or not UTF-8.
+///
+/// # Safety
+///
+/// `ptr` must be null or a valid null-terminated C string.
+unsafe fn required_str<'a>(ptr: *const c_char, name: &str) -> Option<&'a str> {
+    if ptr.is_null() {
+        set_last_error(&format!("{name} is null"));
+        return None;
+    }
+    unsafe { optional_str(ptr) }.flatten()
+}
+
+/// Validate a caller-supplied handle array and borrow it as a slice.
+///
+/// Records the reason in `wmcp_last_error()` and returns `None` on a null,
+/// oversized, or misaligned input.
+///
+/// # Safety
+///
+/// `handles` must point to `handle_count` contiguous, initialized `isize` values.
+unsafe fn handle_slice<'a>(handles: *const isize, handle_count: usize) -> Option<&'a [isize]> {
+    if handle_count == 0 {
+        return Some(&[]);
+    }
+
+    if handle_count > MAX_HANDLE_COUNT {
+        set_last_error(&format!(
+            "handle_count {handle_count} exceeds maximum {MAX_HANDLE_COUNT}"
+        ));
+        return None;
+    }
+
+    // Validate pointer alignment
+    if (handles as usize) % std::mem::align_of::<isize>() != 0 {
+        set_last_error("handles pointer is not properly aligned");
+        return None;
+    }
+
+    Some(unsafe { std::slice::from_raw_parts(handles, handle_count) })
+}
+
+/// Store a serialization result in `*out_json` as a heap-allocated C string.
+///
+/// # Safety
+///
+/// `out_json` must be a valid, non-null pointer to a `*mut c_char`.
+unsafe fn write_json(json: serde_json::Result<String>, out_json: *mut *mut c_char) -> i32 {
+    match json {
+        Ok(json) => {
+            // Sanitize null bytes that would break CString
+            let json_sanitized = json.replace('\0', "\\u0000");
+            match CString::new(json_sanitized) {
+                Ok(cstr) => {
+                    unsafe { *out_json = cstr.into_raw() };
+                    WMCP_OK
+                }
+                Err(e) => {
+                    set_last_error(&format!("CString conversion failed: {e}"));
+                    WMCP_ERROR
+                }
+            }
+        },
+        Err(e) => {
+            set_last_error(&format!("JSON serialization failed: {e}"));
+            WMCP_ERROR
+        }
+    }
+}
+
+/// Capture the UIA tree for window handles as a JSON string.
+///
+/// # Safety
+///
+/// `handles` must point to `handle_count` contiguous, initialized `isize` values.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_tree(
+    handles: *const isize,
+    handle_count: usize,
+    max_depth: usize,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if handles.is_null() || out_json.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+
+    let Some(handle_slice) = (unsafe { handle_slice(handles, handle_count) }) else {
+        return WMCP_ERROR;
+    };
+    let snapshots = wmcp_core::tree::capture_tree_raw(
+        handle_slice,
+        max_depth,
+        &wmcp_core::tree::CaptureOptions::default(),
+    );
+
+    unsafe { write_json(serde_json::to_string(&snapshots), out_json) }
+}
+
+/// Capture the UIA tree for window handles as a flat JSON array.
+///
+/// Each element carries the `wmcp_capture_tree` keys minus `children`,
+/// plus `index` and `parent_index` (`null` for window roots), in pre-order.
+///
+/// # Safety
+///
+/// `handles` must point to `handle_count` contiguous, initialized `isize` values.
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_tree_flat(
+    handles: *const isize,
+    handle_count: usize,
+    max_depth: usize,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    if handles.is_null() || out_json.is_null() {
+        set_last_error("null pointer argument");
+        return WMCP_ERROR;
+    }
+
+    let Some(handle_slice) = (unsafe { handle_slice(handles, handle_count) }) else {
+        return WMCP_ERROR;
+    };
+    let elements = wmcp_core::tree::capture_tree_flat_raw(
+        handle_slice,
+        max_depth,
+        &wmcp_core::tree::CaptureOptions::default(),
+    );
+
+    unsafe { write_json(serde_json::to_string(&elements), out_json) }
+}
+
+/// Observe the desktop in one call: the foreground window, its UIA tree,
+/// and a screenshot.
+///
+/// Writes a JSON object with `foreground_window` (as in
+/// `wmcp_list_windows`), `tree` (as one `wmcp_capture_tree` element) and
+/// `screenshot_png` (base64-encoded PNG of monitor `monitor_index`).
+/// `foreground_window` and `tree` are `null` when no window is in the
+/// foreground or it closed mid-call.  Returns `WMCP_ERROR` only if the
+/// screenshot fails.
+///
+/// # Safety
+///
+/// `*out_json` will be set to a heap-allocated string; free with
+/// `wmcp_free_string()`.
+#[no_mangle]
+pub unsafe extern "C" fn wmcp_capture_context(
+    max_depth: usize,
+    monitor_index: u32,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    use base64::Engine as _;
+
+    if out_json.is_null() {
+        set_last_error("out_json is null");
+        return WMCP_ERROR;
+    }
+
+    let png = match wmcp_core::screenshot::capture_png(monitor_index, &Default::default()) {
+        Ok(png) => png,
+        Err(e) => {
+            set_last_core_error(&e);
+            return WMCP_ERROR;
+        }
+    };
+
+    let hwnd = wmcp_core::window::get_foreground_hwnd();
+    let (window, tree) = if hwnd == 0 {
+        (None, None)
+    } else {
+        let window = wmcp_core::window::get_window_info(hwnd).ok();
+        let tree = wmcp_core::tree::capture_tree_raw(
+            &[hwnd],
+            max_depth,
+            &wmcp_core::tree::CaptureOptions::default(),
+        )
+        .into_iter()
+        .next();
+        (window, tree)
+    };
+
+    let context = serde_json::json!({
+        "foreground_window": window,
+        "tree": tree,
+        "screenshot_png": base64::engine::general_purpose::STANDARD.encode(png),
+    });
+    unsafe { write_json(serde_json::to_string(&context), out_json) }
+}
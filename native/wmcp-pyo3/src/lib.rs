@@ -4,13 +4,15 @@
 //! the Rust result to Python objects.  All business logic lives in
 //! `wmcp_core`.
 
+use pyo3::create_exception;
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 
-use wmcp_core::tree::element::TreeElementSnapshot;
+use wmcp_core::tree::element::{DiffNode, FlatElement, TreeDiff, TreeElementSnapshot};
+use wmcp_core::tree::UiaProperty;
 
-/// Maximum text length accepted by `send_text` (matches core).
+/// Maximum text length (UTF-16 code units) accepted by `send_text` (matches core).
 const MAX_SEND_TEXT_LEN: usize = 10_000;
 
 /// Maximum window handles accepted by `capture_tree` (matches FFI).
@@ -20,8 +22,33 @@ const MAX_HANDLE_COUNT: usize = 256;
 // Error conversion helper
 // ---------------------------------------------------------------------------
 
+// Base class for all core errors.  Subclasses `RuntimeError` so existing
+// `except RuntimeError` handlers keep working.
+create_exception!(windows_mcp_core, WmcpError, PyRuntimeError);
+create_exception!(windows_mcp_core, WmcpSystemInfoError, WmcpError);
+create_exception!(windows_mcp_core, WmcpComError, WmcpError);
+create_exception!(windows_mcp_core, WmcpTreeError, WmcpError);
+create_exception!(windows_mcp_core, WmcpInputError, WmcpError);
+create_exception!(windows_mcp_core, WmcpScreenshotError, WmcpError);
+create_exception!(windows_mcp_core, WmcpClipboardError, WmcpError);
+create_exception!(windows_mcp_core, WmcpLoggingError, WmcpError);
+
+/// Raise the `WmcpError` subclass matching the error's variant.
+///
+/// The message is the error's `Display` output, unchanged.
 fn to_py_err(e: wmcp_core::errors::WindowsMcpError) -> PyErr {
-    PyRuntimeError::new_err(e.to_string())
+    use wmcp_core::errors::WindowsMcpError;
+
+    let msg = e.to_string();
+    match e {
+        WindowsMcpError::SystemInfoError(_) => WmcpSystemInfoError::new_err(msg),
+        WindowsMcpError::ComError(_) => WmcpComError::new_err(msg),
+        WindowsMcpError::TreeError(_) => WmcpTreeError::new_err(msg),
+        WindowsMcpError::InputError(_) => WmcpInputError::new_err(msg),
+        WindowsMcpError::ScreenshotError(_) => WmcpScreenshotError::new_err(msg),
+        WindowsMcpError::ClipboardError(_) => WmcpClipboardError::new_err(msg),
+        WindowsMcpError::LoggingError(_) => WmcpLoggingError::new_err(msg),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -53,7 +80,14 @@ fn snapshot_to_py_dict(py: Python<'_>, root: &TreeElementSnapshot) -> PyResult<P
         dict.set_item("has_keyboard_focus", snap.has_keyboard_focus)?;
         dict.set_item("is_keyboard_focusable", snap.is_keyboard_focusable)?;
         dict.set_item("accelerator_key", &snap.accelerator_key)?;
+        dict.set_item("help_text", &snap.help_text)?;
+        dict.set_item("access_key", &snap.access_key)?;
+        dict.set_item("culture", snap.culture)?;
+        dict.set_item("value", &snap.value)?;
+        dict.set_item("toggle_state", &snap.toggle_state)?;
+        dict.set_item("is_selected", snap.is_selected)?;
         dict.set_item("depth", snap.depth)?;
+        dict.set_item("truncated", snap.truncated)?;
 
         let children_list = PyList::empty(py);
         dict.set_item("children", &children_list)?;
@@ -71,6 +105,35 @@ fn snapshot_to_py_dict(py: Python<'_>, root: &TreeElementSnapshot) -> PyResult<P
     root_list.get_item(0).map(|item| item.into())
 }
 
+/// Convert a [`FlatElement`] to a Python dict (`parent_index` is `None`
+/// for window roots).
+fn flat_element_to_py_dict(py: Python<'_>, elem: &FlatElement) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("index", elem.index)?;
+    dict.set_item("parent_index", elem.parent_index)?;
+    dict.set_item("name", &elem.name)?;
+    dict.set_item("automation_id", &elem.automation_id)?;
+    dict.set_item("control_type", &elem.control_type)?;
+    dict.set_item("localized_control_type", &elem.localized_control_type)?;
+    dict.set_item("class_name", &elem.class_name)?;
+    dict.set_item("bounding_rect", elem.bounding_rect.to_vec())?;
+    dict.set_item("is_offscreen", elem.is_offscreen)?;
+    dict.set_item("is_enabled", elem.is_enabled)?;
+    dict.set_item("is_control_element", elem.is_control_element)?;
+    dict.set_item("has_keyboard_focus", elem.has_keyboard_focus)?;
+    dict.set_item("is_keyboard_focusable", elem.is_keyboard_focusable)?;
+    dict.set_item("accelerator_key", &elem.accelerator_key)?;
+    dict.set_item("help_text", &elem.help_text)?;
+    dict.set_item("access_key", &elem.access_key)?;
+    dict.set_item("culture", elem.culture)?;
+    dict.set_item("value", &elem.value)?;
+    dict.set_item("toggle_state", &elem.toggle_state)?;
+    dict.set_item("is_selected", elem.is_selected)?;
+    dict.set_item("depth", elem.depth)?;
+    dict.set_item("truncated", elem.truncated)?;
+    Ok(dict.into())
+}
+
 /// Convert a [`WindowInfo`] to a Python dict.
 fn window_info_to_dict(
     py: Python<'_>,
@@ -84,6 +147,10 @@ fn window_info_to_dict(
     dict.set_item("is_minimized", info.is_minimized)?;
     dict.set_item("is_maximized", info.is_maximized)?;
     dict.set_item("is_visible", info.is_visible)?;
+    dict.set_item("is_cloaked", info.is_cloaked)?;
+    dict.set_item("is_topmost", info.is_topmost)?;
+    dict.set_item("z_order", info.z_order)?;
+    dict.set_item("monitor_index", info.monitor_index)?;
 
     let rect = PyDict::new(py);
     rect.set_item("left", info.rect.left)?;
@@ -100,10 +167,19 @@ fn window_info_to_dict(
 // ---------------------------------------------------------------------------
 
 /// Collect system information and return it as a Python dict.
+///
+/// Pass `include_disks=False` (disk enumeration can block for seconds on
+/// unreachable network drives) or `include_networks=False` to skip those
+/// sections; their lists are then empty.  Repeat calls within the refresh
+/// interval (see `set_system_info_refresh_interval`) return the previous
+/// snapshot.
 #[pyfunction]
-fn system_info(py: Python<'_>) -> PyResult<PyObject> {
+#[pyo3(signature = (include_disks=true, include_networks=true))]
+fn system_info(py: Python<'_>, include_disks: bool, include_networks: bool) -> PyResult<PyObject> {
     let snapshot = py
-        .allow_threads(wmcp_core::system_info::collect_system_info)
+        .allow_threads(move || {
+            wmcp_core::system_info::collect_system_info_opts(include_disks, include_networks)
+        })
         .map_err(to_py_err)?;
 
     let dict = PyDict::new(py);
@@ -130,20 +206,298 @@ fn system_info(py: Python<'_>) -> PyResult<PyObject> {
     }
     dict.set_item("disks", disk_list)?;
 
+    let gpu_list = PyList::empty(py);
+    for gpu in &snapshot.gpus {
+        let g = PyDict::new(py);
+        g.set_item("description", &gpu.description)?;
+        g.set_item("dedicated_video_memory_bytes", gpu.dedicated_video_memory_bytes)?;
+        g.set_item("shared_system_memory_bytes", gpu.shared_system_memory_bytes)?;
+        g.set_item("is_software", gpu.is_software)?;
+        gpu_list.append(g)?;
+    }
+    dict.set_item("gpus", gpu_list)?;
+
+    let network_list = PyList::empty(py);
+    for network in &snapshot.networks {
+        let n = PyDict::new(py);
+        n.set_item("name", &network.name)?;
+        n.set_item("mac_address", &network.mac_address)?;
+        n.set_item("total_received_bytes", network.total_received_bytes)?;
+        n.set_item("total_transmitted_bytes", network.total_transmitted_bytes)?;
+        n.set_item("ip_addresses", &network.ip_addresses)?;
+        network_list.append(n)?;
+    }
+    dict.set_item("networks", network_list)?;
+
+    match &snapshot.power {
+        Some(power) => {
+            let p = PyDict::new(py);
+            p.set_item("ac_online", power.ac_online)?;
+            p.set_item("battery_percent", power.battery_percent)?;
+            p.set_item("seconds_remaining", power.seconds_remaining)?;
+            dict.set_item("power", p)?;
+        }
+        None => dict.set_item("power", py.None())?,
+    }
+
     Ok(dict.into())
 }
 
+/// Set how long `system_info()` reuses its last snapshot (default 250ms).
+///
+/// Calls within the interval return the previous result unchanged; pass 0
+/// to refresh on every call.
+#[pyfunction]
+#[pyo3(signature = (interval_ms,))]
+fn set_system_info_refresh_interval(interval_ms: u64) {
+    wmcp_core::system_info::set_refresh_interval(std::time::Duration::from_millis(interval_ms));
+}
+
+/// Measure per-core CPU usage percentages over `sample_ms` milliseconds.
+///
+/// Blocks for `sample_ms` (with the GIL released) but, unlike
+/// `system_info()["cpu_usage_percent"]`, is accurate on the first call.
+#[pyfunction]
+#[pyo3(signature = (sample_ms=100))]
+fn cpu_usage(py: Python<'_>, sample_ms: u64) -> PyResult<Vec<f32>> {
+    py.allow_threads(move || wmcp_core::system_info::collect_cpu_usage(sample_ms))
+        .map_err(to_py_err)
+}
+
+/// List running processes as dicts, ordered by PID.
+///
+/// Each dict has `pid`, `name`, `parent_pid`, `memory_bytes` and
+/// `cpu_percent`.  CPU usage is measured between calls, so the first call
+/// reports 0.  `limit` caps the number returned.
+#[pyfunction]
+#[pyo3(signature = (limit=None))]
+fn list_processes(py: Python<'_>, limit: Option<usize>) -> PyResult<PyObject> {
+    let processes = py
+        .allow_threads(move || wmcp_core::system_info::list_processes(limit))
+        .map_err(to_py_err)?;
+
+    let list = PyList::empty(py);
+    for process in &processes {
+        let d = PyDict::new(py);
+        d.set_item("pid", process.pid)?;
+        d.set_item("name", &process.name)?;
+        d.set_item("parent_pid", process.parent_pid)?;
+        d.set_item("memory_bytes", process.memory_bytes)?;
+        d.set_item("cpu_percent", process.cpu_percent)?;
+        list.append(d)?;
+    }
+    Ok(list.into())
+}
+
 // ---------------------------------------------------------------------------
 // capture_tree
 // ---------------------------------------------------------------------------
 
+/// Parse a Python `properties` list into [`UiaProperty`] values; `None`
+/// selects the default set.
+fn parse_properties(properties: Option<Vec<String>>) -> PyResult<Vec<UiaProperty>> {
+    match properties {
+        Some(names) => names
+            .iter()
+            .map(|name| UiaProperty::from_name(name))
+            .collect::<Result<_, _>>()
+            .map_err(to_py_err),
+        None => Ok(UiaProperty::DEFAULT.to_vec()),
+    }
+}
+
 /// Capture the UIA accessibility tree for one or more windows.
+///
+/// `include_values=False` skips reading `ValuePattern.Value` for speed;
+/// the `value` key is then always empty.
+///
+/// `control_types=["Button", "Edit"]` keeps only nodes of those types.
+/// Descendants of a dropped node are re-parented to the nearest kept
+/// ancestor, and the window root is always kept.
+///
+/// `controls_only=True` likewise drops unnamed non-control elements
+/// (UIA "control view" rather than "raw view").
+///
+/// `skip_offscreen=True` likewise drops offscreen and zero-size elements
+/// (collapsed menus, virtualized list items).
+///
+/// `max_children` caps children read per node; a node whose children were
+/// clipped has `truncated=True`.  `max_total_nodes` caps nodes read across
+/// the whole call the same way, so a runaway tree cannot exhaust memory.
+///
+/// `include_state=True` fills `toggle_state` ("on"/"off"/"indeterminate")
+/// and `is_selected` where the element supports those patterns.
+///
+/// `max_threads` caps capture concurrency on a dedicated thread pool;
+/// `None` uses the shared Rayon pool.
+///
+/// `properties` lists the element keys to read, e.g. `["name",
+/// "control_type", "bounding_rect"]`; other keys keep empty/false/zero
+/// values, shrinking the capture.  Add `"help_text"`, `"access_key"` or
+/// `"culture"` to read those extras.  `None` reads the standard twelve.
 #[pyfunction]
-#[pyo3(signature = (window_handles, max_depth=None))]
+#[pyo3(signature = (
+    window_handles,
+    max_depth=None,
+    include_values=true,
+    control_types=None,
+    controls_only=false,
+    skip_offscreen=false,
+    max_children=wmcp_core::tree::MAX_CHILDREN_PER_NODE,
+    max_total_nodes=wmcp_core::tree::MAX_TOTAL_NODES,
+    include_state=false,
+    max_threads=None,
+    properties=None
+))]
+#[allow(clippy::too_many_arguments)] // One parameter per Python keyword argument
 fn capture_tree(
     py: Python<'_>,
     window_handles: Vec<isize>,
     max_depth: Option<usize>,
+    include_values: bool,
+    control_types: Option<Vec<String>>,
+    controls_only: bool,
+    skip_offscreen: bool,
+    max_children: usize,
+    max_total_nodes: usize,
+    include_state: bool,
+    max_threads: Option<usize>,
+    properties: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    if window_handles.len() > MAX_HANDLE_COUNT {
+        return Err(PyRuntimeError::new_err(format!(
+            "window_handles length {} exceeds maximum {MAX_HANDLE_COUNT}",
+            window_handles.len()
+        )));
+    }
+
+    let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+    let options = wmcp_core::tree::CaptureOptions {
+        include_values,
+        control_type_filter: control_types,
+        controls_only,
+        skip_offscreen,
+        max_children,
+        max_total_nodes,
+        include_state,
+        max_threads,
+        properties: parse_properties(properties)?,
+    };
+
+    let snapshots = py.allow_threads(|| {
+        wmcp_core::tree::capture_tree_raw(&window_handles, max_depth, &options)
+    });
+
+    let result = PyList::empty(py);
+    for snapshot in &snapshots {
+        result.append(snapshot_to_py_dict(py, snapshot)?)?;
+    }
+
+    Ok(result.into())
+}
+
+/// Capture the UIA tree as a flat pre-order list of dicts.
+///
+/// Each dict has the `capture_tree` keys minus `children`, plus `index`
+/// and `parent_index` (`None` for window roots).  `skip_offscreen` and
+/// `properties` are as for `capture_tree`.
+#[pyfunction]
+#[pyo3(signature = (
+    window_handles,
+    max_depth=None,
+    include_values=true,
+    control_types=None,
+    controls_only=false,
+    skip_offscreen=false,
+    max_children=wmcp_core::tree::MAX_CHILDREN_PER_NODE,
+    include_state=false,
+    properties=None
+))]
+#[allow(clippy::too_many_arguments)] // One parameter per Python keyword argument
+fn capture_tree_flat(
+    py: Python<'_>,
+    window_handles: Vec<isize>,
+    max_depth: Option<usize>,
+    include_values: bool,
+    control_types: Option<Vec<String>>,
+    controls_only: bool,
+    skip_offscreen: bool,
+    max_children: usize,
+    include_state: bool,
+    properties: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    if window_handles.len() > MAX_HANDLE_COUNT {
+        return Err(PyRuntimeError::new_err(format!(
+            "window_handles length {} exceeds maximum {MAX_HANDLE_COUNT}",
+            window_handles.len()
+        )));
+    }
+
+    let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+    let options = wmcp_core::tree::CaptureOptions {
+        include_values,
+        control_type_filter: control_types,
+        controls_only,
+        skip_offscreen,
+        max_children,
+        include_state,
+        properties: parse_properties(properties)?,
+        ..Default::default()
+    };
+
+    let elements = py.allow_threads(|| {
+        wmcp_core::tree::capture_tree_flat_raw(&window_handles, max_depth, &options)
+    });
+
+    let result = PyList::empty(py);
+    for elem in &elements {
+        result.append(flat_element_to_py_dict(py, elem)?)?;
+    }
+
+    Ok(result.into())
+}
+
+/// Capture the UIA tree and render it as an indented text outline.
+///
+/// One line per element, e.g. `  Button "Save" [id=btnSave] (enabled)`,
+/// indented two spaces per depth -- far fewer tokens than the nested
+/// dicts from `capture_tree` when the tree goes into a prompt.
+#[pyfunction]
+#[pyo3(signature = (window_handles, max_depth=None))]
+fn capture_tree_outline(
+    py: Python<'_>,
+    window_handles: Vec<isize>,
+    max_depth: Option<usize>,
+) -> PyResult<String> {
+    if window_handles.len() > MAX_HANDLE_COUNT {
+        return Err(PyRuntimeError::new_err(format!(
+            "window_handles length {} exceeds maximum {MAX_HANDLE_COUNT}",
+            window_handles.len()
+        )));
+    }
+
+    let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+    Ok(py.allow_threads(|| {
+        let snapshots = wmcp_core::tree::capture_tree_raw(
+            &window_handles,
+            max_depth,
+            &wmcp_core::tree::CaptureOptions::default(),
+        );
+        wmcp_core::tree::element::render_outline(&snapshots)
+    }))
+}
+
+/// Capture the UIA tree, reporting the outcome for every handle.
+///
+/// Returns one dict per input handle, in order: `{"hwnd": ..., "tree": {...}}`
+/// on success (same shape as a `capture_tree` entry) or
+/// `{"hwnd": ..., "error": "..."}` naming why that window failed.
+#[pyfunction]
+#[pyo3(signature = (window_handles, max_depth=None))]
+fn capture_tree_detailed(
+    py: Python<'_>,
+    window_handles: Vec<isize>,
+    max_depth: Option<usize>,
 ) -> PyResult<PyObject> {
     if window_handles.len() > MAX_HANDLE_COUNT {
         return Err(PyRuntimeError::new_err(format!(
@@ -153,9 +507,51 @@ fn capture_tree(
     }
 
     let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+    let outcomes = py.allow_threads(|| {
+        wmcp_core::tree::capture_tree_detailed_raw(
+            &window_handles,
+            max_depth,
+            &wmcp_core::tree::CaptureOptions::default(),
+        )
+    });
+
+    let result = PyList::empty(py);
+    for (hwnd, outcome) in &outcomes {
+        let entry = PyDict::new(py);
+        entry.set_item("hwnd", hwnd)?;
+        match outcome {
+            Ok(snapshot) => entry.set_item("tree", snapshot_to_py_dict(py, snapshot)?)?,
+            Err(error) => entry.set_item("error", error)?,
+        }
+        result.append(entry)?;
+    }
+
+    Ok(result.into())
+}
+
+/// Capture the UIA tree breadth-first with at most `max_nodes` nodes per
+/// window.  Same dict shape as `capture_tree`; nodes with unvisited
+/// children have `truncated=True`.
+#[pyfunction]
+#[pyo3(signature = (window_handles, max_nodes))]
+fn capture_tree_bfs(
+    py: Python<'_>,
+    window_handles: Vec<isize>,
+    max_nodes: usize,
+) -> PyResult<PyObject> {
+    if window_handles.len() > MAX_HANDLE_COUNT {
+        return Err(PyRuntimeError::new_err(format!(
+            "window_handles length {} exceeds maximum {MAX_HANDLE_COUNT}",
+            window_handles.len()
+        )));
+    }
 
     let snapshots = py.allow_threads(|| {
-        wmcp_core::tree::capture_tree_raw(&window_handles, max_depth)
+        wmcp_core::tree::capture_tree_bfs_raw(
+            &window_handles,
+            max_nodes,
+            &wmcp_core::tree::CaptureOptions::default(),
+        )
     });
 
     let result = PyList::empty(py);
@@ -166,65 +562,388 @@ fn capture_tree(
     Ok(result.into())
 }
 
+/// Capture the UIA subtree under the element at screen point `(x, y)`.
+///
+/// Returns a nested dict like `capture_tree` entries, or `None` if no
+/// element is found at the point.
+#[pyfunction]
+#[pyo3(signature = (x, y, max_depth=None))]
+fn capture_subtree_from_point(
+    py: Python<'_>,
+    x: i32,
+    y: i32,
+    max_depth: Option<usize>,
+) -> PyResult<Option<PyObject>> {
+    let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+
+    let snapshot = py.allow_threads(|| {
+        wmcp_core::tree::capture_subtree_from_point_raw(
+            x,
+            y,
+            max_depth,
+            &wmcp_core::tree::CaptureOptions::default(),
+        )
+    });
+
+    snapshot
+        .map(|snap| snapshot_to_py_dict(py, &snap))
+        .transpose()
+}
+
+/// Capture the UIA tree of the current foreground window.
+///
+/// Returns a nested dict like `capture_tree` entries, or `None` if no
+/// window is in the foreground or it cannot be captured.
+#[pyfunction]
+#[pyo3(signature = (max_depth=None))]
+fn capture_foreground_tree(py: Python<'_>, max_depth: Option<usize>) -> PyResult<Option<PyObject>> {
+    let max_depth = max_depth.unwrap_or(wmcp_core::tree::MAX_TREE_DEPTH);
+
+    let snapshot = py.allow_threads(|| {
+        wmcp_core::tree::capture_foreground_tree_raw(
+            max_depth,
+            &wmcp_core::tree::CaptureOptions::default(),
+        )
+    });
+
+    snapshot
+        .map(|snap| snapshot_to_py_dict(py, &snap))
+        .transpose()
+}
+
+/// Deepest dict nesting [`snapshot_from_py_dict`] accepts.
+const MAX_DICT_TREE_DEPTH: usize = 256;
+
+/// Read `key` from a tree dict, using the type's default when the key is
+/// missing or `None`.
+fn dict_field<'py, T: FromPyObject<'py> + Default>(
+    dict: &Bound<'py, PyDict>,
+    key: &str,
+) -> PyResult<T> {
+    match dict.get_item(key)? {
+        Some(value) if !value.is_none() => value.extract(),
+        _ => Ok(T::default()),
+    }
+}
+
+/// Rebuild a [`TreeElementSnapshot`] from a `capture_tree` dict, the
+/// inverse of [`snapshot_to_py_dict`].
+///
+/// Missing keys take their empty/false/zero defaults.  Recursive, so the
+/// nesting is capped at [`MAX_DICT_TREE_DEPTH`].
+fn snapshot_from_py_dict(dict: &Bound<'_, PyDict>, level: usize) -> PyResult<TreeElementSnapshot> {
+    if level > MAX_DICT_TREE_DEPTH {
+        return Err(PyRuntimeError::new_err(format!(
+            "tree dict nested deeper than {MAX_DICT_TREE_DEPTH} levels"
+        )));
+    }
+    let children: Vec<Bound<'_, PyDict>> = dict_field(dict, "children")?;
+    Ok(TreeElementSnapshot {
+        name: dict_field(dict, "name")?,
+        automation_id: dict_field(dict, "automation_id")?,
+        control_type: dict_field(dict, "control_type")?,
+        localized_control_type: dict_field(dict, "localized_control_type")?,
+        class_name: dict_field(dict, "class_name")?,
+        bounding_rect: dict_field(dict, "bounding_rect")?,
+        is_offscreen: dict_field(dict, "is_offscreen")?,
+        is_enabled: dict_field(dict, "is_enabled")?,
+        is_control_element: dict_field(dict, "is_control_element")?,
+        has_keyboard_focus: dict_field(dict, "has_keyboard_focus")?,
+        is_keyboard_focusable: dict_field(dict, "is_keyboard_focusable")?,
+        accelerator_key: dict_field(dict, "accelerator_key")?,
+        help_text: dict_field(dict, "help_text")?,
+        access_key: dict_field(dict, "access_key")?,
+        culture: dict_field(dict, "culture")?,
+        value: dict_field(dict, "value")?,
+        toggle_state: dict_field(dict, "toggle_state")?,
+        is_selected: dict_field(dict, "is_selected")?,
+        depth: dict_field(dict, "depth")?,
+        truncated: dict_field(dict, "truncated")?,
+        children: children
+            .iter()
+            .map(|child| snapshot_from_py_dict(child, level + 1))
+            .collect::<PyResult<_>>()?,
+    })
+}
+
+/// Convert a [`DiffNode`] to a Python dict.
+fn diff_node_to_dict<'py>(py: Python<'py>, node: &DiffNode) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("automation_id", &node.automation_id)?;
+    dict.set_item("control_type", &node.control_type)?;
+    dict.set_item("name", &node.name)?;
+    dict.set_item("depth", node.depth)?;
+    dict.set_item("bounding_rect", node.bounding_rect.to_vec())?;
+    Ok(dict)
+}
+
+/// Convert a [`TreeDiff`] to a Python dict.
+fn tree_diff_to_dict(py: Python<'_>, diff: &TreeDiff) -> PyResult<PyObject> {
+    let added = PyList::empty(py);
+    for node in &diff.added {
+        added.append(diff_node_to_dict(py, node)?)?;
+    }
+    let removed = PyList::empty(py);
+    for node in &diff.removed {
+        removed.append(diff_node_to_dict(py, node)?)?;
+    }
+    let changed = PyList::empty(py);
+    for entry in &diff.changed {
+        let dict = diff_node_to_dict(py, &entry.node)?;
+        let changes = PyList::empty(py);
+        for change in &entry.changes {
+            let item = PyDict::new(py);
+            item.set_item("field", change.field)?;
+            item.set_item("before", &change.before)?;
+            item.set_item("after", &change.after)?;
+            changes.append(item)?;
+        }
+        dict.set_item("changes", changes)?;
+        changed.append(dict)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("added", added)?;
+    dict.set_item("removed", removed)?;
+    dict.set_item("changed", changed)?;
+    Ok(dict.into())
+}
+
+/// Compare two `capture_tree` results for the same window.
+///
+/// Takes one root dict from each capture and returns `{"added": [...],
+/// "removed": [...], "changed": [...]}`.  Nodes are matched by
+/// (automation_id, control_type, name); `added`/`removed` entries carry
+/// those keys plus `depth` and `bounding_rect`, and `changed` entries add
+/// a `changes` list of `{"field", "before", "after"}` string dicts.
+#[pyfunction]
+#[pyo3(signature = (before, after))]
+fn diff_trees(before: &Bound<'_, PyDict>, after: &Bound<'_, PyDict>) -> PyResult<PyObject> {
+    let py = before.py();
+    let before = snapshot_from_py_dict(before, 0)?;
+    let after = snapshot_from_py_dict(after, 0)?;
+    let diff = py.allow_threads(|| wmcp_core::tree::element::diff_trees(&before, &after));
+    tree_diff_to_dict(py, &diff)
+}
+
 // ---------------------------------------------------------------------------
 // input functions
 // ---------------------------------------------------------------------------
 
 /// Type Unicode text via SendInput.
+///
+/// With `delay_ms > 0`, characters are sent one at a time with a sleep
+/// between them.  The GIL is released for the full duration.
 #[pyfunction]
-#[pyo3(signature = (text,))]
-fn send_text(py: Python<'_>, text: &str) -> PyResult<u32> {
-    if text.len() > MAX_SEND_TEXT_LEN {
+#[pyo3(signature = (text, delay_ms=0))]
+fn send_text(py: Python<'_>, text: &str, delay_ms: u32) -> PyResult<u32> {
+    let text_units = text.encode_utf16().count();
+    if text_units > MAX_SEND_TEXT_LEN {
         return Err(PyRuntimeError::new_err(format!(
-            "text length {} exceeds maximum {MAX_SEND_TEXT_LEN}",
-            text.len()
+            "text length {text_units} UTF-16 units exceeds maximum {MAX_SEND_TEXT_LEN}"
         )));
     }
     let text_owned = text.to_owned();
-    Ok(py.allow_threads(move || wmcp_core::input::send_text_raw(&text_owned)))
+    py.allow_threads(move || {
+        wmcp_core::input::send_text_delayed_raw(&text_owned, delay_ms)
+    })
+    .map_err(to_py_err)
 }
 
 /// Press or release a virtual key code.
 #[pyfunction]
 #[pyo3(signature = (vk_code, key_up=false))]
 fn send_key(py: Python<'_>, vk_code: u16, key_up: bool) -> PyResult<u32> {
-    Ok(py.allow_threads(move || wmcp_core::input::send_key_raw(vk_code, key_up)))
+    py.allow_threads(move || wmcp_core::input::send_key_raw(vk_code, key_up))
+        .map_err(to_py_err)
+}
+
+/// Press or release a key by hardware scan code (for games that ignore
+/// virtual keys).  Set `extended` for E0-prefixed keys such as arrows.
+#[pyfunction]
+#[pyo3(signature = (scan_code, key_up=false, extended=false))]
+fn send_scancode(py: Python<'_>, scan_code: u16, key_up: bool, extended: bool) -> PyResult<u32> {
+    py.allow_threads(move || wmcp_core::input::send_scancode_raw(scan_code, key_up, extended))
+        .map_err(to_py_err)
+}
+
+/// Press or release a key by name (e.g. "enter", "ctrl", "f5").
+#[pyfunction]
+#[pyo3(signature = (name, key_up=false))]
+fn send_key_by_name(py: Python<'_>, name: &str, key_up: bool) -> PyResult<u32> {
+    let name_owned = name.to_owned();
+    py.allow_threads(move || wmcp_core::input::send_key_by_name_raw(&name_owned, key_up))
+        .map_err(to_py_err)
 }
 
 /// Click the mouse at absolute screen coordinates.
+///
+/// With `hover_ms > 0` the cursor moves first and rests there that long
+/// before the click, for apps that ignore a click arriving with the move.
+#[pyfunction]
+#[pyo3(signature = (x, y, button="left", hover_ms=0))]
+fn send_click(py: Python<'_>, x: i32, y: i32, button: &str, hover_ms: u32) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || {
+        wmcp_core::input::send_click_hover_raw(x, y, &button_owned, hover_ms)
+    })
+    .map_err(to_py_err)
+}
+
+/// Click at client-area coordinates of window `hwnd`.
+///
+/// The point is converted to screen coordinates when the click is sent,
+/// so it follows the window if it has moved.
+#[pyfunction]
+#[pyo3(signature = (hwnd, x, y, button="left"))]
+fn send_click_in_window(
+    py: Python<'_>,
+    hwnd: isize,
+    x: i32,
+    y: i32,
+    button: &str,
+) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || {
+        wmcp_core::input::send_click_in_window_raw(hwnd, x, y, &button_owned)
+    })
+    .map_err(to_py_err)
+}
+
+/// Double-click the mouse at absolute screen coordinates.
+#[pyfunction]
+#[pyo3(signature = (x, y, button="left"))]
+fn send_double_click(py: Python<'_>, x: i32, y: i32, button: &str) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || wmcp_core::input::send_double_click_raw(x, y, &button_owned))
+        .map_err(to_py_err)
+}
+
+/// Press a mouse button at absolute screen coordinates (no release).
+#[pyfunction]
+#[pyo3(signature = (x, y, button="left"))]
+fn send_mouse_down(py: Python<'_>, x: i32, y: i32, button: &str) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || wmcp_core::input::send_mouse_down_raw(x, y, &button_owned))
+        .map_err(to_py_err)
+}
+
+/// Release a mouse button at absolute screen coordinates.
 #[pyfunction]
 #[pyo3(signature = (x, y, button="left"))]
-fn send_click(py: Python<'_>, x: i32, y: i32, button: &str) -> PyResult<u32> {
+fn send_mouse_up(py: Python<'_>, x: i32, y: i32, button: &str) -> PyResult<u32> {
     let button_owned = button.to_lowercase();
-    Ok(py.allow_threads(move || wmcp_core::input::send_click_raw(x, y, &button_owned)))
+    py.allow_threads(move || wmcp_core::input::send_mouse_up_raw(x, y, &button_owned))
+        .map_err(to_py_err)
 }
 
 /// Move the mouse cursor to absolute screen coordinates.
 #[pyfunction]
 #[pyo3(signature = (x, y))]
 fn send_mouse_move(py: Python<'_>, x: i32, y: i32) -> PyResult<u32> {
-    Ok(py.allow_threads(move || wmcp_core::input::send_mouse_move_raw(x, y)))
+    py.allow_threads(move || wmcp_core::input::send_mouse_move_raw(x, y))
+        .map_err(to_py_err)
+}
+
+/// Get the current cursor position as an `(x, y)` tuple of screen pixels.
+#[pyfunction]
+fn get_cursor_pos(py: Python<'_>) -> PyResult<(i32, i32)> {
+    py.allow_threads(wmcp_core::input::get_cursor_pos).map_err(to_py_err)
+}
+
+/// Move the mouse cursor by (dx, dy) pixels relative to its current position.
+///
+/// Subject to mouse acceleration ("Enhance pointer precision"), so the
+/// on-screen distance may differ from (dx, dy).
+#[pyfunction]
+#[pyo3(signature = (dx, dy))]
+fn send_mouse_move_relative(py: Python<'_>, dx: i32, dy: i32) -> PyResult<u32> {
+    py.allow_threads(move || wmcp_core::input::send_mouse_move_relative_raw(dx, dy))
+        .map_err(to_py_err)
 }
 
 /// Send a key combination (e.g. Ctrl+C).
 #[pyfunction]
 #[pyo3(signature = (vk_codes,))]
 fn send_hotkey(py: Python<'_>, vk_codes: Vec<u16>) -> PyResult<u32> {
-    Ok(py.allow_threads(move || wmcp_core::input::send_hotkey_raw(&vk_codes)))
+    py.allow_threads(move || wmcp_core::input::send_hotkey_raw(&vk_codes))
+        .map_err(to_py_err)
+}
+
+/// Send a key combination given as a string (e.g. "alt+f4").
+#[pyfunction]
+#[pyo3(signature = (combo,))]
+fn send_hotkey_str(py: Python<'_>, combo: &str) -> PyResult<u32> {
+    let combo_owned = combo.to_owned();
+    py.allow_threads(move || wmcp_core::input::send_hotkey_str_raw(&combo_owned))
+        .map_err(to_py_err)
+}
+
+/// Send a SendKeys-style sequence (e.g. "^a{DELETE}Hello{ENTER}").
+///
+/// `{NAME}` taps a named key, `+`/`^`/`%` hold Shift/Ctrl/Alt over the
+/// next key or `(...)` group, and other characters are typed as text.
+#[pyfunction]
+#[pyo3(signature = (sequence,))]
+fn send_keys(py: Python<'_>, sequence: &str) -> PyResult<u32> {
+    let sequence_owned = sequence.to_owned();
+    py.allow_threads(move || wmcp_core::input::send_keys_raw(&sequence_owned))
+        .map_err(to_py_err)
 }
 
 /// Scroll the mouse wheel at screen coordinates.
 #[pyfunction]
 #[pyo3(signature = (x, y, delta, horizontal=false))]
 fn send_scroll(py: Python<'_>, x: i32, y: i32, delta: i32, horizontal: bool) -> PyResult<u32> {
-    Ok(py.allow_threads(move || wmcp_core::input::send_scroll_raw(x, y, delta, horizontal)))
+    py.allow_threads(move || wmcp_core::input::send_scroll_raw(x, y, delta, horizontal))
+        .map_err(to_py_err)
+}
+
+/// Scroll the mouse wheel by lines at screen coordinates.
+///
+/// Uses the user's wheel-scroll-lines setting; positive scrolls up (or
+/// right when `horizontal`).
+#[pyfunction]
+#[pyo3(signature = (x, y, lines, horizontal=false))]
+fn send_scroll_lines(
+    py: Python<'_>,
+    x: i32,
+    y: i32,
+    lines: i32,
+    horizontal: bool,
+) -> PyResult<u32> {
+    py.allow_threads(move || wmcp_core::input::send_scroll_lines_raw(x, y, lines, horizontal))
+        .map_err(to_py_err)
 }
 
 /// Drag the mouse from current position to destination coordinates.
 #[pyfunction]
-#[pyo3(signature = (to_x, to_y, steps=10))]
-fn send_drag(py: Python<'_>, to_x: i32, to_y: i32, steps: u32) -> PyResult<u32> {
-    Ok(py.allow_threads(move || wmcp_core::input::send_drag_raw(to_x, to_y, steps)))
+#[pyo3(signature = (to_x, to_y, steps=10, button="left"))]
+fn send_drag(py: Python<'_>, to_x: i32, to_y: i32, steps: u32, button: &str) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || {
+        wmcp_core::input::send_drag_raw(to_x, to_y, steps, &button_owned)
+    })
+    .map_err(to_py_err)
+}
+
+/// Drag the mouse between two explicit screen coordinates.
+#[pyfunction]
+#[pyo3(signature = (from_x, from_y, to_x, to_y, steps=10, button="left"))]
+fn send_drag_from(
+    py: Python<'_>,
+    from_x: i32,
+    from_y: i32,
+    to_x: i32,
+    to_y: i32,
+    steps: u32,
+    button: &str,
+) -> PyResult<u32> {
+    let button_owned = button.to_lowercase();
+    py.allow_threads(move || {
+        wmcp_core::input::send_drag_from_raw(from_x, from_y, to_x, to_y, steps, &button_owned)
+    })
+    .map_err(to_py_err)
 }
 
 // ---------------------------------------------------------------------------
@@ -252,17 +971,51 @@ fn get_window_info(py: Python<'_>, hwnd: isize) -> PyResult<PyObject> {
     window_info_to_dict(py, &info)
 }
 
+/// Get information about the top-level window under screen point (x, y).
+///
+/// Raises `RuntimeError` if no window is at the point.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn window_from_point(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
+    let info = py
+        .allow_threads(move || wmcp_core::window::window_from_point(x, y))
+        .map_err(to_py_err)?;
+
+    window_info_to_dict(py, &info)
+}
+
+/// Count the listed windows above `hwnd` in the Z order (0 = topmost).
+#[pyfunction]
+#[pyo3(signature = (hwnd,))]
+fn get_window_z_order(py: Python<'_>, hwnd: isize) -> PyResult<usize> {
+    py.allow_threads(move || wmcp_core::window::get_window_z_order(hwnd))
+        .map_err(to_py_err)
+}
+
+/// Index of the monitor `hwnd` is mostly on, usable as the
+/// `monitor_index` of the screenshot functions.
+#[pyfunction]
+#[pyo3(signature = (hwnd,))]
+fn get_window_monitor_index(py: Python<'_>, hwnd: isize) -> PyResult<u32> {
+    py.allow_threads(move || wmcp_core::window::get_window_monitor_index(hwnd))
+        .map_err(to_py_err)
+}
+
 /// Get the foreground (active) window handle.
 #[pyfunction]
 fn get_foreground_window(py: Python<'_>) -> PyResult<isize> {
     Ok(py.allow_threads(wmcp_core::window::get_foreground_hwnd))
 }
 
-/// List all visible windows with their information.
+/// List every visible top-level window without the Alt+Tab filter.
+///
+/// With `include_cloaked`, windows on other virtual desktops (and other
+/// DWM-cloaked windows) are included; their `is_cloaked` key is true.
 #[pyfunction]
-fn list_windows(py: Python<'_>) -> PyResult<PyObject> {
+#[pyo3(signature = (include_cloaked=false))]
+fn list_all_windows(py: Python<'_>, include_cloaked: bool) -> PyResult<PyObject> {
     let windows = py
-        .allow_threads(wmcp_core::window::list_windows)
+        .allow_threads(move || wmcp_core::window::list_all_windows(include_cloaked))
         .map_err(to_py_err)?;
 
     let result = PyList::empty(py);
@@ -273,20 +1026,160 @@ fn list_windows(py: Python<'_>) -> PyResult<PyObject> {
     Ok(result.into())
 }
 
-// ---------------------------------------------------------------------------
-// screenshot functions
-// ---------------------------------------------------------------------------
-
-/// Capture a screenshot as raw BGRA pixel bytes.
+/// Find visible windows by title substring (case-insensitive), exact class
+/// name, and/or process ID.  Omitted filters match everything.
 ///
-/// Returns a dict with keys: `width` (int), `height` (int), `data` (bytes).
+/// Returns a list of dicts with the same keys as `list_windows`.
 #[pyfunction]
-#[pyo3(signature = (monitor_index=0))]
-fn capture_screenshot_raw(py: Python<'_>, monitor_index: u32) -> PyResult<PyObject> {
-    let frame = py
-        .allow_threads(move || wmcp_core::screenshot::capture_raw(monitor_index))
-        .map_err(to_py_err)?;
-
+#[pyo3(signature = (title=None, class_name=None, pid=None))]
+fn find_windows(
+    py: Python<'_>,
+    title: Option<String>,
+    class_name: Option<String>,
+    pid: Option<u32>,
+) -> PyResult<PyObject> {
+    let windows = py
+        .allow_threads(move || {
+            wmcp_core::window::find_windows(title.as_deref(), class_name.as_deref(), pid)
+        })
+        .map_err(to_py_err)?;
+
+    let result = PyList::empty(py);
+    for info in &windows {
+        result.append(window_info_to_dict(py, info)?)?;
+    }
+
+    Ok(result.into())
+}
+
+/// Move and resize a window; coordinates are physical screen pixels.
+///
+/// Does not change focus or Z order.  Raises `RuntimeError` if the handle
+/// is invalid or `SetWindowPos` fails.
+#[pyfunction]
+#[pyo3(signature = (hwnd, x, y, w, h))]
+fn set_window_rect(py: Python<'_>, hwnd: isize, x: i32, y: i32, w: i32, h: i32) -> PyResult<()> {
+    py.allow_threads(move || wmcp_core::window::set_window_rect(hwnd, x, y, w, h))
+        .map_err(to_py_err)
+}
+
+/// Make a window always-on-top (`topmost=True`) or undo it.
+///
+/// Does not move, resize or focus the window.  Raises `RuntimeError` if
+/// the handle is invalid or `SetWindowPos` fails.
+#[pyfunction]
+#[pyo3(signature = (hwnd, topmost=true))]
+fn set_window_topmost(py: Python<'_>, hwnd: isize, topmost: bool) -> PyResult<()> {
+    py.allow_threads(move || wmcp_core::window::set_window_topmost(hwnd, topmost))
+        .map_err(to_py_err)
+}
+
+/// Change a window's show state.
+///
+/// `state` is one of "minimize", "maximize", "restore", "hide", "show".
+/// Raises `RuntimeError` for an invalid handle or unknown state.
+#[pyfunction]
+#[pyo3(signature = (hwnd, state))]
+fn set_window_state(py: Python<'_>, hwnd: isize, state: &str) -> PyResult<()> {
+    let state_owned = state.to_owned();
+    py.allow_threads(move || wmcp_core::window::set_window_state(hwnd, &state_owned))
+        .map_err(to_py_err)
+}
+
+/// Bring a window to the foreground and give it keyboard focus.
+///
+/// Call before `send_text` / `send_key` so input reaches the intended
+/// window.  Raises `RuntimeError` if the window could not be focused.
+#[pyfunction]
+#[pyo3(signature = (hwnd,))]
+fn focus_window(py: Python<'_>, hwnd: isize) -> PyResult<()> {
+    py.allow_threads(move || wmcp_core::window::focus_window(hwnd))
+        .map_err(to_py_err)
+}
+
+/// Ask a window to close (posts WM_CLOSE).
+///
+/// A polite request: the application may ignore it or prompt the user
+/// (e.g. to save changes), so the window can still exist afterwards.
+#[pyfunction]
+#[pyo3(signature = (hwnd,))]
+fn close_window(py: Python<'_>, hwnd: isize) -> PyResult<()> {
+    py.allow_threads(move || wmcp_core::window::close_window(hwnd))
+        .map_err(to_py_err)
+}
+
+/// Click at (x, y) and wait up to `timeout_ms` for a new top-level window
+/// (e.g. a dialog) to appear, with the GIL released while polling.
+///
+/// Returns the new window's info dict (shaped like a `list_windows`
+/// entry), or `None` on timeout.  Owned dialogs count as new windows.
+#[pyfunction]
+#[pyo3(signature = (x, y, button="left", timeout_ms=5000))]
+fn click_and_wait_for_window(
+    py: Python<'_>,
+    x: i32,
+    y: i32,
+    button: &str,
+    timeout_ms: u64,
+) -> PyResult<Option<PyObject>> {
+    let button_owned = button.to_lowercase();
+    let found = py
+        .allow_threads(move || {
+            wmcp_core::window::click_and_wait_for_window(x, y, &button_owned, timeout_ms)
+        })
+        .map_err(to_py_err)?;
+    found.map(|info| window_info_to_dict(py, &info)).transpose()
+}
+
+/// List all visible windows with their information.
+#[pyfunction]
+fn list_windows(py: Python<'_>) -> PyResult<PyObject> {
+    let windows = py
+        .allow_threads(wmcp_core::window::list_windows)
+        .map_err(to_py_err)?;
+
+    let result = PyList::empty(py);
+    for info in &windows {
+        result.append(window_info_to_dict(py, info)?)?;
+    }
+
+    Ok(result.into())
+}
+
+// ---------------------------------------------------------------------------
+// screenshot functions
+// ---------------------------------------------------------------------------
+
+/// Capture a screenshot as raw BGRA pixel bytes.
+///
+/// Returns a dict with keys: `width` (int), `height` (int), `data` (bytes).
+/// With `max_dimension`, the frame is downscaled so its longest side fits
+/// and `width`/`height` report the scaled size.  `include_cursor` draws the
+/// mouse pointer into the frame.  `timeout_ms` bounds the wait for a new
+/// frame; on a still desktop the previous frame is returned.
+#[pyfunction]
+#[pyo3(signature = (
+    monitor_index=0,
+    max_dimension=None,
+    include_cursor=false,
+    timeout_ms=wmcp_core::screenshot::DEFAULT_FRAME_TIMEOUT_MS
+))]
+fn capture_screenshot_raw(
+    py: Python<'_>,
+    monitor_index: u32,
+    max_dimension: Option<u32>,
+    include_cursor: bool,
+    timeout_ms: u32,
+) -> PyResult<PyObject> {
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        max_dimension,
+        include_cursor,
+        timeout_ms,
+    };
+    let frame = py
+        .allow_threads(move || wmcp_core::screenshot::capture_frame(monitor_index, &options))
+        .map_err(to_py_err)?;
+
     let dict = PyDict::new(py);
     dict.set_item("width", frame.width)?;
     dict.set_item("height", frame.height)?;
@@ -296,17 +1189,191 @@ fn capture_screenshot_raw(py: Python<'_>, monitor_index: u32) -> PyResult<PyObje
 
 /// Capture a screenshot and encode it as PNG bytes.
 ///
-/// Returns a `bytes` object containing the PNG file data.
+/// Returns a `bytes` object containing the PNG file data.  With
+/// `max_dimension`, the frame is first downscaled (Lanczos3) so its longest
+/// side is at most that many pixels.  `include_cursor` and `timeout_ms`
+/// behave as for `capture_screenshot_raw`.
+#[pyfunction]
+#[pyo3(signature = (
+    monitor_index=0,
+    max_dimension=None,
+    include_cursor=false,
+    timeout_ms=wmcp_core::screenshot::DEFAULT_FRAME_TIMEOUT_MS
+))]
+fn capture_screenshot_png(
+    py: Python<'_>,
+    monitor_index: u32,
+    max_dimension: Option<u32>,
+    include_cursor: bool,
+    timeout_ms: u32,
+) -> PyResult<PyObject> {
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        max_dimension,
+        include_cursor,
+        timeout_ms,
+    };
+    let png_bytes = py
+        .allow_threads(move || wmcp_core::screenshot::capture_png(monitor_index, &options))
+        .map_err(to_py_err)?;
+
+    Ok(pyo3::types::PyBytes::new(py, &png_bytes).into())
+}
+
+/// Release cached screenshot sessions (D3D11 devices, DXGI duplications).
+///
+/// They are rebuilt on the next capture.
+#[pyfunction]
+fn reset_screenshot_cache(py: Python<'_>) {
+    py.allow_threads(wmcp_core::screenshot::reset_screenshot_cache);
+}
+
+/// Capture a screenshot and encode it as JPEG bytes.
+///
+/// `quality` is clamped to 1-100; lower values shrink the payload at the
+/// cost of artefacts around text.  The other arguments behave as for
+/// `capture_screenshot_png`.
+#[pyfunction]
+#[pyo3(signature = (
+    monitor_index=0,
+    quality=wmcp_core::screenshot::DEFAULT_JPEG_QUALITY,
+    max_dimension=None,
+    include_cursor=false,
+    timeout_ms=wmcp_core::screenshot::DEFAULT_FRAME_TIMEOUT_MS
+))]
+fn capture_screenshot_jpeg(
+    py: Python<'_>,
+    monitor_index: u32,
+    quality: u8,
+    max_dimension: Option<u32>,
+    include_cursor: bool,
+    timeout_ms: u32,
+) -> PyResult<PyObject> {
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        max_dimension,
+        include_cursor,
+        timeout_ms,
+    };
+    let jpeg_bytes = py
+        .allow_threads(move || {
+            wmcp_core::screenshot::capture_jpeg(monitor_index, quality, &options)
+        })
+        .map_err(to_py_err)?;
+
+    Ok(pyo3::types::PyBytes::new(py, &jpeg_bytes).into())
+}
+
+/// Capture a screenshot and encode it as lossless WebP bytes.
+///
+/// `include_cursor` and `timeout_ms` behave as for `capture_screenshot_raw`.
+#[pyfunction]
+#[pyo3(signature = (monitor_index=0, include_cursor=false, timeout_ms=wmcp_core::screenshot::DEFAULT_FRAME_TIMEOUT_MS))]
+fn capture_screenshot_webp(
+    py: Python<'_>,
+    monitor_index: u32,
+    include_cursor: bool,
+    timeout_ms: u32,
+) -> PyResult<PyObject> {
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        include_cursor,
+        timeout_ms,
+        ..Default::default()
+    };
+    let webp_bytes = py
+        .allow_threads(move || wmcp_core::screenshot::capture_webp(monitor_index, &options))
+        .map_err(to_py_err)?;
+
+    Ok(pyo3::types::PyBytes::new(py, &webp_bytes).into())
+}
+
+/// Capture a sub-rectangle of a monitor and encode it as PNG bytes.
+///
+/// `(x, y)` is relative to the monitor's top-left corner.  Raises
+/// `RuntimeError` if the region falls outside the monitor.
+/// `include_cursor` draws the mouse pointer when it lies inside the region;
+/// `timeout_ms` behaves as for `capture_screenshot_raw`.
+#[pyfunction]
+#[pyo3(signature = (
+    x,
+    y,
+    width,
+    height,
+    monitor_index=0,
+    include_cursor=false,
+    timeout_ms=wmcp_core::screenshot::DEFAULT_FRAME_TIMEOUT_MS
+))]
+#[allow(clippy::too_many_arguments)] // One parameter per Python keyword argument
+fn capture_region_png(
+    py: Python<'_>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    monitor_index: u32,
+    include_cursor: bool,
+    timeout_ms: u32,
+) -> PyResult<PyObject> {
+    let options = wmcp_core::screenshot::ScreenshotOptions {
+        include_cursor,
+        timeout_ms,
+        ..Default::default()
+    };
+    let png_bytes = py
+        .allow_threads(move || {
+            wmcp_core::screenshot::capture_region_png(monitor_index, x, y, width, height, &options)
+        })
+        .map_err(to_py_err)?;
+
+    Ok(pyo3::types::PyBytes::new(py, &png_bytes).into())
+}
+
+/// Capture a monitor as a `data:image/png;base64,...` URI string.
+///
+/// Ready to embed in JSON or pass straight to a vision model API.
 #[pyfunction]
 #[pyo3(signature = (monitor_index=0))]
-fn capture_screenshot_png(py: Python<'_>, monitor_index: u32) -> PyResult<PyObject> {
+fn capture_screenshot_data_uri(py: Python<'_>, monitor_index: u32) -> PyResult<String> {
+    py.allow_threads(move || wmcp_core::screenshot::capture_png_base64(monitor_index))
+        .map_err(to_py_err)
+}
+
+/// Capture all monitors stitched into one image, as PNG bytes.
+///
+/// Monitors keep their desktop layout; gaps are transparent.  Slower and
+/// far larger than a single-monitor capture.
+#[pyfunction]
+fn capture_all_monitors_png(py: Python<'_>) -> PyResult<PyObject> {
     let png_bytes = py
-        .allow_threads(move || wmcp_core::screenshot::capture_png(monitor_index))
+        .allow_threads(wmcp_core::screenshot::capture_all_monitors_png)
         .map_err(to_py_err)?;
 
     Ok(pyo3::types::PyBytes::new(py, &png_bytes).into())
 }
 
+/// Capture a single window by HWND and encode it as PNG bytes.
+///
+/// Raises `RuntimeError` if the handle is invalid or the window is minimized.
+#[pyfunction]
+fn capture_window_png(py: Python<'_>, hwnd: isize) -> PyResult<PyObject> {
+    let png_bytes = py
+        .allow_threads(move || wmcp_core::screenshot::capture_window_png(hwnd))
+        .map_err(to_py_err)?;
+
+    Ok(pyo3::types::PyBytes::new(py, &png_bytes).into())
+}
+
+/// Draw a temporary coloured border around the screen rect
+/// `[left, top, right, bottom]`, e.g. an element's `rect`, to check what an
+/// automation step resolved.
+///
+/// `color` is `0xRRGGBB`.  Blocks (with the GIL released) for
+/// `duration_ms`, capped at 60 seconds.
+#[pyfunction]
+#[pyo3(signature = (rect, duration_ms=800, color=wmcp_core::highlight::DEFAULT_HIGHLIGHT_COLOR))]
+fn highlight_rect(py: Python<'_>, rect: [i32; 4], duration_ms: u64, color: u32) -> PyResult<()> {
+    py.allow_threads(move || wmcp_core::highlight::highlight_rect(rect, duration_ms, color))
+        .map_err(to_py_err)
+}
+
 // ---------------------------------------------------------------------------
 // UIA query functions
 // ---------------------------------------------------------------------------
@@ -323,6 +1390,8 @@ fn element_info_to_dict(
     dict.set_item("localized_control_type", &info.localized_control_type)?;
     dict.set_item("class_name", &info.class_name)?;
     dict.set_item("bounding_rect", info.bounding_rect.to_vec())?;
+    dict.set_item("center", info.center.to_vec())?;
+    dict.set_item("runtime_id", &info.runtime_id)?;
     dict.set_item("is_enabled", info.is_enabled)?;
     dict.set_item("is_offscreen", info.is_offscreen)?;
     dict.set_item("has_keyboard_focus", info.has_keyboard_focus)?;
@@ -341,6 +1410,21 @@ fn pattern_result_to_dict(
     dict.set_item("action", &r.action)?;
     dict.set_item("success", r.success)?;
     dict.set_item("detail", &r.detail)?;
+    if let Some(read_only) = r.is_read_only {
+        dict.set_item("is_read_only", read_only)?;
+    }
+    Ok(dict.into())
+}
+
+/// Convert a [`TextResult`] to a Python dict.
+fn text_result_to_dict(py: Python<'_>, r: &wmcp_core::pattern::TextResult) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("element_name", &r.element_name)?;
+    dict.set_item("element_type", &r.element_type)?;
+    dict.set_item("success", r.success)?;
+    dict.set_item("detail", &r.detail)?;
+    dict.set_item("full_text", &r.full_text)?;
+    dict.set_item("selected_text", &r.selected_text)?;
     Ok(dict.into())
 }
 
@@ -354,9 +1438,39 @@ fn element_from_point(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
     element_info_to_dict(py, &info)
 }
 
+/// List the control patterns the element at (x, y) supports, e.g.
+/// `["InvokePattern", "ScrollPattern"]`, without reading its other
+/// properties.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn get_supported_patterns_at(py: Python<'_>, x: i32, y: i32) -> PyResult<Vec<String>> {
+    py.allow_threads(move || wmcp_core::query::get_supported_patterns_at(x, y))
+        .map_err(to_py_err)
+}
+
 /// Search for UIA elements matching criteria.
+///
+/// `name` is a case-insensitive substring match; `name_regex` is a regular
+/// expression on the name, applied as well when both are given.
+/// `automation_id` and `class_name` match exactly unless `substring=True`,
+/// which makes them case-insensitive substring matches too.
+///
+/// `scope` is "children" (direct children only; fastest), "descendants"
+/// (the whole subtree; can take seconds on large windows) or "subtree"
+/// (descendants plus the root itself).
 #[pyfunction]
-#[pyo3(signature = (name=None, control_type=None, automation_id=None, window_handle=None, limit=20))]
+#[pyo3(signature = (
+    name=None,
+    control_type=None,
+    automation_id=None,
+    window_handle=None,
+    limit=20,
+    class_name=None,
+    substring=false,
+    name_regex=None,
+    scope="descendants"
+))]
+#[allow(clippy::too_many_arguments)] // One parameter per Python keyword argument
 fn find_elements(
     py: Python<'_>,
     name: Option<String>,
@@ -364,12 +1478,21 @@ fn find_elements(
     automation_id: Option<String>,
     window_handle: Option<isize>,
     limit: usize,
+    class_name: Option<String>,
+    substring: bool,
+    name_regex: Option<String>,
+    scope: &str,
 ) -> PyResult<PyObject> {
+    let scope = wmcp_core::query::FindScope::from_name(scope).map_err(to_py_err)?;
     let criteria = wmcp_core::query::FindCriteria {
         name,
+        name_regex,
         control_type,
         automation_id,
+        class_name,
+        substring,
         window_handle,
+        scope,
         limit,
     };
 
@@ -384,6 +1507,97 @@ fn find_elements(
     Ok(list.into())
 }
 
+/// Wait until an element matching the criteria appears, polling every
+/// `poll_ms` for up to `timeout_ms`.
+///
+/// Criteria match as in `find_elements`.  Returns the first matching
+/// element dict, or `None` on timeout.  The GIL is released while
+/// waiting.
+#[pyfunction]
+#[pyo3(signature = (
+    name=None,
+    control_type=None,
+    automation_id=None,
+    window_handle=None,
+    timeout_ms=5000,
+    poll_ms=200
+))]
+fn wait_for_element(
+    py: Python<'_>,
+    name: Option<String>,
+    control_type: Option<String>,
+    automation_id: Option<String>,
+    window_handle: Option<isize>,
+    timeout_ms: u64,
+    poll_ms: u64,
+) -> PyResult<Option<PyObject>> {
+    let criteria = wmcp_core::query::FindCriteria {
+        name,
+        control_type,
+        automation_id,
+        window_handle,
+        ..Default::default()
+    };
+
+    let found = py
+        .allow_threads(move || wmcp_core::query::wait_for_element(&criteria, timeout_ms, poll_ms))
+        .map_err(to_py_err)?;
+    found.map(|info| element_info_to_dict(py, &info)).transpose()
+}
+
+/// Query the element at (x, y) and its control-view relatives.
+///
+/// Returns `{"element", "parent", "first_child", "last_child",
+/// "next_sibling", "previous_sibling"}`, each an element dict like
+/// `element_from_point` returns, or `None` when that relative does not
+/// exist.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn get_element_relatives(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
+    let relatives = py
+        .allow_threads(move || wmcp_core::query::get_element_relatives(x, y))
+        .map_err(to_py_err)?;
+
+    let optional = |info: &Option<wmcp_core::query::ElementInfo>| -> PyResult<PyObject> {
+        match info {
+            Some(info) => element_info_to_dict(py, info),
+            None => Ok(py.None()),
+        }
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("element", element_info_to_dict(py, &relatives.element)?)?;
+    dict.set_item("parent", optional(&relatives.parent)?)?;
+    dict.set_item("first_child", optional(&relatives.first_child)?)?;
+    dict.set_item("last_child", optional(&relatives.last_child)?)?;
+    dict.set_item("next_sibling", optional(&relatives.next_sibling)?)?;
+    dict.set_item("previous_sibling", optional(&relatives.previous_sibling)?)?;
+    Ok(dict.into())
+}
+
+/// Find elements of a window whose bounding rect overlaps `rect`
+/// (`[left, top, right, bottom]` screen pixels), optionally only those of
+/// the given `control_types`.  Returns up to 100 element dicts.
+#[pyfunction]
+#[pyo3(signature = (window_handle, rect, control_types=None))]
+fn find_elements_in_rect(
+    py: Python<'_>,
+    window_handle: isize,
+    rect: [i32; 4],
+    control_types: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let results = py
+        .allow_threads(move || {
+            wmcp_core::query::find_elements_in_rect(window_handle, rect, control_types)
+        })
+        .map_err(to_py_err)?;
+
+    let list = PyList::empty(py);
+    for info in &results {
+        list.append(element_info_to_dict(py, info)?)?;
+    }
+    Ok(list.into())
+}
+
 /// Query primary and virtual screen dimensions.
 #[pyfunction]
 fn get_screen_metrics(py: Python<'_>) -> PyResult<PyObject> {
@@ -434,6 +1648,136 @@ fn set_value_at(py: Python<'_>, x: i32, y: i32, value: &str) -> PyResult<PyObjec
     pattern_result_to_dict(py, &result)
 }
 
+/// Focus the element at (x, y) and type `text` into it.
+///
+/// Returns a pattern result dict; `success` is False (and nothing is
+/// typed) when the element is not keyboard focusable or refuses focus.
+#[pyfunction]
+#[pyo3(signature = (x, y, text))]
+fn send_text_to_element(py: Python<'_>, x: i32, y: i32, text: &str) -> PyResult<PyObject> {
+    let text_owned = text.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::send_text_to_element(x, y, &text_owned))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Replace the text of the field at (x, y) by clicking it, pressing
+/// Ctrl+A and Delete, then typing `text`.
+///
+/// Uses input injection, not ValuePattern, so it also works on controls
+/// without that pattern.  Returns a pattern result dict.
+#[pyfunction]
+#[pyo3(signature = (x, y, text))]
+fn replace_text_at(py: Python<'_>, x: i32, y: i32, text: &str) -> PyResult<PyObject> {
+    let text_owned = text.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::replace_text_at(x, y, &text_owned))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Append text to the current value via ValuePattern on the element at (x, y).
+///
+/// Fails like `set_value_at` when the element lacks ValuePattern or is
+/// read-only.
+#[pyfunction]
+#[pyo3(signature = (x, y, text))]
+fn append_value_at(py: Python<'_>, x: i32, y: i32, text: &str) -> PyResult<PyObject> {
+    let text_owned = text.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::append_value_at(x, y, &text_owned))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Scroll the container at (x, y) to absolute percentages via ScrollPattern.
+///
+/// Each percent is 0-100, or -1 to leave that axis unchanged.  Unlike
+/// `send_scroll` (mouse wheel notches delivered to whatever is under the
+/// pointer), this targets the element directly, jumps to an exact
+/// position, and leaves the mouse where it is.
+#[pyfunction]
+#[pyo3(signature = (x, y, horizontal_percent=-1.0, vertical_percent=-1.0))]
+fn scroll_pattern_at(
+    py: Python<'_>,
+    x: i32,
+    y: i32,
+    horizontal_percent: f64,
+    vertical_percent: f64,
+) -> PyResult<PyObject> {
+    let result = py
+        .allow_threads(move || {
+            wmcp_core::pattern::scroll_pattern_at(x, y, horizontal_percent, vertical_percent)
+        })
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Read the current value via ValuePattern on the element at (x, y).
+///
+/// The value is in `detail`; `is_read_only` reports whether it can be set.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn get_value_at(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::get_value_at(x, y))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Maximize, minimize, restore or close the window containing (x, y) via
+/// UIA WindowPattern.
+///
+/// `action` is "maximize", "minimize", "normal" or "close".  Use this for
+/// UWP/WPF apps that ignore `set_window_state`; `detail` reports the
+/// window's interaction state afterwards.
+#[pyfunction]
+#[pyo3(signature = (x, y, action))]
+fn window_pattern_action_at(py: Python<'_>, x: i32, y: i32, action: &str) -> PyResult<PyObject> {
+    let action_owned = action.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::window_pattern_action_at(x, y, &action_owned))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Read document text and the current selection via TextPattern at (x, y).
+///
+/// Returns a dict with `full_text` and `selected_text`; `success` is False
+/// when the element does not support TextPattern.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn get_text_at(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::get_text_at(x, y))
+        .map_err(to_py_err)?;
+    text_result_to_dict(py, &result)
+}
+
+/// Read the cell at zero-based (row, col) of the grid or table at (x, y)
+/// via GridPattern; (x, y) may also be on a cell inside the grid.
+///
+/// Returns an element info dict like `element_from_point`.  Raises
+/// `WmcpTreeError` when there is no GridPattern or the cell is out of
+/// range.
+#[pyfunction]
+#[pyo3(signature = (x, y, row, col))]
+fn get_grid_cell_at(py: Python<'_>, x: i32, y: i32, row: i32, col: i32) -> PyResult<PyObject> {
+    let info = py
+        .allow_threads(move || wmcp_core::pattern::get_grid_cell_at(x, y, row, col))
+        .map_err(to_py_err)?;
+    element_info_to_dict(py, &info)
+}
+
+/// Return `(rows, columns)` of the grid or table at (x, y) via GridPattern.
+#[pyfunction]
+#[pyo3(signature = (x, y))]
+fn get_grid_size_at(py: Python<'_>, x: i32, y: i32) -> PyResult<(i32, i32)> {
+    py.allow_threads(move || wmcp_core::pattern::get_grid_size_at(x, y))
+        .map_err(to_py_err)
+}
+
 /// Expand via ExpandCollapsePattern on the element at (x, y).
 #[pyfunction]
 #[pyo3(signature = (x, y))]
@@ -464,6 +1808,137 @@ fn select_at(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
     pattern_result_to_dict(py, &result)
 }
 
+/// Invoke the InvokePattern on the element with `automation_id` in window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id))]
+fn invoke_by_automation_id(py: Python<'_>, hwnd: isize, automation_id: &str) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::invoke_by_automation_id(hwnd, &id))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Toggle the TogglePattern on the element with `automation_id` in window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id))]
+fn toggle_by_automation_id(py: Python<'_>, hwnd: isize, automation_id: &str) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::toggle_by_automation_id(hwnd, &id))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Set a value via ValuePattern on the element with `automation_id` in
+/// window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id, value))]
+fn set_value_by_automation_id(
+    py: Python<'_>,
+    hwnd: isize,
+    automation_id: &str,
+    value: &str,
+) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let value_owned = value.to_owned();
+    let result = py
+        .allow_threads(move || {
+            wmcp_core::pattern::set_value_by_automation_id(hwnd, &id, &value_owned)
+        })
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Expand via ExpandCollapsePattern on the element with `automation_id` in window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id))]
+fn expand_by_automation_id(py: Python<'_>, hwnd: isize, automation_id: &str) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::expand_by_automation_id(hwnd, &id))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Collapse via ExpandCollapsePattern on the element with `automation_id` in window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id))]
+fn collapse_by_automation_id(
+    py: Python<'_>,
+    hwnd: isize,
+    automation_id: &str,
+) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::collapse_by_automation_id(hwnd, &id))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+/// Select via SelectionItemPattern on the element with `automation_id` in window `hwnd`.
+#[pyfunction]
+#[pyo3(signature = (hwnd, automation_id))]
+fn select_by_automation_id(py: Python<'_>, hwnd: isize, automation_id: &str) -> PyResult<PyObject> {
+    let id = automation_id.to_owned();
+    let result = py
+        .allow_threads(move || wmcp_core::pattern::select_by_automation_id(hwnd, &id))
+        .map_err(to_py_err)?;
+    pattern_result_to_dict(py, &result)
+}
+
+// ---------------------------------------------------------------------------
+// clipboard
+// ---------------------------------------------------------------------------
+
+/// Read the clipboard as text; "" when it holds no text.
+#[pyfunction]
+fn get_clipboard_text(py: Python<'_>) -> PyResult<String> {
+    py.allow_threads(wmcp_core::clipboard::get_clipboard_text)
+        .map_err(to_py_err)
+}
+
+/// Replace the clipboard contents with `text`.
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn set_clipboard_text(py: Python<'_>, text: &str) -> PyResult<()> {
+    let text_owned = text.to_owned();
+    py.allow_threads(move || wmcp_core::clipboard::set_clipboard_text(&text_owned))
+        .map_err(to_py_err)
+}
+
+/// Place a BGRA image (e.g. from `capture_screenshot_raw`) on the clipboard.
+///
+/// `bgra_bytes` must hold `width * height * 4` bytes, top row first.  The
+/// image is pasted fully opaque, as Paint and Word expect.
+#[pyfunction]
+#[pyo3(signature = (width, height, bgra_bytes))]
+fn set_clipboard_image(py: Python<'_>, width: u32, height: u32, bgra_bytes: &[u8]) -> PyResult<()> {
+    let frame = wmcp_core::screenshot::ScreenshotData {
+        width,
+        height,
+        data: bgra_bytes.to_vec(),
+    };
+    py.allow_threads(move || wmcp_core::clipboard::set_clipboard_image(&frame))
+        .map_err(to_py_err)
+}
+
+// ---------------------------------------------------------------------------
+// Logging
+// ---------------------------------------------------------------------------
+
+/// Print the library's diagnostics (COM setup, screenshot fallbacks,
+/// skipped windows) to stderr at `level`: "off", "error", "warn", "info",
+/// "debug" or "trace".  `RUST_LOG`, if set, refines the filter.
+///
+/// Only the first successful call takes effect; later calls are no-ops.
+/// Raises `WmcpLoggingError` for an unknown level.
+#[pyfunction]
+#[pyo3(signature = (level="warn"))]
+fn init_logging(level: &str) -> PyResult<()> {
+    wmcp_core::logging::init_logging(level).map_err(to_py_err)
+}
+
 // ---------------------------------------------------------------------------
 // Module registration
 // ---------------------------------------------------------------------------
@@ -471,32 +1946,107 @@ fn select_at(py: Python<'_>, x: i32, y: i32) -> PyResult<PyObject> {
 /// Register the `windows_mcp_core` Python module.
 #[pymodule]
 fn windows_mcp_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("WmcpError", py.get_type::<WmcpError>())?;
+    m.add("WmcpSystemInfoError", py.get_type::<WmcpSystemInfoError>())?;
+    m.add("WmcpComError", py.get_type::<WmcpComError>())?;
+    m.add("WmcpTreeError", py.get_type::<WmcpTreeError>())?;
+    m.add("WmcpInputError", py.get_type::<WmcpInputError>())?;
+    m.add("WmcpScreenshotError", py.get_type::<WmcpScreenshotError>())?;
+    m.add("WmcpClipboardError", py.get_type::<WmcpClipboardError>())?;
+    m.add("WmcpLoggingError", py.get_type::<WmcpLoggingError>())?;
+
     m.add_function(wrap_pyfunction!(system_info, m)?)?;
+    m.add_function(wrap_pyfunction!(set_system_info_refresh_interval, m)?)?;
+    m.add_function(wrap_pyfunction!(cpu_usage, m)?)?;
+    m.add_function(wrap_pyfunction!(list_processes, m)?)?;
     m.add_function(wrap_pyfunction!(capture_tree, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_tree_flat, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_tree_outline, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_tree_detailed, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_tree_bfs, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_trees, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_subtree_from_point, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_foreground_tree, m)?)?;
     m.add_function(wrap_pyfunction!(send_text, m)?)?;
     m.add_function(wrap_pyfunction!(send_key, m)?)?;
+    m.add_function(wrap_pyfunction!(send_scancode, m)?)?;
+    m.add_function(wrap_pyfunction!(send_key_by_name, m)?)?;
     m.add_function(wrap_pyfunction!(send_click, m)?)?;
+    m.add_function(wrap_pyfunction!(send_click_in_window, m)?)?;
+    m.add_function(wrap_pyfunction!(send_double_click, m)?)?;
+    m.add_function(wrap_pyfunction!(send_mouse_down, m)?)?;
+    m.add_function(wrap_pyfunction!(send_mouse_up, m)?)?;
     m.add_function(wrap_pyfunction!(send_mouse_move, m)?)?;
+    m.add_function(wrap_pyfunction!(send_mouse_move_relative, m)?)?;
+    m.add_function(wrap_pyfunction!(get_cursor_pos, m)?)?;
     m.add_function(wrap_pyfunction!(send_hotkey, m)?)?;
+    m.add_function(wrap_pyfunction!(send_hotkey_str, m)?)?;
+    m.add_function(wrap_pyfunction!(send_keys, m)?)?;
     m.add_function(wrap_pyfunction!(send_scroll, m)?)?;
+    m.add_function(wrap_pyfunction!(send_scroll_lines, m)?)?;
     m.add_function(wrap_pyfunction!(send_drag, m)?)?;
+    m.add_function(wrap_pyfunction!(send_drag_from, m)?)?;
     m.add_function(wrap_pyfunction!(enumerate_windows, m)?)?;
     m.add_function(wrap_pyfunction!(get_window_info, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_z_order, m)?)?;
+    m.add_function(wrap_pyfunction!(get_window_monitor_index, m)?)?;
     m.add_function(wrap_pyfunction!(get_foreground_window, m)?)?;
     m.add_function(wrap_pyfunction!(list_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(list_all_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(find_windows, m)?)?;
+    m.add_function(wrap_pyfunction!(window_from_point, m)?)?;
+    m.add_function(wrap_pyfunction!(set_window_rect, m)?)?;
+    m.add_function(wrap_pyfunction!(set_window_topmost, m)?)?;
+    m.add_function(wrap_pyfunction!(set_window_state, m)?)?;
+    m.add_function(wrap_pyfunction!(close_window, m)?)?;
+    m.add_function(wrap_pyfunction!(click_and_wait_for_window, m)?)?;
+    m.add_function(wrap_pyfunction!(focus_window, m)?)?;
     m.add_function(wrap_pyfunction!(capture_screenshot_raw, m)?)?;
     m.add_function(wrap_pyfunction!(capture_screenshot_png, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_screenshot_jpeg, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_screenshot_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_screenshot_webp, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_region_png, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_window_png, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_all_monitors_png, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_screenshot_data_uri, m)?)?;
+    m.add_function(wrap_pyfunction!(highlight_rect, m)?)?;
     // UIA query functions
     m.add_function(wrap_pyfunction!(element_from_point, m)?)?;
+    m.add_function(wrap_pyfunction!(get_supported_patterns_at, m)?)?;
     m.add_function(wrap_pyfunction!(find_elements, m)?)?;
+    m.add_function(wrap_pyfunction!(find_elements_in_rect, m)?)?;
+    m.add_function(wrap_pyfunction!(get_element_relatives, m)?)?;
+    m.add_function(wrap_pyfunction!(wait_for_element, m)?)?;
     m.add_function(wrap_pyfunction!(get_screen_metrics, m)?)?;
     // UIA pattern functions
     m.add_function(wrap_pyfunction!(invoke_at, m)?)?;
     m.add_function(wrap_pyfunction!(toggle_at, m)?)?;
     m.add_function(wrap_pyfunction!(set_value_at, m)?)?;
+    m.add_function(wrap_pyfunction!(append_value_at, m)?)?;
+    m.add_function(wrap_pyfunction!(send_text_to_element, m)?)?;
+    m.add_function(wrap_pyfunction!(replace_text_at, m)?)?;
+    m.add_function(wrap_pyfunction!(get_value_at, m)?)?;
     m.add_function(wrap_pyfunction!(expand_at, m)?)?;
     m.add_function(wrap_pyfunction!(collapse_at, m)?)?;
     m.add_function(wrap_pyfunction!(select_at, m)?)?;
+    m.add_function(wrap_pyfunction!(scroll_pattern_at, m)?)?;
+    m.add_function(wrap_pyfunction!(get_text_at, m)?)?;
+    m.add_function(wrap_pyfunction!(get_grid_cell_at, m)?)?;
+    m.add_function(wrap_pyfunction!(get_grid_size_at, m)?)?;
+    m.add_function(wrap_pyfunction!(window_pattern_action_at, m)?)?;
+    m.add_function(wrap_pyfunction!(invoke_by_automation_id, m)?)?;
+    m.add_function(wrap_pyfunction!(toggle_by_automation_id, m)?)?;
+    m.add_function(wrap_pyfunction!(set_value_by_automation_id, m)?)?;
+    m.add_function(wrap_pyfunction!(expand_by_automation_id, m)?)?;
+    m.add_function(wrap_pyfunction!(collapse_by_automation_id, m)?)?;
+    m.add_function(wrap_pyfunction!(select_by_automation_id, m)?)?;
+    // Clipboard functions
+    m.add_function(wrap_pyfunction!(get_clipboard_text, m)?)?;
+    m.add_function(wrap_pyfunction!(set_clipboard_text, m)?)?;
+    m.add_function(wrap_pyfunction!(set_clipboard_image, m)?)?;
+    m.add_function(wrap_pyfunction!(init_logging, m)?)?;
 
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add("__doc__", "Native Rust acceleration layer for Windows-MCP.")?;
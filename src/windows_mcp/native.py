@@ -176,7 +176,7 @@ def native_send_drag(to_x: int, to_y: int, steps: int = 10) -> int | None:
 
     Args:
         to_x, to_y: Destination screen coordinates.
-        steps: Reserved for future interpolation.
+        steps: Number of interpolated moves between origin and destination (1-500).
 
     Returns the number of events injected, or None if unavailable.
     """